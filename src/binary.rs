@@ -0,0 +1,144 @@
+//! Compact binary encoding of boards, for storing many of them at once.
+//!
+//! A board of dimensions `width` x `height` is encoded as:
+//!
+//! * two bytes holding `width` and `height`,
+//! * the value of every cell in row-major order, packed into as few bits as
+//!   hold `width * height + 1`, with the lowest bits first and the last byte
+//!   padded with zeros, where a blocked cell holds `width * height + 1`, and
+//! * two bytes holding the x and y location of the current position.
+//!
+//! A 10x10 board takes 92 bytes. The clues, move rule and wrap mode of a
+//! board, and the moves that can be redone, are not encoded, so only boards
+//! that follow the default rule without wrapping can be decoded. The history
+//! is not needed, since the moves of the path can be read from the values.
+
+use crate::board::{Board, BoardError};
+
+impl Board {
+    /// Encode the board in the compact binary format described in the
+    /// [module documentation](self).
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let blocked = (self.width() * self.height() + 1) as u32;
+        let bits_per_value = value_bits(blocked as usize);
+        let mut bytes = vec![self.width() as u8, self.height() as u8];
+        let mut bits = 0u32;
+        let mut count = 0;
+        for y in 0..self.height() {
+            for x in 0..self.width() {
+                let value = if self.blocked_at(x, y) {
+                    blocked
+                } else {
+                    self.value_at(x, y) as u32
+                };
+                bits |= value << count;
+                count += bits_per_value;
+                while count >= 8 {
+                    bytes.push(bits as u8);
+                    bits >>= 8;
+                    count -= 8;
+                }
+            }
+        }
+        if count > 0 {
+            bytes.push(bits as u8);
+        }
+        let (x, y) = self.current_position();
+        bytes.push(x as u8);
+        bytes.push(y as u8);
+        bytes
+    }
+
+    /// Decode a board from bytes written by [`Board::to_bytes`]. Returns an
+    /// error if the length does not match the size, or if the values and
+    /// position do not describe a legal game.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Board, BoardError> {
+        let invalid = |reason: &str| BoardError::InvalidGrid {
+            reason: reason.to_string(),
+        };
+        if bytes.len() < 2 {
+            return Err(invalid("no size"));
+        }
+        let (width, height) = (bytes[0] as usize, bytes[1] as usize);
+        let cells = width * height;
+        let bits_per_value = value_bits(cells + 1);
+        let packed = (cells * bits_per_value as usize).div_ceil(8);
+        if bytes.len() != 2 + packed + 2 {
+            return Err(invalid("length does not match the size"));
+        }
+        let mut board = Board::try_with_dimensions(width, height)?;
+        let mut values = Vec::with_capacity(cells);
+        let mut bits = 0u32;
+        let mut count = 0;
+        let mut input = bytes[2..2 + packed].iter();
+        for i in 0..cells {
+            while count < bits_per_value {
+                bits |= (*input.next().unwrap() as u32) << count;
+                count += 8;
+            }
+            match (bits & ((1 << bits_per_value) - 1)) as usize {
+                value if value == cells + 1 => {
+                    board = board.block(i % width, i / width)?;
+                    values.push(0);
+                }
+                value => values.push(value as u16),
+            }
+            bits >>= bits_per_value;
+            count -= bits_per_value;
+        }
+        let board = board.replay_values(&values)?;
+        let position = (bytes[packed + 2] as usize, bytes[packed + 3] as usize);
+        if board.is_started() && board.current_position() != position {
+            return Err(invalid("position does not match the values"));
+        }
+        Ok(board)
+    }
+}
+
+/// Return the number of bits needed to hold every value from 0 to `cells`.
+fn value_bits(cells: usize) -> u32 {
+    usize::BITS - cells.leading_zeros()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    // Boards survive a round trip through bytes, in the documented length.
+    fn bytes_round_trip() {
+        let mut board = Board::new(10).start_at(4, 4).unwrap();
+        let empty = Board::new(10);
+        assert_eq!(Board::from_bytes(&empty.to_bytes()).unwrap().score(), 0);
+        for &dir in &[Direction::Down, Direction::Right, Direction::UpRight] {
+            board = board.next_move(dir).unwrap();
+        }
+        let bytes = board.to_bytes();
+        assert_eq!(bytes.len(), 92);
+        let read = Board::from_bytes(&bytes).unwrap();
+        assert_eq!(read.to_string(), board.to_string());
+        assert_eq!(read.history(), board.history());
+        let board = Board::new(5).start_at(2, 2).unwrap();
+        assert_eq!(board.to_bytes().len(), 2 + 16 + 2);
+        assert_eq!(
+            Board::from_bytes(&board.to_bytes()).unwrap().to_string(),
+            board.to_string()
+        );
+        let board = Board::with_dimensions(8, 12).start_at(7, 0).unwrap();
+        assert_eq!(Board::from_bytes(&board.to_bytes()).unwrap(), board);
+        let board = Board::new(5).block(4, 0).unwrap().start_at(2, 2).unwrap();
+        assert_eq!(Board::from_bytes(&board.to_bytes()).unwrap(), board);
+    }
+
+    #[test]
+    // Truncated bytes and a position off the path are rejected.
+    fn bytes_invalid() {
+        let board = Board::new(5).start_at(2, 2).unwrap();
+        let mut bytes = board.to_bytes();
+        assert!(Board::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(Board::from_bytes(&[]).is_err());
+        *bytes.last_mut().unwrap() = 0;
+        assert!(Board::from_bytes(&bytes).is_err());
+    }
+}
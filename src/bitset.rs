@@ -0,0 +1,74 @@
+//! Sets of cells stored one bit per cell.
+//!
+//! Boards of every shape number their cells from 0, so a set of cells, such
+//! as the cells that have been filled, is a set of small integers whatever
+//! the shape of the board. [`BitSet`] keeps one as a bit per cell, so that a
+//! cell is tested with a shift and a mask, and two sets are compared or
+//! hashed a word at a time.
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// BitSet is a set of the cells of a board with a fixed number of cells.
+pub(crate) struct BitSet {
+    words: Box<[u64]>,
+}
+
+impl BitSet {
+    /// Return an empty set of cells numbered below `cells`.
+    pub(crate) fn new(cells: usize) -> Self {
+        BitSet {
+            words: vec![0; cells.div_ceil(64)].into_boxed_slice(),
+        }
+    }
+
+    #[inline]
+    /// Return `true` if `cell` is in the set.
+    pub(crate) fn contains(&self, cell: usize) -> bool {
+        self.words[cell / 64] & (1 << (cell % 64)) != 0
+    }
+
+    #[inline]
+    /// Add `cell` to the set.
+    pub(crate) fn insert(&mut self, cell: usize) {
+        self.words[cell / 64] |= 1 << (cell % 64);
+    }
+
+    #[inline]
+    /// Take `cell` out of the set.
+    pub(crate) fn remove(&mut self, cell: usize) {
+        self.words[cell / 64] &= !(1 << (cell % 64));
+    }
+
+    /// Empty the set, keeping its size.
+    pub(crate) fn clear(&mut self) {
+        self.words.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Return the number of cells in the set.
+    pub(crate) fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Cells are added and taken out across word boundaries.
+    fn bitset() {
+        let mut set = BitSet::new(130);
+        for cell in [0, 63, 64, 129] {
+            set.insert(cell);
+        }
+        assert!(set.contains(63) && set.contains(64) && !set.contains(65));
+        assert_eq!(set.len(), 4);
+        set.remove(64);
+        assert!(!set.contains(64));
+        assert!(set.contains(0) && set.contains(129));
+        set.clear();
+        assert_eq!(set, BitSet::new(130));
+    }
+}
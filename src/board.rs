@@ -25,49 +25,228 @@ top-left corner
 
 //#[deny(missing_docs)]
 
-use failure::Fail;
+use crate::bitset::BitSet;
+use crate::pos::Pos;
+use crate::symmetry::Symmetry;
 use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Index;
 use std::slice::Iter;
+use std::str::FromStr;
+use std::sync::Arc;
 
-#[derive(Debug, Fail, PartialEq)]
-/// Custom Error for invalid board actions.
+#[derive(Debug, PartialEq)]
+/// Custom Error for invalid board actions. Each kind of failure is its own
+/// variant, carrying the cells and values involved, so that callers can
+/// match on it rather than read its message.
 pub enum BoardError {
-    #[fail(display = "Board not started.")]
     NotStarted, // Board not started
-    #[fail(
-        display = "Moving in direction '{}' from [{},{}] is invalid",
-        dir, x, y
-    )]
-    BadDirection { dir: Direction, x: usize, y: usize }, // Moving in direction '{}' is invalid
-    #[fail(display = "Cannot set cell [{},{}]: out of range {}", x, y, max)]
-    IndexOutOfRange { x: usize, y: usize, max: usize }, // cannot set cell [{}, {}], out of range ({})
-    #[fail(display = "Cannot clear cell [{}, {}]", x, y)]
-    CannotClear { x: usize, y: usize }, // cannot clear cell [{}, {}]
-    #[fail(
-        display = "cannot set cell [{}, {}] = {}: value already used",
-        x, y, value
-    )]
-    ValueUsed { x: usize, y: usize, value: u8 }, // cannot set cell [{}, {}] = {}, value already used
-    #[fail(
-        display = "Cannot set cell [{},{}] = {}: value larger than {}",
-        x, y, value, max
-    )]
+    BadDirection {
+        dir: Direction,
+        x: usize,
+        y: usize,
+    }, // Moving in direction '{}' is invalid
+    IndexOutOfRange {
+        x: usize,
+        y: usize,
+        max: usize,
+    }, // cannot set cell [{}, {}], out of range ({})
+    CannotClear {
+        x: usize,
+        y: usize,
+    }, // cannot clear cell [{}, {}]
+    ValueUsed {
+        x: usize,
+        y: usize,
+        value: u16,
+    }, // cannot set cell [{}, {}] = {}, value already used
     ValueOutOfRange {
         x: usize,
         y: usize,
-        value: u8,
+        value: u16,
         max: usize,
     }, // cannot set cell [{}, {}] = {}, larger than {}
-    #[fail(display = "Cell [{},{}] is not empty", x, y)]
-    NotEmpty { x: usize, y: usize }, // cell [{}, {}] is not empty
+    NotEmpty {
+        x: usize,
+        y: usize,
+    }, // cell [{}, {}] is not empty
+    BadAssignment {
+        value: usize,
+    }, // assignment does not place value {} one jump from the last
+    ClueConflict {
+        x: usize,
+        y: usize,
+        value: u16,
+    }, // cell [{}, {}] cannot hold {}, conflicts with a clue
+    NothingToRedo, // no undone move to redo
+    InvalidGrid {
+        reason: String,
+    }, // cannot read board: {}
+    BadSize {
+        width: usize,
+        height: usize,
+    }, // board size {}x{} is not supported
+    AlreadyStarted, // board already started
+    BadCell {
+        cell: usize,
+    }, // cell {} is not on the board
+    BadMove {
+        dir: usize,
+        cell: usize,
+    }, // move {} from cell {} is invalid
+    BadNotation {
+        word: String,
+    }, // '{}' is not a move
+    InContext {
+        error: Box<BoardError>,
+        context: Box<ErrorContext>,
+    }, // {}, with the state of the board when it happened
+}
+
+impl fmt::Display for BoardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BoardError::NotStarted => write!(f, "Board not started."),
+            BoardError::BadDirection { dir, x, y } => {
+                write!(
+                    f,
+                    "Moving in direction '{}' from [{},{}] is invalid",
+                    dir, x, y
+                )
+            }
+            BoardError::IndexOutOfRange { x, y, max } => {
+                write!(f, "Cannot set cell [{},{}]: out of range {}", x, y, max)
+            }
+            BoardError::CannotClear { x, y } => write!(f, "Cannot clear cell [{}, {}]", x, y),
+            BoardError::ValueUsed { x, y, value } => write!(
+                f,
+                "cannot set cell [{}, {}] = {}: value already used",
+                x, y, value
+            ),
+            BoardError::ValueOutOfRange { x, y, value, max } => write!(
+                f,
+                "Cannot set cell [{},{}] = {}: value larger than {}",
+                x, y, value, max
+            ),
+            BoardError::NotEmpty { x, y } => write!(f, "Cell [{},{}] is not empty", x, y),
+            BoardError::BadAssignment { value } => write!(
+                f,
+                "Assignment does not place value {} one jump from the last",
+                value
+            ),
+            BoardError::ClueConflict { x, y, value } => write!(
+                f,
+                "Cell [{},{}] cannot hold {}: conflicts with a clue",
+                x, y, value
+            ),
+            BoardError::NothingToRedo => write!(f, "No undone move to redo"),
+            BoardError::InvalidGrid { reason } => write!(f, "Cannot read board: {}", reason),
+            BoardError::BadSize { width, height } => {
+                write!(f, "Board size {}x{} is not supported", width, height)
+            }
+            BoardError::AlreadyStarted => write!(f, "Board already started"),
+            BoardError::BadCell { cell } => write!(f, "Cell {} is not on the board", cell),
+            BoardError::BadMove { dir, cell } => {
+                write!(f, "Move {} from cell {} is invalid", dir, cell)
+            }
+            BoardError::BadNotation { word } => write!(f, "'{}' is not a move", word),
+            BoardError::InContext { error, context } => write!(f, "{} ({})", error, context),
+        }
+    }
+}
+
+impl std::error::Error for BoardError {}
+
+impl BoardError {
+    /// Return the error with `context` attached, replacing any context it
+    /// already carries.
+    pub fn with_context(self, context: ErrorContext) -> BoardError {
+        BoardError::InContext {
+            error: Box::new(self.into_kind()),
+            context: Box::new(context),
+        }
+    }
+
+    /// Return the state of the board when the error happened, if it was
+    /// attached.
+    pub fn context(&self) -> Option<&ErrorContext> {
+        match self {
+            BoardError::InContext { context, .. } => Some(context),
+            _ => None,
+        }
+    }
+
+    /// Return the error without any context attached, to match on its kind.
+    pub fn kind(&self) -> &BoardError {
+        match self {
+            BoardError::InContext { error, .. } => error,
+            error => error,
+        }
+    }
+
+    /// Return the error without any context attached, by value.
+    pub fn into_kind(self) -> BoardError {
+        match self {
+            BoardError::InContext { error, .. } => *error,
+            error => error,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// ErrorContext is a snapshot of a board taken when an operation on it
+/// failed: where the path stood, how far it had got, and the move that was
+/// being tried, if any. `step` is the index of that move in the moves being
+/// replayed.
+pub struct ErrorContext {
+    pub position: Option<(usize, usize)>,
+    pub score: usize,
+    pub attempted: Option<Direction>,
+    pub step: Option<usize>,
+}
+
+impl ErrorContext {
+    /// Return a snapshot of `board`, with no move attempted.
+    pub fn new(board: &Board) -> Self {
+        ErrorContext {
+            position: Some(board.current_position()).filter(|_| board.is_started()),
+            score: board.score(),
+            attempted: None,
+            step: None,
+        }
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(step) = self.step {
+            write!(f, "move {}, ", step + 1)?;
+        }
+        match self.position {
+            Some((x, y)) => write!(f, "score {} at [{},{}]", self.score, x, y)?,
+            None => write!(f, "board not started")?,
+        }
+        if let Some(dir) = self.attempted {
+            write!(f, ", trying {}", dir)?;
+        }
+        Ok(())
+    }
 }
 
+/// The fewest cells a board can have along each side.
+pub const MIN_SIZE: usize = 5;
+
+/// The most cells a board can have along each side. Values are stored as
+/// `u16`, and the largest board has fewer than `u16::MAX` cells.
+pub const MAX_SIZE: usize = 255;
+
 /// Distance from source for horizontal or vertical moves.
 pub const HV_OFFSET: i32 = 3;
 
 /// Distance from source for diagnal moves (both horizontal and vertical).
 pub const DIAG_OFFSET: i32 = 2;
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Debug, Copy, Clone, PartialEq)]
 /// Direction represents the direction of a move from the source location.
 pub enum Direction {
@@ -93,10 +272,99 @@ impl Direction {
             Direction::Left,
             Direction::DownLeft,
         ];
-        DIRECTIONS.into_iter()
+        DIRECTIONS.iter()
+    }
+
+    /// Return the short name of the direction: `D`, `DR`, `R`, `UR`, `U`,
+    /// `UL`, `L` or `DL`.
+    pub fn abbreviation(self) -> &'static str {
+        match self {
+            Direction::Down => "D",
+            Direction::DownRight => "DR",
+            Direction::Right => "R",
+            Direction::UpRight => "UR",
+            Direction::Up => "U",
+            Direction::UpLeft => "UL",
+            Direction::Left => "L",
+            Direction::DownLeft => "DL",
+        }
+    }
+
+    /// Return the direction as a point of the compass, with up as north:
+    /// `S`, `SE`, `E`, `NE`, `N`, `NW`, `W` or `SW`.
+    pub fn compass(self) -> &'static str {
+        match self {
+            Direction::Down => "S",
+            Direction::DownRight => "SE",
+            Direction::Right => "E",
+            Direction::UpRight => "NE",
+            Direction::Up => "N",
+            Direction::UpLeft => "NW",
+            Direction::Left => "W",
+            Direction::DownLeft => "SW",
+        }
+    }
+
+    /// Return an arrow pointing in the direction: `↓`, `↘`, `→`, `↗`, `↑`,
+    /// `↖`, `←` or `↙`.
+    pub fn arrow(self) -> char {
+        match self {
+            Direction::Down => '↓',
+            Direction::DownRight => '↘',
+            Direction::Right => '→',
+            Direction::UpRight => '↗',
+            Direction::Up => '↑',
+            Direction::UpLeft => '↖',
+            Direction::Left => '←',
+            Direction::DownLeft => '↙',
+        }
+    }
+}
+
+/// Read a direction from its name as printed by `Debug`, such as
+/// `DownRight`, its abbreviation, such as `DR`, or its point of the
+/// compass, such as `SE`, in any case.
+impl FromStr for Direction {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Direction::iterator()
+            .find(|dir| {
+                [
+                    format!("{:?}", dir).as_str(),
+                    dir.abbreviation(),
+                    dir.compass(),
+                ]
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(s))
+            })
+            .copied()
+            .ok_or_else(|| BoardError::BadNotation {
+                word: s.to_string(),
+            })
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Move is a jump from the current position: its direction, the (x, y)
+/// location of the cell it lands on, and the value it places there.
+pub struct Move {
+    pub dir: Direction,
+    pub to: Pos,
+    pub value: u16,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// CellChange is a cell whose value differs between two boards: its (x, y)
+/// location, its value before and its value after, with 0 for empty.
+pub struct CellChange {
+    pub at: Pos,
+    pub from: u16,
+    pub to: u16,
+}
+
 impl fmt::Display for Direction {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let name = match self {
@@ -113,287 +381,2674 @@ impl fmt::Display for Direction {
     }
 }
 
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// MoveRule decides where a move in each direction lands. The default is
+/// the rule of the original game.
+pub enum MoveRule {
+    /// Jump `straight` cells horizontally or vertically, or `diagonal` cells
+    /// along both axes at once. A distance of 0 rules out those moves.
+    Jump { straight: u8, diagonal: u8 },
+    /// Move like a chess knight, two cells along one axis and one along the
+    /// other, so that a full path is a knight's tour. The directions only
+    /// label the eight moves: `Down` is the move one cell right and two
+    /// down, and the others follow in the order of `Direction::iterator()`,
+    /// turning the same way as the directions they are named after.
+    Knight,
+}
+
+impl MoveRule {
+    /// Return the change (dx, dy) in location made by a move in direction
+    /// `dir`, or `None` if the rule has no move in that direction.
+    pub fn offset(self, dir: Direction) -> Option<(i32, i32)> {
+        match self {
+            MoveRule::Jump { straight, diagonal } => {
+                let (s, d) = (straight as i32, diagonal as i32);
+                let (dx, dy) = match dir {
+                    Direction::Down => (0, s),
+                    Direction::DownRight => (d, d),
+                    Direction::Right => (s, 0),
+                    Direction::UpRight => (d, -d),
+                    Direction::Up => (0, -s),
+                    Direction::UpLeft => (-d, -d),
+                    Direction::Left => (-s, 0),
+                    Direction::DownLeft => (-d, d),
+                };
+                Some((dx, dy)).filter(|&offset| offset != (0, 0))
+            }
+            MoveRule::Knight => Some(match dir {
+                Direction::Down => (1, 2),
+                Direction::DownRight => (2, 1),
+                Direction::Right => (2, -1),
+                Direction::UpRight => (1, -2),
+                Direction::Up => (-1, -2),
+                Direction::UpLeft => (-2, -1),
+                Direction::Left => (-2, 1),
+                Direction::DownLeft => (-1, 2),
+            }),
+        }
+    }
+}
+
+impl Default for MoveRule {
+    fn default() -> Self {
+        MoveRule::Jump {
+            straight: HV_OFFSET as u8,
+            diagonal: DIAG_OFFSET as u8,
+        }
+    }
+}
+
+impl fmt::Display for MoveRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveRule::Jump { straight, diagonal } => {
+                write!(f, "straight {}, diagonal {}", straight, diagonal)
+            }
+            MoveRule::Knight => write!(f, "knight"),
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(try_from = "BoardRecord", into = "BoardRecord")
+)]
 #[derive(Debug, Clone)]
-/// Board represents the puzzle board. It is a square grid of
-/// values 0-(size x size), where size is the vertical/horizontal
-/// dimensions of the board. O represents an empty cell.
+/// Board represents the puzzle board. It is a grid of values
+/// 0-(width x height), usually square, where 0 represents an empty cell.
+/// Some cells may be blocked, in which case the path never visits them and
+/// the values run up to the number of cells that are left.
 pub struct Board {
-    /// The number of vertical/horizontal cells in te board.
-    size: usize,
-    /// The total number of cells in the board (size x size).
+    /// The number of cells in each row of the board.
+    width: usize,
+    /// The number of cells in each column of the board.
+    height: usize,
+    /// The number of cells the path must visit: width x height, less the
+    /// blocked cells.
     cells: usize,
     /// The values of the cell in the board.
-    values: Vec<u8>,
+    values: Vec<u16>,
+    /// The filled cells as a bit set, indexed like `values`, so that a cell
+    /// can be tested, and the filled cells compared, a word at a time.
+    occupied: BitSet,
+    /// The index into `values` of the cell holding each value on the path,
+    /// with the cell holding `v` at index `v - 1`.
+    path: Vec<u16>,
+    /// The number of empty cells one jump away from each cell in the board.
+    degrees: Vec<u8>,
+    /// The fixed values placed before the game started, indexed like
+    /// `values`, or empty if the board has no clues. A clue cell stays empty
+    /// until the move that places its value lands on it.
+    clues: Vec<u16>,
+    /// Whether each cell is blocked, indexed like `values`, or empty if no
+    /// cell is. A blocked cell is treated as if it were not on the board.
+    blocked: Vec<bool>,
+    /// Where a move in each direction lands.
+    rule: MoveRule,
+    /// Whether jumps off one edge come back on at the opposite edge.
+    wrap: bool,
+    /// The index into `values` of the cell a jump in each direction lands
+    /// on from each cell, in the order of `Direction::iterator()`, or `None`
+    /// where `target()` refuses the jump. It is worked out once from the
+    /// rule, wrap mode and blocked cells, and shared by every copy of the
+    /// board.
+    neighbors: Arc<[[Option<u16>; 8]]>,
     /// The x location of the last cell set in the board.
     x: usize,
     /// The y location of the last cell set in the board.
     y: usize,
+    /// The moves made since the board was started, in order.
+    history: Vec<Direction>,
+    /// The moves taken back by `undo()`, most recently undone last.
+    undone: Vec<Direction>,
+    /// The Zobrist hash of the filled cells and the current position.
+    zobrist: u64,
 }
 
 impl Board {
     /// Create a new board with the dimensions `size` x `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not between `MIN_SIZE` and `MAX_SIZE`.
     pub fn new(size: usize) -> Self {
-        let mut size = size;
-        if size < 5 {
-            size = 5;
-        }
-        if size > 16 {
-            size = 16;
+        Board::with_dimensions(size, size)
+    }
+
+    /// Create a new board `width` cells wide and `height` cells high, such as
+    /// an 8x12 board. Cells are indexed in row-major order, as on a square
+    /// board.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either dimension is not between `MIN_SIZE` and `MAX_SIZE`.
+    pub fn with_dimensions(width: usize, height: usize) -> Self {
+        Board::try_with_dimensions(width, height).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Create a new board `width` cells wide and `height` cells high, or
+    /// return an error if either dimension is not between `MIN_SIZE` and
+    /// `MAX_SIZE`.
+    pub fn try_with_dimensions(width: usize, height: usize) -> Result<Board, BoardError> {
+        let range = MIN_SIZE..=MAX_SIZE;
+        if !range.contains(&width) || !range.contains(&height) {
+            return Err(BoardError::BadSize { width, height });
         }
 
-        Board {
-            size,
-            cells: size * size,
-            values: vec![0; size * size],
+        let mut board = Board {
+            width,
+            height,
+            cells: width * height,
+            values: vec![0; width * height],
+            occupied: BitSet::new(width * height),
+            path: Vec::new(),
+            degrees: vec![0; width * height],
+            clues: Vec::new(),
+            blocked: Vec::new(),
+            rule: MoveRule::default(),
+            wrap: false,
+            neighbors: Arc::new([]),
             x: 0,
             y: 0,
+            history: Vec::new(),
+            undone: Vec::new(),
+            zobrist: 0,
+        };
+        board.count_neighbors();
+        Ok(board)
+    }
+
+    /// Work out where each jump lands from each cell, and count the cells
+    /// one jump away from each cell of an empty board.
+    fn count_neighbors(&mut self) {
+        self.neighbors = (0..self.values.len())
+            .map(|i| {
+                let (x, y) = Pos::from_index(i, self.width).into();
+                let mut targets = [None; 8];
+                for (target, &dir) in targets.iter_mut().zip(Direction::iterator()) {
+                    *target = self
+                        .jump(x, y, dir)
+                        .map(|(x, y)| (y * self.width + x) as u16);
+                }
+                targets
+            })
+            .collect();
+        for i in 0..self.values.len() {
+            self.degrees[i] = self.neighbors[i].iter().flatten().count() as u8;
         }
     }
 
-    #[inline]
-    /// Return `true` if the board has been started; otherwise `false`.
-    pub fn is_started(&self) -> bool {
-        self.value_at(self.x, self.y) > 0
+    /// Return a copy of the board whose moves follow `rule`, keeping its
+    /// blocked cells and clues. Returns an error if the board has been
+    /// started, or if a clue cannot be reached from the clues around it
+    /// under the new rule.
+    pub fn with_rule(&self, rule: MoveRule) -> Result<Board, BoardError> {
+        self.with_moves(rule, self.wrap)
     }
 
-    /// Return a list of all possible moves from the current location.
-    /// Returns an empty list if there are no moves, or the board is empty.
-    pub fn possible_moves(&self) -> Vec<&'static Direction> {
-        Direction::iterator()
-            .filter(|&x| self.valid_move(*x).is_some())
-            .collect()
+    /// Return a copy of the board on which jumps off one edge come back on
+    /// at the opposite edge if `wrap` is `true`, as if the board were drawn
+    /// on a torus, keeping its blocked cells and clues. Returns an error
+    /// like `with_rule()`.
+    pub fn with_wrap(&self, wrap: bool) -> Result<Board, BoardError> {
+        self.with_moves(self.rule, wrap)
     }
 
-    /// Determines if a move in the given direction is valid. A move is valid
-    /// if the resulting position is valid, and if the the resulting position
-    /// is an empty cell. If the move is valid, it returns `Some((x, y))`
-    /// where (x, y) is the cell location resulting from the move. Otherwise,
-    /// it returns `None`.
-    fn valid_move(&self, dir: Direction) -> Option<(usize, usize)> {
-        let x: i32 = self.x as i32;
-        let y: i32 = self.y as i32;
-        let size: i32 = self.size as i32;
+    /// Return a copy of the unstarted board with its move rule and wrap mode
+    /// replaced, and its degrees and clues checked again.
+    fn with_moves(&self, rule: MoveRule, wrap: bool) -> Result<Board, BoardError> {
         if self.is_started() {
-            let (x, y) = match dir {
-                Direction::Down => (x, y + HV_OFFSET),
-                Direction::DownRight => (x + DIAG_OFFSET, y + DIAG_OFFSET),
-                Direction::Right => (x + HV_OFFSET, y),
-                Direction::UpRight => (x + DIAG_OFFSET, y - DIAG_OFFSET),
-                Direction::Up => (x, y - HV_OFFSET),
-                Direction::UpLeft => (x - DIAG_OFFSET, y - DIAG_OFFSET),
-                Direction::Left => (x - HV_OFFSET, y),
-                Direction::DownLeft => (x - DIAG_OFFSET, y + DIAG_OFFSET),
-            };
-            if x >= 0
-                && y >= 0
-                && x < size
-                && y < size
-                && self.value_at(x as usize, y as usize) == 0 as u8
-            {
-                return Some((x as usize, y as usize));
-            }
+            return Err(BoardError::AlreadyStarted);
         }
-        None
+        let mut board = Board::try_with_dimensions(self.width, self.height)?;
+        board.rule = rule;
+        board.wrap = wrap;
+        board.count_neighbors();
+        self.blocked()
+            .iter()
+            .try_fold(board, |board, &(x, y)| board.block(x, y))?
+            .add_clues(&self.clues())
     }
 
-    /// Return true if the board is complete. A board is complete if the value
-    /// of the last move equals the maximum number of cells, and there are no
-    /// empty cells in the board.
     #[inline]
-    pub fn is_won(&self) -> bool {
-        static ZERO: u8 = 0 as u8;
-        self.value_at(self.x, self.y) == self.cells as u8 && !self.values.contains(&ZERO)
+    /// Return the rule that decides where moves land.
+    pub fn rule(&self) -> MoveRule {
+        self.rule
     }
 
-    /// Return `true` if there are no possible moves for the current board.
     #[inline]
-    pub fn is_blocked(&self) -> bool {
-        self.is_started() && self.possible_moves().len() == 0
+    /// Return `true` if jumps off one edge of the board come back on at the
+    /// opposite edge.
+    pub fn wraps(&self) -> bool {
+        self.wrap
     }
 
-    #[inline]
-    /// The score is simply the highest value on the board.
-    pub fn score(&self) -> usize {
-        self.values.iter().cloned().fold(0, u8::max) as usize
+    /// Create a new board with the dimensions `size` x `size` and fixed clue
+    /// values, given as (x, y, value). The path must pass through each clue
+    /// cell with exactly the clue's value, and no other cell may take a
+    /// value that is a clue. Returns an error if a clue is off the board or
+    /// out of range, if two clues share a cell or a value, or if two clues
+    /// are too many jumps apart for the values between them to join them.
+    pub fn with_clues(size: usize, clues: &[(usize, usize, u16)]) -> Result<Board, BoardError> {
+        Board::new(size).add_clues(clues)
     }
 
-    #[inline]
-    /// Return the value at the given location on the board.
-    pub fn value_at(&self, x: usize, y: usize) -> u8 {
-        self.values[y * self.size + x]
+    /// Return a copy of the board with each of `clues`, given as (x, y,
+    /// value), added in turn by `add_clue()`.
+    pub(crate) fn add_clues(&self, clues: &[(usize, usize, u16)]) -> Result<Board, BoardError> {
+        clues
+            .iter()
+            .try_fold(self.clone(), |board, &(x, y, value)| {
+                board.add_clue(x, y, value)
+            })
     }
 
-    /// Start the puzzle by placing a 1 in the given location.
-    pub fn start_at(&mut self, x: usize, y: usize) -> Result<Board, BoardError> {
-        self.set_value(x, y, 1)
+    /// Return a copy of the board with a clue of `value` added at (x, y).
+    /// The cell keeps the clue for the rest of the game: only the path
+    /// arriving with `value` can fill it, and undoing moves does not clear
+    /// it. Returns an error if the cell is off the board, filled, blocked or
+    /// already a clue, if `value` is out of range or already placed, or if
+    /// the nearest value below or above `value` that is known, as a clue or
+    /// the current position, is more jumps away than the values between
+    /// them can make.
+    pub fn add_clue(&self, x: usize, y: usize, value: u16) -> Result<Board, BoardError> {
+        self.check_bounds(x, y)?;
+        if value < 1 || value as usize > self.cells {
+            return Err(BoardError::ValueOutOfRange {
+                x,
+                y,
+                value,
+                max: self.cells,
+            });
+        }
+        if value as usize <= self.score() || self.clue_position(value).is_some() {
+            return Err(BoardError::ValueUsed { x, y, value });
+        }
+        if self.value_at(x, y) != 0 || self.clue_at(x, y) != 0 || self.blocked_at(x, y) {
+            return Err(BoardError::NotEmpty { x, y });
+        }
+        let mut board = self.clone();
+        if board.clues.is_empty() {
+            board.clues = vec![0; board.values.len()];
+        }
+        board.clues[y * board.width + x] = value;
+        let mut known: Vec<(u16, (usize, usize))> = board
+            .clues()
+            .iter()
+            .map(|&(x, y, value)| (value, (x, y)))
+            .collect();
+        if board.is_started() {
+            known.push((board.value_at(board.x, board.y), (board.x, board.y)));
+        }
+        known.sort_unstable();
+        let i = known.iter().position(|&(v, _)| v == value).unwrap();
+        for pair in known[i.saturating_sub(1)..known.len().min(i + 2)].windows(2) {
+            let ((low, from), (high, to)) = (pair[0], pair[1]);
+            if board
+                .jumps_between(from, to)
+                .is_none_or(|jumps| jumps > (high - low) as usize)
+            {
+                return Err(BoardError::BadAssignment {
+                    value: high as usize,
+                });
+            }
+        }
+        Ok(board)
     }
 
-    /// Make the next move on the board using a given direction.
-    pub fn next_move(&mut self, dir: Direction) -> Result<Board, BoardError> {
-        if !self.is_started() {
-            return Err(BoardError::NotStarted);
-        }
-        match self.valid_move(dir) {
-            Some((x, y)) => {
-                let val = self.value_at(self.x, self.y);
-                self.set_value(x, y, val + 1)
+    /// Return the fewest jumps from `from` to `to` through cells that are
+    /// not blocked, or `None` if `to` cannot be reached. Filled cells are not
+    /// avoided, so this is a lower bound on the moves the path needs.
+    fn jumps_between(&self, from: (usize, usize), to: (usize, usize)) -> Option<usize> {
+        let mut seen = vec![false; self.values.len()];
+        seen[from.1 * self.width + from.0] = true;
+        let mut frontier = vec![from];
+        let mut jumps = 0;
+        while !frontier.is_empty() {
+            if frontier.contains(&to) {
+                return Some(jumps);
             }
-            None => Err(BoardError::BadDirection {
-                dir,
-                x: self.x,
-                y: self.y,
-            }),
+            let mut next = Vec::new();
+            for (x, y) in frontier {
+                for &dir in Direction::iterator() {
+                    if let Some((x, y)) = self.target(x, y, dir) {
+                        if !seen[y * self.width + x] {
+                            seen[y * self.width + x] = true;
+                            next.push((x, y));
+                        }
+                    }
+                }
+            }
+            frontier = next;
+            jumps += 1;
         }
+        None
     }
 
-    /// Set the value of location on the board to `value`.
-    fn set_value(&mut self, x: usize, y: usize, value: u8) -> Result<Board, BoardError> {
-        if x >= self.size || y >= self.size {
-            return Err(BoardError::IndexOutOfRange {
-                x,
-                y,
-                max: self.size,
-            });
+    /// Return a copy of the board with the cell at (x, y) blocked, so that
+    /// the path can never visit it. Returns an error if the cell is off the
+    /// board or holds a clue, if the board has been started, or if a clue
+    /// is larger than the number of cells left.
+    pub fn block(&self, x: usize, y: usize) -> Result<Board, BoardError> {
+        self.check_bounds(x, y)?;
+        if self.is_started() {
+            return Err(BoardError::AlreadyStarted);
         }
-        if value < 1 {
-            return Err(BoardError::CannotClear { x, y });
+        if self.clue_at(x, y) != 0 {
+            return Err(BoardError::NotEmpty { x, y });
         }
-        if value <= self.score() as u8 {
-            return Err(BoardError::ValueUsed { x, y, value });
+        if self.blocked_at(x, y) {
+            return Ok(self.clone());
         }
-        if value > self.cells as u8 {
+        if let Some((cx, cy, value)) = self
+            .clues()
+            .into_iter()
+            .find(|&(_, _, v)| v as usize >= self.cells)
+        {
             return Err(BoardError::ValueOutOfRange {
-                x,
-                y,
+                x: cx,
+                y: cy,
                 value,
-                max: self.cells,
+                max: self.cells - 1,
             });
         }
-        if self.value_at(x, y) != 0 {
-            return Err(BoardError::NotEmpty { x, y });
-        }
         let mut board = self.clone();
-        board.x = x;
-        board.y = y;
-        board.values[y * self.size + x] = value;
+        for &dir in Direction::iterator() {
+            if let Some((x, y)) = self.target(x, y, dir) {
+                board.degrees[y * self.width + x] -= 1;
+            }
+        }
+        if board.blocked.is_empty() {
+            board.blocked = vec![false; board.values.len()];
+        }
+        let i = y * self.width + x;
+        board.blocked[i] = true;
+        board.degrees[i] = 0;
+        let mut neighbors = board.neighbors.to_vec();
+        for target in neighbors.iter_mut().flatten() {
+            if *target == Some(i as u16) {
+                *target = None;
+            }
+        }
+        board.neighbors = neighbors.into();
+        board.cells -= 1;
         Ok(board)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[inline]
+    /// Return `true` if the cell at (x, y) is blocked.
+    pub fn blocked_at(&self, x: usize, y: usize) -> bool {
+        self.blocked
+            .get(y * self.width + x)
+            .cloned()
+            .unwrap_or(false)
+    }
 
-    #[test]
-    // Start a board and check that invariants hold.
-    fn new_board() {
-        let mut board = Board::new(10);
-        // newly created board has a size of 10
-        assert_eq!(board.size, 10);
-        // newly created bboard has cell count of 100
-        assert_eq!(board.cells, 100);
-        // newly created board has a score of 0
-        assert_eq!(board.score(), 0);
-        // newly created board is not started
-        assert_eq!(board.is_started(), false);
-        // unstarted board cannot be won
-        assert_eq!(board.is_won(), false);
-        // no possible moves because board isn't started
-        assert_eq!(board.possible_moves().len(), 0);
-        // start the board
-        board = board.start_at(5, 5).unwrap();
-        // board is started
-        assert_eq!(board.is_started(), true);
-        // cell at (5, 5) should be 1
-        assert_eq!(board.values[55], 1);
-        // score is 1
-        assert_eq!(board.score(), 1);
-        // all moves should be possible
-        assert_eq!(board.possible_moves().len(), 8);
-        // board isn't won
-        assert_eq!(board.is_won(), false);
+    /// Return the blocked cells of the board as (x, y), in row-major order.
+    pub fn blocked(&self) -> Vec<(usize, usize)> {
+        self.blocked
+            .iter()
+            .enumerate()
+            .filter(|&(_, &b)| b)
+            .map(|(i, _)| Pos::from_index(i, self.width).into())
+            .collect()
     }
 
-    #[test]
-    // Test a complete set of moves on a 5x5 board and confirm that all
-    // invariants hold. The completed board looks like:
-    //
-    //      1 24 14  2 25
-    //     16 21  5  8 20
-    //     13 10 18 23 11
-    //      4  7 15  3  6
-    //     17 22 12  9 19
-    fn win_5() {
-        let moves = [
-            Direction::Right,
-            Direction::Down,
-            Direction::Left,
-            Direction::UpRight,
-            Direction::DownRight,
-            Direction::Left,
-            Direction::UpRight,
-            Direction::Down,
-            Direction::UpLeft,
-            Direction::Right,
-            Direction::DownLeft,
-            Direction::UpLeft,
-            Direction::UpRight,
-            Direction::Down,
-            Direction::UpLeft,
-            Direction::Down,
-            Direction::UpRight,
-            Direction::DownRight,
-            Direction::Up,
-            Direction::Left,
-            Direction::Down,
-            Direction::UpRight,
-            Direction::UpLeft,
-            Direction::Right,
-        ];
-        // The number of possible moves on the board after each move.
-        let possible_moves = [
-            3, 2, 2, 1, 2, 2, 2, 2, 2, 1, 2, 1, 2, 1, 2, 1, 1, 2, 2, 1, 1, 1, 1, 1, 0,
-        ];
+    /// Return the cells that are not blocked in row-major order, and for
+    /// each cell of the grid, its index in that list, or 0 if it is blocked.
+    pub(crate) fn open_cells(&self) -> (Vec<(usize, usize)>, Vec<usize>) {
+        let width = self.width();
+        let mut open = Vec::with_capacity(self.cells());
+        let mut index = vec![0; width * self.height()];
+        for y in 0..self.height() {
+            for x in 0..width {
+                if !self.blocked_at(x, y) {
+                    index[y * width + x] = open.len();
+                    open.push((x, y));
+                }
+            }
+        }
+        (open, index)
+    }
 
-        let mut board = Board::new(5);
-        assert_eq!(board.is_started(), false);
-        board = board.start_at(0, 0).unwrap();
-        assert_eq!(board.is_started(), true);
+    /// Return the clues of the board as (x, y, value), in row-major order.
+    pub fn clues(&self) -> Vec<(usize, usize, u16)> {
+        self.clues
+            .iter()
+            .enumerate()
+            .filter(|&(_, &v)| v != 0)
+            .map(|(i, &v)| (i % self.width, i / self.width, v))
+            .collect()
+    }
+
+    #[inline]
+    /// Return `true` if the board was created with any clues.
+    pub fn has_clues(&self) -> bool {
+        !self.clues.is_empty()
+    }
+
+    /// Check that the board plays the plain game that a
+    /// [`PlainBoard`](crate::plain::PlainBoard) can hold: no clues or blocked
+    /// cells, and the default move rule without wrapping around.
+    pub(crate) fn check_plain(&self) -> Result<(), BoardError> {
+        if self.has_clues() || !self.blocked().is_empty() {
+            return Err(BoardError::InvalidGrid {
+                reason: "a plain board cannot hold clues or blocked cells".to_string(),
+            });
+        }
+        if self.rule() != MoveRule::default() || self.wraps() {
+            return Err(BoardError::InvalidGrid {
+                reason: "a plain board only follows the default move rule, without wrapping"
+                    .to_string(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Return the clue at the given location, or 0 if the cell has none.
+    pub fn clue_at(&self, x: usize, y: usize) -> u16 {
+        self.clues.get(y * self.width + x).cloned().unwrap_or(0)
+    }
+
+    /// Return the location of the clue with value `value`, if there is one.
+    fn clue_position(&self, value: u16) -> Option<(usize, usize)> {
+        self.clues
+            .iter()
+            .position(|&v| v == value)
+            .map(|i| Pos::from_index(i, self.width).into())
+    }
+
+    /// Return `true` if the clues allow the cell at (x, y) to take `value`.
+    fn clue_allows(&self, x: usize, y: usize, value: u16) -> bool {
+        match self.clue_at(x, y) {
+            0 => self.clue_position(value).is_none(),
+            clue => clue == value,
+        }
+    }
+
+    #[inline]
+    /// Return the number of vertical/horizontal cells in a square board. On
+    /// a rectangular board this is the width; see `width()` and `height()`.
+    pub fn size(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    /// Return the number of cells in each row of the board.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    /// Return the number of cells in each column of the board.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    /// Return `true` if the board is as wide as it is high.
+    pub fn is_square(&self) -> bool {
+        self.width == self.height
+    }
+
+    /// Return an error if (x, y) is off the board.
+    fn check_bounds(&self, x: usize, y: usize) -> Result<(), BoardError> {
+        if x >= self.width {
+            Err(BoardError::IndexOutOfRange {
+                x,
+                y,
+                max: self.width,
+            })
+        } else if y >= self.height {
+            Err(BoardError::IndexOutOfRange {
+                x,
+                y,
+                max: self.height,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    /// Return the number of cells the path must visit, which is the value
+    /// of the last cell of a finished game: width x height, less any blocked
+    /// cells.
+    pub fn cells(&self) -> usize {
+        self.cells
+    }
+
+    #[inline]
+    /// Return the (x, y) location of the last cell set in the board.
+    pub fn current_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    #[inline]
+    /// Return the value of the last cell set in the board, or 0 if the board
+    /// has not been started.
+    pub fn last_value(&self) -> u16 {
+        self.value_at(self.x, self.y)
+    }
+
+    /// Return an iterator over the cells of the board in row-major order,
+    /// as their (x, y) location and value, where 0 is an empty cell. Blocked
+    /// cells are skipped.
+    pub fn iter_cells(&self) -> impl Iterator<Item = ((usize, usize), u16)> + '_ {
+        let width = self.width;
+        self.values
+            .iter()
+            .enumerate()
+            .map(move |(i, &value)| ((i % width, i / width), value))
+            .filter(move |&((x, y), _)| !self.blocked_at(x, y))
+    }
+
+    /// Return an iterator over the (x, y) locations of the cells the path
+    /// has yet to visit, in row-major order. Blocked cells are skipped.
+    pub fn empty_cells(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.iter_cells()
+            .filter(|&(_, value)| value == 0)
+            .map(|(cell, _)| cell)
+    }
+
+    #[inline]
+    /// Return the value at (x, y), where 0 is an empty cell, or `None` if
+    /// the location is off the board.
+    pub fn get(&self, x: usize, y: usize) -> Option<u16> {
+        if x < self.width && y < self.height {
+            Some(self[(x, y)])
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    /// Return `true` if the board has been started; otherwise `false`.
+    pub fn is_started(&self) -> bool {
+        self.value_at(self.x, self.y) > 0
+    }
+
+    /// Return a list of all possible moves from the current location.
+    /// Returns an empty list if there are no moves, or the board is empty.
+    pub fn possible_moves(&self) -> Vec<&'static Direction> {
+        Direction::iterator()
+            .filter(|&x| self.valid_move(*x).is_some())
+            .collect()
+    }
+
+    /// Return the possible moves from the current location, in the same
+    /// order as `possible_moves()`, without allocating.
+    pub fn iter_moves(&self) -> impl Iterator<Item = Direction> + '_ {
+        Direction::iterator()
+            .copied()
+            .filter(move |&dir| self.valid_move(dir).is_some())
+    }
+
+    /// Return the possible moves from the current location, in the same
+    /// order as `possible_moves()`, with the cell each lands on and the
+    /// value it places.
+    pub fn moves(&self) -> impl Iterator<Item = Move> + '_ {
+        let value = self.last_value() + 1;
+        Direction::iterator().filter_map(move |&dir| {
+            self.valid_move(dir).map(|to| Move {
+                dir,
+                to: to.into(),
+                value,
+            })
+        })
+    }
+
+    /// Return the moves made since the board was started, in order, with
+    /// the cell each landed on and the value it placed. Moves taken back
+    /// with `undo()` are not included.
+    pub fn played_moves(&self) -> Vec<Move> {
+        self.history
+            .iter()
+            .zip(self.path().into_iter().skip(1))
+            .enumerate()
+            .map(|(i, (&dir, to))| Move {
+                dir,
+                to: to.into(),
+                value: i as u16 + 2,
+            })
+            .collect()
+    }
+
+    #[inline]
+    /// Return the number of possible moves from the current location,
+    /// without allocating.
+    pub fn move_count(&self) -> usize {
+        self.iter_moves().count()
+    }
+
+    /// Determines if a move in the given direction is valid. A move is valid
+    /// if the resulting position is valid, and if the the resulting position
+    /// is an empty cell. If the move is valid, it returns `Some((x, y))`
+    /// where (x, y) is the cell location resulting from the move. Otherwise,
+    /// it returns `None`.
+    fn valid_move(&self, dir: Direction) -> Option<(usize, usize)> {
+        if self.is_started() {
+            if let Some(i) = self.neighbors[self.y * self.width + self.x][dir as usize] {
+                let (x, y) = Pos::from_index(i as usize, self.width).into();
+                let next = self.value_at(self.x, self.y) + 1;
+                if !self.is_occupied(i as usize)
+                    && (self.clues.is_empty() || self.clue_allows(x, y, next))
+                {
+                    return Some((x, y));
+                }
+            }
+        }
+        None
+    }
+
+    /// Return the cell reached by jumping in direction `dir` from the cell at
+    /// (x, y), regardless of whether either cell is empty. Returns `None` if
+    /// the jump would leave the board or land on a blocked cell. On a board
+    /// that wraps, a jump never leaves the board, but one that comes back to
+    /// (x, y) itself is refused; two directions may land on the same cell.
+    /// Returns `None` if (x, y) is off the board.
+    pub fn target(&self, x: usize, y: usize, dir: Direction) -> Option<(usize, usize)> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.neighbors[y * self.width + x][dir as usize]
+            .map(|i| Pos::from_index(i as usize, self.width).into())
+    }
+
+    /// Work out the cell reached by jumping in direction `dir` from (x, y),
+    /// as returned by `target()`, from the rule and wrap mode.
+    fn jump(&self, x: usize, y: usize, dir: Direction) -> Option<(usize, usize)> {
+        let from = Pos::new(x, y);
+        if self.wrap {
+            from.jump_wrapping(self.rule, dir, self.width, self.height)
+                .filter(|&p| p != from && !self.blocked_at(p.x, p.y))
+                .map(Pos::into)
+        } else {
+            from.jump(self.rule, dir)
+                .filter(|p| p.in_bounds(self.width, self.height) && !self.blocked_at(p.x, p.y))
+                .map(Pos::into)
+        }
+    }
+
+    /// Return the number of empty cells that can be reached from the current
+    /// location by a sequence of jumps through empty cells. This is an upper
+    /// bound on the number of moves left in the game. Returns 0 if the board
+    /// has not been started.
+    pub fn reachable(&self) -> usize {
+        self.reachable_cells().len()
+    }
+
+    /// Return the empty cells that can be reached from the current location
+    /// by a sequence of jumps through empty cells, in the order they are
+    /// found. Returns no cells if the board has not been started.
+    pub fn reachable_cells(&self) -> Vec<(usize, usize)> {
+        if !self.is_started() {
+            return Vec::new();
+        }
+        // Filled cells are marked seen from the start, so they are skipped.
+        let mut seen = self.occupied.clone();
+        let mut stack = vec![(self.x, self.y)];
+        let mut cells = Vec::new();
+        while let Some((x, y)) = stack.pop() {
+            for &dir in Direction::iterator() {
+                if let Some((x, y)) = self.target(x, y, dir) {
+                    let i = y * self.width + x;
+                    if !seen.contains(i) {
+                        seen.insert(i);
+                        cells.push((x, y));
+                        stack.push((x, y));
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    #[inline]
+    /// Return `true` if the cell at index `i` of `values` has been filled.
+    fn is_occupied(&self, i: usize) -> bool {
+        self.occupied.contains(i)
+    }
+
+    /// Return the filled cells as a bit set, indexed by `y * width + x`.
+    pub(crate) fn occupied(&self) -> &BitSet {
+        &self.occupied
+    }
+
+    #[inline]
+    /// Return the number of empty cells one jump away from the cell at (x, y).
+    /// A cell that two directions land on is counted for each of them.
+    pub fn free_neighbors(&self, x: usize, y: usize) -> u8 {
+        self.degrees[y * self.width + x]
+    }
+
+    /// Return the number of empty cells one jump away from every cell, in
+    /// row-major order like `free_neighbors()`, with 0 for the cells that
+    /// are filled or blocked. The counts are kept up to date as moves are
+    /// made, so this only copies them.
+    pub fn degrees(&self) -> Vec<u8> {
+        self.degrees
+            .iter()
+            .zip(&self.values)
+            .enumerate()
+            .map(|(i, (&degree, &value))| {
+                if value == 0 && !self.blocked.get(i).cloned().unwrap_or(false) {
+                    degree
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+
+    /// Return true if the board is complete. A board is complete if the value
+    /// of the last move equals the number of cells to visit, so that every
+    /// cell that is not blocked has been filled.
+    #[inline]
+    pub fn is_won(&self) -> bool {
+        self.value_at(self.x, self.y) as usize == self.cells
+    }
+
+    /// Return true if the board is complete and its last cell is one jump
+    /// away from its first, so that the path closes into a cycle.
+    pub fn is_closed_tour(&self) -> bool {
+        self.is_won()
+            && self.start_position().is_some_and(|(x, y)| {
+                Direction::iterator().any(|&dir| self.target(x, y, dir) == Some((self.x, self.y)))
+            })
+    }
+
+    /// Return the (x, y) location of the cell holding 1, or `None` if the
+    /// board has not been started.
+    pub fn start_position(&self) -> Option<(usize, usize)> {
+        self.position_of(1)
+    }
+
+    #[inline]
+    /// Return the (x, y) location of the cell holding `value`, or `None` if
+    /// the path has not reached it yet. Clues are not looked up until the
+    /// path lands on them.
+    pub fn position_of(&self, value: u16) -> Option<(usize, usize)> {
+        let i = *self.path.get((value as usize).checked_sub(1)?)? as usize;
+        Some(Pos::from_index(i, self.width).into())
+    }
+
+    /// Return the (x, y) locations of the cells on the path, in the order
+    /// they were visited, so that the cell holding `v` is at index `v - 1`.
+    pub fn path(&self) -> Vec<(usize, usize)> {
+        self.path
+            .iter()
+            .map(|&i| Pos::from_index(i as usize, self.width).into())
+            .collect()
+    }
+
+    /// Return `true` if there are no possible moves for the current board.
+    #[inline]
+    pub fn is_blocked(&self) -> bool {
+        self.is_started() && self.iter_moves().next().is_none()
+    }
+
+    #[inline]
+    /// The score is simply the highest value on the board.
+    pub fn score(&self) -> usize {
+        self.path.len()
+    }
+
+    #[inline]
+    /// Return the number of cells the path has yet to visit.
+    pub fn remaining(&self) -> usize {
+        self.cells - self.path.len()
+    }
+
+    #[inline]
+    /// Return `true` if every cell that is not blocked has been filled.
+    pub fn is_full(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    #[inline]
+    /// Return the value at the given location on the board.
+    pub fn value_at(&self, x: usize, y: usize) -> u16 {
+        self[(x, y)]
+    }
+
+    /// Return a copy of the board started by placing a 1 in the given
+    /// location. The board itself is left unchanged.
+    pub fn start_at(&self, x: usize, y: usize) -> Result<Board, BoardError> {
+        self.set_value(x, y, 1)
+    }
+
+    /// Return the cell a move in direction `dir` would land on, or `None`
+    /// if the move is not legal, without making it. Lookahead heuristics can
+    /// use this to weigh a move before committing to it.
+    pub fn peek_move(&self, dir: Direction) -> Option<(usize, usize)> {
+        self.valid_move(dir)
+    }
+
+    /// Return a copy of the board with the move in direction `dir` made,
+    /// leaving the board itself unchanged. This is `next_move()` under a name
+    /// that says the board is not consumed.
+    pub fn with_move(&self, dir: Direction) -> Result<Board, BoardError> {
+        self.next_move(dir)
+    }
+
+    /// Return a copy of the board with the next move made in the given
+    /// direction. The board itself is left unchanged.
+    pub fn next_move(&self, dir: Direction) -> Result<Board, BoardError> {
+        if !self.is_started() {
+            return Err(BoardError::NotStarted);
+        }
+        match self.valid_move(dir) {
+            Some((x, y)) => {
+                let val = self.value_at(self.x, self.y);
+                let mut board = self.set_value(x, y, val + 1)?;
+                board.history.push(dir);
+                board.undone.clear();
+                Ok(board)
+            }
+            None => Err(BoardError::BadDirection {
+                dir,
+                x: self.x,
+                y: self.y,
+            }),
+        }
+    }
+
+    #[inline]
+    /// Return the Zobrist hash of the board: a 64-bit hash of the filled
+    /// cells and the current position, kept up to date as moves are made and
+    /// undone rather than computed from the whole board. Boards that fill the
+    /// same cells and finish on the same cell, however they got there, have
+    /// the same hash, since they leave the same game to play out.
+    pub fn zobrist(&self) -> u64 {
+        self.zobrist
+    }
+
+    #[inline]
+    /// Return the moves made since the board was started, in order. Moves
+    /// taken back with `undo()` are not included.
+    pub fn history(&self) -> &[Direction] {
+        &self.history
+    }
+
+    /// Place the next value at (x, y): 1 if the board has not been started,
+    /// otherwise one more than the last value, which must then be one jump
+    /// away. This is the checked way to write a cell, since writing values
+    /// directly could break the path.
+    pub fn place(&mut self, x: usize, y: usize) -> Result<(), BoardError> {
+        if !self.is_started() {
+            *self = self.start_at(x, y)?;
+            return Ok(());
+        }
+        let (cx, cy) = (self.x, self.y);
+        match Direction::iterator().find(|&&dir| self.target(cx, cy, dir) == Some((x, y))) {
+            Some(&dir) => {
+                *self = self.next_move(dir)?;
+                Ok(())
+            }
+            None => Err(BoardError::BadAssignment {
+                value: self.last_value() as usize + 1,
+            }),
+        }
+    }
+
+    /// Return the image of the board under `symmetry`: its values, clues and
+    /// current position are moved to the image of their cells, and its
+    /// history and the moves that can be redone are mapped to the matching
+    /// directions, so the result is the game played by the image moves.
+    pub fn transform(&self, symmetry: Symmetry) -> Board {
+        let (width, height) = (self.width, self.height);
+        let map = |moves: &[Direction]| -> Vec<Direction> {
+            moves
+                .iter()
+                .map(|&d| symmetry.apply_move(self.rule, d))
+                .collect()
+        };
+        let mut image = if symmetry.swaps_axes() {
+            Board::with_dimensions(height, width)
+        } else {
+            Board::with_dimensions(width, height)
+        }
+        .with_moves(self.rule, self.wrap)
+        .expect("an empty board can take any rule");
+        for (x, y) in self.blocked() {
+            let (x, y) = symmetry.apply_rect(width, height, (x, y));
+            image = image
+                .block(x, y)
+                .expect("the image of a blocked cell can be blocked");
+        }
+        let clues: Vec<(usize, usize, u16)> = self
+            .clues()
+            .into_iter()
+            .map(|(x, y, v)| {
+                let (x, y) = symmetry.apply_rect(width, height, (x, y));
+                (x, y, v)
+            })
+            .collect();
+        let mut board = image
+            .add_clues(&clues)
+            .expect("the image of valid clues is valid");
+        if let Some(start) = self.start_position() {
+            let (x, y) = symmetry.apply_rect(width, height, start);
+            board = board
+                .start_at(x, y)
+                .and_then(|mut b| b.apply_moves(&map(&self.history)).map(|_| b))
+                .expect("the image of a legal game is legal");
+        }
+        board.undone = map(&self.undone);
+        board
+    }
+
+    /// Return the symmetries that map the board onto a board of the same
+    /// dimensions: all eight for a square board, but only the identity, the
+    /// half turn and the two reflections for a rectangular one.
+    pub fn symmetries(&self) -> Vec<Symmetry> {
+        Symmetry::all()
+            .iter()
+            .copied()
+            .filter(|s| self.is_square() || !s.swaps_axes())
+            .collect()
+    }
+
+    /// Return whichever of the rotations and reflections of the board in
+    /// `symmetries()` comes first when their values are compared in
+    /// row-major order, with ties broken by their clues. Boards that are
+    /// images of each other have the same canonical form.
+    pub fn canonical(&self) -> Board {
+        self.symmetries()
+            .iter()
+            .map(|&s| self.transform(s))
+            .min_by(|a, b| (&a.values, &a.clues).cmp(&(&b.values, &b.clues)))
+            .unwrap()
+    }
+
+    /// Return the board rotated a quarter turn clockwise.
+    pub fn rotate90(&self) -> Board {
+        self.transform(Symmetry::Rotate90)
+    }
+
+    /// Return the board rotated half a turn.
+    pub fn rotate180(&self) -> Board {
+        self.transform(Symmetry::Rotate180)
+    }
+
+    /// Return the board reflected left to right.
+    pub fn flip_h(&self) -> Board {
+        self.transform(Symmetry::FlipH)
+    }
+
+    /// Return the board reflected top to bottom.
+    pub fn flip_v(&self) -> Board {
+        self.transform(Symmetry::FlipV)
+    }
+
+    /// Return the cells whose values differ between this board and `other`,
+    /// in row-major order, going from this board's values to `other`'s. If
+    /// the boards differ in size, a cell that is only on one of them reads
+    /// as empty on the other.
+    pub fn diff(&self, other: &Board) -> Vec<CellChange> {
+        let (width, height) = (self.width.max(other.width), self.height.max(other.height));
+        (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| CellChange {
+                at: Pos::new(x, y),
+                from: self.get(x, y).unwrap_or(0),
+                to: other.get(x, y).unwrap_or(0),
+            })
+            .filter(|change| change.from != change.to)
+            .collect()
+    }
+
+    /// Check that the board holds a legal game: its values run from 1 up
+    /// without gaps, each one jump from the one before, none of them on a
+    /// blocked cell or against a clue, and the current position is the cell
+    /// holding the highest value. Boards built through this API always
+    /// pass, so this is a check on data read from elsewhere. Returns the
+    /// first problem found.
+    pub fn validate(&self) -> Result<(), BoardError> {
+        let invalid = |reason: String| BoardError::InvalidGrid { reason };
+        let mut positions = vec![None; self.cells + 1];
+        for (i, &value) in self.values.iter().enumerate() {
+            let (x, y) = (i % self.width, i / self.width);
+            if value == 0 {
+                continue;
+            }
+            if self.blocked_at(x, y) {
+                return Err(BoardError::NotEmpty { x, y });
+            }
+            if value as usize > self.cells {
+                return Err(BoardError::ValueOutOfRange {
+                    x,
+                    y,
+                    value,
+                    max: self.cells,
+                });
+            }
+            if positions[value as usize].replace((x, y)).is_some() {
+                return Err(BoardError::ValueUsed { x, y, value });
+            }
+            if !self.clue_allows(x, y, value) {
+                return Err(BoardError::ClueConflict { x, y, value });
+            }
+        }
+        let last = positions.iter().rposition(|p| p.is_some()).unwrap_or(0);
+        let mut prev: Option<(usize, usize)> = None;
+        for (value, position) in positions.iter().enumerate().take(last + 1).skip(1) {
+            let (x, y) = position.ok_or_else(|| invalid(format!("value {} is missing", value)))?;
+            if let Some((px, py)) = prev {
+                if !Direction::iterator().any(|&dir| self.target(px, py, dir) == Some((x, y))) {
+                    return Err(BoardError::BadAssignment { value });
+                }
+            }
+            prev = Some((x, y));
+        }
+        if last > 0 && Some(self.current_position()) != prev {
+            return Err(invalid(
+                "the current position does not hold the highest value".to_string(),
+            ));
+        }
+        if self.path()
+            != positions[1..=last]
+                .iter()
+                .flatten()
+                .copied()
+                .collect::<Vec<_>>()
+        {
+            return Err(invalid("the path does not match the values".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Rebuild the game on this unstarted board whose cells hold `values`,
+    /// given in row-major order with 0 for an empty or blocked cell, by
+    /// placing the values in order. The values must run from 1 up without
+    /// gaps, each one jump from the one before.
+    pub(crate) fn replay_values(&self, values: &[u16]) -> Result<Board, BoardError> {
+        let invalid = |reason: String| BoardError::InvalidGrid { reason };
+        let mut board = self.clone();
+        if values.len() != board.values.len() {
+            return Err(invalid(format!(
+                "{} values, not {}",
+                values.len(),
+                board.values.len()
+            )));
+        }
+        let mut positions = vec![None; board.cells + 1];
+        for (i, &value) in values.iter().enumerate() {
+            let (x, y) = (i % board.width, i / board.width);
+            if value > 0 && board.blocked_at(x, y) {
+                return Err(BoardError::NotEmpty { x, y });
+            }
+            if value as usize > board.cells {
+                return Err(BoardError::ValueOutOfRange {
+                    x,
+                    y,
+                    value,
+                    max: board.cells,
+                });
+            }
+            if value > 0 && positions[value as usize].replace((x, y)).is_some() {
+                return Err(BoardError::ValueUsed { x, y, value });
+            }
+        }
+        let last = positions.iter().rposition(|p| p.is_some()).unwrap_or(0);
+        for (value, position) in positions.iter().enumerate().take(last + 1).skip(1) {
+            match position {
+                Some((x, y)) => board.place(*x, *y)?,
+                None => return Err(invalid(format!("value {} is missing", value))),
+            }
+        }
+        Ok(board)
+    }
+
+    /// Make each of `moves` in turn. If any of them is not valid, the board
+    /// is left as it was before the first and the error for that move is
+    /// returned, with the state of the board just before it and its index
+    /// in `moves` attached as its `context()`.
+    pub fn apply_moves(&mut self, moves: &[Direction]) -> Result<(), BoardError> {
+        let mut board = self.clone();
+        for (step, &dir) in moves.iter().enumerate() {
+            board = board.next_move(dir).map_err(|err| {
+                err.with_context(ErrorContext {
+                    attempted: Some(dir),
+                    step: Some(step),
+                    ..ErrorContext::new(&board)
+                })
+            })?;
+        }
+        *self = board;
+        Ok(())
+    }
+
+    /// Take back the last move by clearing the cell holding the highest
+    /// value, making the cell before it the current position again. The move
+    /// can be made again with `redo()`. Undoing the first move leaves the
+    /// board unstarted, and forgets any moves that could be redone. Returns
+    /// an error if the board has not been started.
+    pub fn undo(&mut self) -> Result<(), BoardError> {
+        if !self.is_started() {
+            return Err(BoardError::NotStarted);
+        }
+        match self.history.pop() {
+            Some(dir) => self.undone.push(dir),
+            None => self.undone.clear(),
+        }
+        let (x, y) = (self.x, self.y);
+        let i = y * self.width + x;
+        self.values[i] = 0;
+        self.occupied.remove(i);
+        self.path.pop();
+        self.zobrist ^= zobrist_key(2 * i) ^ zobrist_key(2 * i + 1);
+        for &dir in Direction::iterator() {
+            if let Some((x, y)) = self.target(x, y, dir) {
+                self.degrees[y * self.width + x] += 1;
+            }
+        }
+        if let Some(&i) = self.path.last() {
+            let i = i as usize;
+            self.x = i % self.width;
+            self.y = i / self.width;
+            self.zobrist ^= zobrist_key(2 * i + 1);
+        }
+        Ok(())
+    }
+
+    /// Make again the move most recently taken back by `undo()`. Returns an
+    /// error if there is none, or if a move has been made since.
+    pub fn redo(&mut self) -> Result<(), BoardError> {
+        let dir = self.undone.pop().ok_or(BoardError::NothingToRedo)?;
+        let undone = std::mem::take(&mut self.undone);
+        match self.next_move(dir) {
+            Ok(board) => {
+                *self = board;
+                self.undone = undone;
+                Ok(())
+            }
+            Err(err) => {
+                self.undone = undone;
+                self.undone.push(dir);
+                Err(err)
+            }
+        }
+    }
+
+    /// Set the value of location on the board to `value`.
+    fn set_value(&self, x: usize, y: usize, value: u16) -> Result<Board, BoardError> {
+        self.check_value(x, y, value)?;
+        let mut board = self.clone();
+        board.fill(x, y, value);
+        Ok(board)
+    }
+
+    /// Return an error if `value` cannot be placed at (x, y) as the next
+    /// value of the path.
+    fn check_value(&self, x: usize, y: usize, value: u16) -> Result<(), BoardError> {
+        self.check_bounds(x, y)?;
+        if value < 1 {
+            return Err(BoardError::CannotClear { x, y });
+        }
+        if value <= self.score() as u16 {
+            return Err(BoardError::ValueUsed { x, y, value });
+        }
+        if value > self.cells as u16 {
+            return Err(BoardError::ValueOutOfRange {
+                x,
+                y,
+                value,
+                max: self.cells,
+            });
+        }
+        if self.value_at(x, y) != 0 || self.blocked_at(x, y) {
+            return Err(BoardError::NotEmpty { x, y });
+        }
+        if !self.clue_allows(x, y, value) {
+            return Err(BoardError::ClueConflict { x, y, value });
+        }
+        Ok(())
+    }
+
+    /// Place `value` at (x, y) in place, which `check_value()` must allow.
+    fn fill(&mut self, x: usize, y: usize, value: u16) {
+        let i = y * self.width + x;
+        if self.is_started() {
+            self.zobrist ^= zobrist_key(2 * (self.y * self.width + self.x) + 1);
+        }
+        self.zobrist ^= zobrist_key(2 * i) ^ zobrist_key(2 * i + 1);
+        self.x = x;
+        self.y = y;
+        self.values[i] = value;
+        self.occupied.insert(i);
+        self.path.push(i as u16);
+        for &dir in Direction::iterator() {
+            if let Some((x, y)) = self.target(x, y, dir) {
+                self.degrees[y * self.width + x] -= 1;
+            }
+        }
+    }
+
+    /// Clear every value from the board, leaving it unstarted with its
+    /// dimensions, blocked cells and clues, and forget its history. The
+    /// board's storage is reused, so nothing is allocated.
+    pub fn reset(&mut self) {
+        let mut path = std::mem::take(&mut self.path);
+        for &i in &path {
+            let (x, y) = Pos::from_index(i as usize, self.width).into();
+            for &dir in Direction::iterator() {
+                if let Some((x, y)) = self.target(x, y, dir) {
+                    self.degrees[y * self.width + x] += 1;
+                }
+            }
+            self.values[i as usize] = 0;
+        }
+        path.clear();
+        self.path = path;
+        self.occupied.clear();
+        self.history.clear();
+        self.undone.clear();
+        self.x = 0;
+        self.y = 0;
+        self.zobrist = 0;
+    }
+
+    /// Reset the board and start it again by placing a 1 at (x, y), without
+    /// allocating. If (x, y) cannot hold the 1, the board is left reset and
+    /// the error is returned.
+    pub fn restart_at(&mut self, x: usize, y: usize) -> Result<(), BoardError> {
+        self.reset();
+        self.check_value(x, y, 1)?;
+        self.fill(x, y, 1);
+        Ok(())
+    }
+}
+
+/// Return the Zobrist key of feature `i`: `2 * cell` for a filled cell and
+/// `2 * cell + 1` for the current position. The keys are generated with
+/// SplitMix64, so they need no table and are the same on every run.
+fn zobrist_key(i: usize) -> u64 {
+    let mut z = (i as u64 + 1).wrapping_mul(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+/// Boards are equal when they hold the same values and clues. The current
+/// position is the cell holding the highest value, so it is equal too unless
+/// neither board has been started. The history and the moves that can be
+/// redone are not compared.
+impl PartialEq for Board {
+    fn eq(&self, other: &Board) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.values == other.values
+            && self.clues == other.clues
+            && self.blocked == other.blocked
+            && self.rule == other.rule
+            && self.wrap == other.wrap
+    }
+}
+
+impl Eq for Board {}
+
+impl Hash for Board {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.width.hash(state);
+        self.height.hash(state);
+        self.values.hash(state);
+        self.clues.hash(state);
+        self.blocked.hash(state);
+        self.rule.hash(state);
+        self.wrap.hash(state);
+    }
+}
+
+/// Print the board as a grid of values, one row per line, with the values
+/// right-justified in columns as wide as the largest value. Empty cells are
+/// shown as `.`, and blocked cells as `#`.
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let width = self.cells.to_string().len();
+        for y in 0..self.height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..self.width {
+                if x > 0 {
+                    write!(f, " ")?;
+                }
+                match self.value_at(x, y) {
+                    0 if self.blocked_at(x, y) => write!(f, "{:>width$}", "#", width = width)?,
+                    0 => write!(f, "{:>width$}", ".", width = width)?,
+                    value => write!(f, "{:>width$}", value, width = width)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read a board from a grid of values in the format printed by `Display`:
+/// one row per line, with the values separated by whitespace. Empty cells may
+/// be written as `.` or `0`, and blocked cells as `#`. The values must run
+/// from 1 up without gaps, each one jump from the one before, and the board
+/// is rebuilt by making those moves, so its history and current position are
+/// restored. To read a grid whose values are clues, use
+/// [`Board::from_puzzle`].
+impl FromStr for Board {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (board, values) = parse_grid(s)?;
+        board.replay_values(&values)
+    }
+}
+
+impl Board {
+    /// Read a puzzle from a grid in the format read by `FromStr`, where each
+    /// value is a clue rather than a move, as in a published Hidato puzzle.
+    /// The clues are checked against each other as by `add_clue()`. If there
+    /// is a clue of 1, the board is started there and the run of clues that
+    /// follow it one jump at a time is played, so a game in progress resumes
+    /// from its last move. Returns an error if the grid is malformed or the
+    /// clues cannot all belong to one game.
+    pub fn from_puzzle(s: &str) -> Result<Board, BoardError> {
+        let (board, values) = parse_grid(s)?;
+        let clues: Vec<(usize, usize, u16)> = values
+            .iter()
+            .enumerate()
+            .filter(|&(_, &value)| value > 0)
+            .map(|(i, &value)| (i % board.width, i / board.width, value))
+            .collect();
+        let mut board = board.add_clues(&clues)?;
+        if let Some((x, y)) = board.clue_position(1) {
+            board = board.start_at(x, y)?;
+            while let Some(to) = board.clue_position(board.last_value() + 1) {
+                let (x, y) = board.current_position();
+                match Direction::iterator().find(|&&dir| board.target(x, y, dir) == Some(to)) {
+                    Some(&dir) => board = board.next_move(dir)?,
+                    None => break,
+                }
+            }
+        }
+        Ok(board)
+    }
+
+    /// Build a `size` x `size` board from the values of its cells, given in
+    /// row-major order with 0 for an empty cell. The values must run from 1
+    /// up without gaps, each one jump from the one before, and the board is
+    /// rebuilt by making those moves, so its history and current position
+    /// are restored as by `FromStr`.
+    pub fn from_values(size: usize, values: &[u16]) -> Result<Board, BoardError> {
+        Board::try_with_dimensions(size, size)?.replay_values(values)
+    }
+}
+
+/// Build a board from its size and the values of its cells, as by
+/// `Board::from_values()`. Values that fit in a byte cover boards of up to
+/// 15x15.
+impl std::convert::TryFrom<(usize, Vec<u8>)> for Board {
+    type Error = BoardError;
+
+    fn try_from((size, values): (usize, Vec<u8>)) -> Result<Self, Self::Error> {
+        let values: Vec<u16> = values.into_iter().map(u16::from).collect();
+        Board::from_values(size, &values)
+    }
+}
+
+/// Build a board from its size and the values of its cells, as by
+/// `Board::from_values()`.
+impl std::convert::TryFrom<(usize, Vec<u16>)> for Board {
+    type Error = BoardError;
+
+    fn try_from((size, values): (usize, Vec<u16>)) -> Result<Self, Self::Error> {
+        Board::from_values(size, &values)
+    }
+}
+
+/// Split a grid in the format read by `FromStr` into a board of its
+/// dimensions, with its blocked cells, and the values of its cells in
+/// row-major order.
+fn parse_grid(s: &str) -> Result<(Board, Vec<u16>), BoardError> {
+    let invalid = |reason: String| BoardError::InvalidGrid { reason };
+    let rows: Vec<Vec<&str>> = s
+        .lines()
+        .map(|line| line.split_whitespace().collect::<Vec<_>>())
+        .filter(|row| !row.is_empty())
+        .collect();
+    let height = rows.len();
+    let width = rows.first().map_or(0, |row| row.len());
+    let mut board = Board::try_with_dimensions(width, height)?;
+    let mut values = Vec::with_capacity(width * height);
+    for (y, row) in rows.iter().enumerate() {
+        if row.len() != width {
+            return Err(invalid(format!(
+                "row {} has {} values, not {}",
+                y,
+                row.len(),
+                width
+            )));
+        }
+        for (x, &word) in row.iter().enumerate() {
+            if word == "#" {
+                board = board.block(x, y)?;
+            }
+            values.push(match word {
+                "." | "#" => 0,
+                word => word
+                    .parse()
+                    .map_err(|_| invalid(format!("'{}' is not a value", word)))?,
+            });
+        }
+    }
+    Ok((board, values))
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+/// The serialized form of a board. Only the size, clues, start and moves are
+/// needed to rebuild a board; the values and position are included so that
+/// the data can be read without replaying the moves, and are checked against
+/// the replay when the board is deserialized.
+struct BoardRecord {
+    /// The width of the board.
+    size: usize,
+    /// The height of the board, if it is not square.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    height: Option<usize>,
+    /// The values of the cells in row-major order, with 0 for empty cells.
+    values: Vec<u16>,
+    /// The (x, y) location of the last cell set, if the board is started.
+    position: Option<(usize, usize)>,
+    /// The moves made since the board was started.
+    history: Vec<Direction>,
+    /// The moves taken back by `undo()`, most recently undone last.
+    redo: Vec<Direction>,
+    /// The clues of the board as (x, y, value).
+    clues: Vec<(usize, usize, u16)>,
+    /// The blocked cells of the board as (x, y), if there are any.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    blocked: Vec<(usize, usize)>,
+    /// The move rule of the board, if it is not the default.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    rule: Option<MoveRule>,
+    /// Whether the board wraps around at its edges.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    wrap: bool,
+}
+
+#[cfg(feature = "serde")]
+impl From<Board> for BoardRecord {
+    fn from(board: Board) -> Self {
+        BoardRecord {
+            size: board.width,
+            height: Some(board.height).filter(|_| !board.is_square()),
+            position: Some(board.current_position()).filter(|_| board.is_started()),
+            clues: board.clues(),
+            blocked: board.blocked(),
+            rule: Some(board.rule).filter(|&rule| rule != MoveRule::default()),
+            wrap: board.wrap,
+            values: board.values,
+            history: board.history,
+            redo: board.undone,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl std::convert::TryFrom<BoardRecord> for Board {
+    type Error = BoardError;
+
+    fn try_from(record: BoardRecord) -> Result<Self, Self::Error> {
+        let invalid = |reason: &str| BoardError::InvalidGrid {
+            reason: reason.to_string(),
+        };
+        let height = record.height.unwrap_or(record.size);
+        let mut board = record
+            .blocked
+            .iter()
+            .try_fold(
+                Board::try_with_dimensions(record.size, height)?
+                    .with_moves(record.rule.unwrap_or_default(), record.wrap)?,
+                |board, &(x, y)| board.block(x, y),
+            )?
+            .add_clues(&record.clues)?;
+        if record.values.len() != board.values.len() {
+            return Err(invalid("size does not match the values"));
+        }
+        if let Some(i) = record.values.iter().position(|&v| v == 1) {
+            board = board.start_at(i % board.width, i / board.width)?;
+            board.apply_moves(&record.history)?;
+        }
+        if board.values != record.values {
+            return Err(invalid("moves do not match the values"));
+        }
+        if record.position != Some(board.current_position()).filter(|_| board.is_started()) {
+            return Err(invalid("position does not match the values"));
+        }
+        board.undone = record.redo;
+        board.validate()?;
+        Ok(board)
+    }
+}
+
+/// Index the board by (x, y) to read the value of a cell, where 0 is an
+/// empty cell. Panics if the location is off the board. There is no mutable
+/// indexing, since writing a cell directly could break the path; use
+/// `Board::place()` instead.
+impl Index<(usize, usize)> for Board {
+    type Output = u16;
+
+    #[inline]
+    fn index(&self, (x, y): (usize, usize)) -> &u16 {
+        assert!(
+            x < self.width && y < self.height,
+            "cell ({}, {}) is off the board",
+            x,
+            y
+        );
+        &self.values[y * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Start a board and check that invariants hold.
+    fn new_board() {
+        let mut board = Board::new(10);
+        // newly created board has a size of 10
+        assert_eq!(board.size(), 10);
+        // newly created bboard has cell count of 100
+        assert_eq!(board.cells, 100);
+        // newly created board has a score of 0
+        assert_eq!(board.score(), 0);
+        // newly created board is not started
+        assert!(!board.is_started());
+        // unstarted board cannot be won
+        assert!(!board.is_won());
+        // no possible moves because board isn't started
+        assert_eq!(board.possible_moves().len(), 0);
+        assert_eq!(board.iter_moves().next(), None);
+        // start the board
+        board = board.start_at(5, 5).unwrap();
+        // board is started
+        assert!(board.is_started());
+        // cell at (5, 5) should be 1
+        assert_eq!(board.values[55], 1);
+        // score is 1
+        assert_eq!(board.score(), 1);
+        // all moves should be possible
+        assert_eq!(board.possible_moves().len(), 8);
+        // board isn't won
+        assert!(!board.is_won());
+    }
+
+    #[test]
+    // Test a complete set of moves on a 5x5 board and confirm that all
+    // invariants hold. The completed board looks like:
+    //
+    //      1 24 14  2 25
+    //     16 21  5  8 20
+    //     13 10 18 23 11
+    //      4  7 15  3  6
+    //     17 22 12  9 19
+    fn win_5() {
+        let moves = [
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+            Direction::UpRight,
+            Direction::DownRight,
+            Direction::Left,
+            Direction::UpRight,
+            Direction::Down,
+            Direction::UpLeft,
+            Direction::Right,
+            Direction::DownLeft,
+            Direction::UpLeft,
+            Direction::UpRight,
+            Direction::Down,
+            Direction::UpLeft,
+            Direction::Down,
+            Direction::UpRight,
+            Direction::DownRight,
+            Direction::Up,
+            Direction::Left,
+            Direction::Down,
+            Direction::UpRight,
+            Direction::UpLeft,
+            Direction::Right,
+        ];
+        // The number of possible moves on the board after each move.
+        let possible_moves = [
+            3, 2, 2, 1, 2, 2, 2, 2, 2, 1, 2, 1, 2, 1, 2, 1, 1, 2, 2, 1, 1, 1, 1, 1, 0,
+        ];
+
+        let mut board = Board::new(5);
+        assert!(!board.is_started());
+        board = board.start_at(0, 0).unwrap();
+        assert!(board.is_started());
         let mut possible = possible_moves.iter();
-        let mut i = 1;
-        for m in moves.iter() {
+        for (i, m) in (1..).zip(moves.iter()) {
             assert_eq!(
                 board.possible_moves().len(),
                 *possible.next().unwrap(),
                 "testing move {}",
                 i
             );
-            assert_eq!(board.is_won(), false);
-            assert_eq!(board.is_blocked(), false);
+            assert!(!board.is_won());
+            assert!(!board.is_blocked());
+            let moves: Vec<Direction> = board.possible_moves().into_iter().copied().collect();
+            assert_eq!(board.iter_moves().collect::<Vec<_>>(), moves);
+            assert_eq!(board.move_count(), moves.len());
             assert_eq!(board.score(), i);
             let ret = board.next_move(*m);
-            assert_eq!(ret.is_ok(), true, "testing move {}", i);
+            assert!(ret.is_ok(), "testing move {}", i);
             board = ret.unwrap();
-            i += 1;
         }
         assert_eq!(board.possible_moves().len(), *possible.next().unwrap());
         // ensure we have checked all values
-        assert_eq!(possible.next().is_none(), true);
+        assert!(possible.next().is_none());
         // board is now won
-        assert_eq!(board.is_won(), true);
+        assert!(board.is_won());
         // score should be 25 (max board)
         assert_eq!(board.score(), board.cells);
         // there should be no possible moves;
-        assert_eq!(board.is_blocked(), true);
+        assert!(board.is_blocked());
+        // 25 is not one jump from 1, so the path is not a closed tour
+        assert!(!board.is_closed_tour());
+    }
+
+    #[test]
+    // Every cell of a newly started 5x5 board is reachable, and none are once
+    // the board is won.
+    fn reachable_cells() {
+        let mut board = Board::new(5);
+        assert_eq!(board.reachable(), 0);
+        board = board.start_at(0, 0).unwrap();
+        assert_eq!(board.reachable(), 24);
+        board = board.next_move(Direction::Right).unwrap();
+        assert_eq!(board.reachable(), 23);
+        assert!(!board.reachable_cells().contains(&(0, 0)));
+        assert!(!board.reachable_cells().contains(&(3, 0)));
+    }
+
+    #[test]
+    // The free neighbors of a cell drop as the cells around it are filled.
+    fn free_neighbors() {
+        let mut board = Board::new(5);
+        assert_eq!(board.free_neighbors(0, 0), 3);
+        assert_eq!(board.free_neighbors(2, 2), 4);
+        board = board.start_at(0, 0).unwrap();
+        assert_eq!(board.free_neighbors(0, 0), 3);
+        assert_eq!(board.free_neighbors(2, 2), 3);
+        assert_eq!(board.free_neighbors(3, 0), 2);
+        board = board.next_move(Direction::Right).unwrap();
+        assert_eq!(board.free_neighbors(0, 0), 2);
+        let degrees = board.degrees();
+        assert_eq!(degrees.len(), 25);
+        assert_eq!((degrees[0], degrees[3]), (0, 0));
+        for (x, y) in board.empty_cells() {
+            assert_eq!(degrees[y * 5 + x], board.free_neighbors(x, y));
+        }
+    }
+
+    #[test]
+    // The start position is the cell holding 1, and an unstarted board is not
+    // a closed tour.
+    fn closed_tour() {
+        let mut board = Board::new(5);
+        assert_eq!(board.start_position(), None);
+        assert!(!board.is_closed_tour());
+        board = board.start_at(2, 1).unwrap();
+        assert_eq!(board.start_position(), Some((2, 1)));
+    }
+
+    #[test]
+    // Clues from the 5x5 game above fix where the path must go: the game can
+    // be replayed through them, but no move may take a clue's value early or
+    // land on a clue with the wrong value.
+    fn clues() {
+        let clues = [(0, 0, 1), (3, 0, 2), (3, 1, 8), (4, 0, 25)];
+        let mut board = Board::with_clues(5, &clues).unwrap();
+        assert!(board.has_clues());
+        assert_eq!(
+            board.clues(),
+            vec![(0, 0, 1), (3, 0, 2), (4, 0, 25), (3, 1, 8)]
+        );
+        assert_eq!(board.clue_at(3, 1), 8);
+        assert_eq!(board.value_at(3, 1), 0);
+        assert_eq!(
+            board.start_at(1, 1).err(),
+            Some(BoardError::ClueConflict {
+                x: 1,
+                y: 1,
+                value: 1
+            })
+        );
+        board = board.start_at(0, 0).unwrap();
+        // 2 must go to (3, 0), so Down is not possible
+        assert_eq!(board.possible_moves(), vec![&Direction::Right]);
+        board = board.next_move(Direction::Right).unwrap();
+        board = board.next_move(Direction::Down).unwrap();
+        assert_eq!(board.value_at(3, 3), 3);
+        assert!(!Board::new(5).has_clues());
+    }
+
+    #[test]
+    // Clues that cannot belong to any game are rejected.
+    fn bad_clues() {
+        assert_eq!(
+            Board::with_clues(5, &[(0, 0, 1), (1, 1, 1)]).err(),
+            Some(BoardError::ValueUsed {
+                x: 1,
+                y: 1,
+                value: 1
+            })
+        );
+        assert_eq!(
+            Board::with_clues(5, &[(0, 0, 1), (0, 0, 2)]).err(),
+            Some(BoardError::NotEmpty { x: 0, y: 0 })
+        );
+        assert_eq!(
+            Board::with_clues(5, &[(0, 0, 26)]).err(),
+            Some(BoardError::ValueOutOfRange {
+                x: 0,
+                y: 0,
+                value: 26,
+                max: 25
+            })
+        );
+        assert_eq!(
+            Board::with_clues(5, &[(0, 0, 1), (1, 0, 2)]).err(),
+            Some(BoardError::BadAssignment { value: 2 })
+        );
+    }
+
+    #[test]
+    // The cell holding each value on the path is known as soon as it is
+    // placed, and forgotten when it is taken back.
+    fn position_of() {
+        let mut board = Board::with_clues(5, &[(3, 3, 3)])
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap();
+        assert_eq!(board.position_of(0), None);
+        assert_eq!(board.position_of(1), Some((0, 0)));
+        assert_eq!(board.position_of(3), None);
+        board
+            .apply_moves(&[Direction::Right, Direction::Down])
+            .unwrap();
+        assert_eq!(board.position_of(2), Some((3, 0)));
+        assert_eq!(board.position_of(3), Some((3, 3)));
+        board.undo().unwrap();
+        assert_eq!(board.position_of(3), None);
+        assert_eq!(board.current_position(), (3, 0));
+        let read: Board = board.to_string().parse().unwrap();
+        assert_eq!(read.position_of(2), Some((3, 0)));
+        assert_eq!(board.rotate90().position_of(2), Some((4, 3)));
+        assert_eq!(board.path(), vec![(0, 0), (3, 0)]);
+        assert!(Board::new(5).path().is_empty());
+    }
+
+    #[test]
+    // The bit set of filled cells follows the values through moves, undo
+    // and redo.
+    fn occupied() {
+        let filled = |board: &Board| -> Vec<usize> {
+            (0..board.values.len())
+                .filter(|&i| board.is_occupied(i))
+                .collect()
+        };
+        let mut board = Board::new(10).start_at(9, 9).unwrap();
+        board
+            .apply_moves(&[Direction::Up, Direction::UpLeft])
+            .unwrap();
+        assert_eq!(board.occupied().len(), 3);
+        assert_eq!(filled(&board), vec![47, 69, 99]);
+        board.undo().unwrap();
+        assert_eq!(filled(&board), vec![69, 99]);
+        board.redo().unwrap();
+        assert_eq!(filled(&board), vec![47, 69, 99]);
+        assert_eq!(board.reachable(), 97);
+    }
+
+    #[test]
+    // Every board reached through the API is valid, and a board whose
+    // values have been tampered with is not.
+    fn validate() {
+        let mut board = Board::with_clues(5, &[(3, 3, 3)])
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap();
+        assert_eq!(board.validate(), Ok(()));
+        board
+            .apply_moves(&[Direction::Right, Direction::Down])
+            .unwrap();
+        assert_eq!(board.validate(), Ok(()));
+        let mut gap = board.clone();
+        gap.values[3] = 0;
+        assert_eq!(
+            gap.validate(),
+            Err(BoardError::InvalidGrid {
+                reason: "value 2 is missing".to_string()
+            })
+        );
+        let mut far = board.clone();
+        far.values.swap(3, 4);
+        assert_eq!(far.validate(), Err(BoardError::BadAssignment { value: 2 }));
+        let mut clash = board.clone();
+        clash.values.swap(18, 24);
+        assert_eq!(
+            clash.validate(),
+            Err(BoardError::ClueConflict {
+                x: 4,
+                y: 4,
+                value: 3
+            })
+        );
+        let mut moved = board.clone();
+        moved.x = 0;
+        assert!(moved.validate().is_err());
+        assert_eq!(Board::new(5).validate(), Ok(()));
+    }
+
+    #[test]
+    // Moves carry the cell they land on and the value they place, both
+    // before they are made and once they have been.
+    fn moves() {
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let moves: Vec<Move> = board.moves().collect();
+        assert_eq!(
+            moves.iter().map(|m| m.dir).collect::<Vec<_>>(),
+            board.iter_moves().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            moves[0],
+            Move {
+                dir: Direction::Down,
+                to: Pos::new(0, 3),
+                value: 2
+            }
+        );
+        let board = board.next_move(Direction::Right).unwrap();
+        let board = board.next_move(Direction::Down).unwrap();
+        let played = board.played_moves();
+        assert_eq!(played.len(), 2);
+        assert_eq!(
+            (played[1].dir, played[1].to, played[1].value),
+            (Direction::Down, Pos::new(3, 3), 3)
+        );
+        assert!(Board::new(5).moves().next().is_none());
+    }
+
+    #[test]
+    // Cells are visited in row-major order with their values, passing over
+    // blocked cells, and the empty ones can be visited alone.
+    fn iter_cells() {
+        let board = Board::new(5).start_at(3, 0).unwrap();
+        assert_eq!(board.iter_cells().count(), 25);
+        assert_eq!(board.iter_cells().nth(3), Some(((3, 0), 1)));
+        assert_eq!(
+            board.iter_cells().map(|(_, v)| v as usize).sum::<usize>(),
+            1
+        );
+        let blocked = Board::new(5).block(1, 0).unwrap();
+        assert_eq!(blocked.iter_cells().count(), 24);
+        assert_eq!(blocked.iter_cells().nth(1), Some(((2, 0), 0)));
+        assert_eq!(blocked.empty_cells().count(), 24);
+        let played = blocked.start_at(0, 0).unwrap();
+        assert_eq!(
+            played.empty_cells().take(2).collect::<Vec<_>>(),
+            vec![(2, 0), (3, 0)]
+        );
+    }
+
+    #[test]
+    // A puzzle grid from the game in `clues()` becomes clues, with the run
+    // from 1 played; clues too far apart for the values between them are
+    // rejected.
+    fn from_puzzle() {
+        let text = " 1  .  .  2 25
+                     .  .  .  8  .
+                     .  .  .  .  .
+                     .  .  .  3  .
+                     .  .  .  .  .";
+        let board = Board::from_puzzle(text).unwrap();
+        assert_eq!(board.score(), 3);
+        assert_eq!(board.current_position(), (3, 3));
+        assert_eq!(board.clues().len(), 5);
+        assert_eq!(board.clue_at(3, 1), 8);
+        assert_eq!(board.value_at(3, 1), 0);
+        let mut undone = board.clone();
+        undone.undo().unwrap();
+        assert_eq!(undone.clue_at(3, 3), 3);
+        let unstarted = Board::from_puzzle(&text.replace(" 1 ", " . ")).unwrap();
+        assert!(!unstarted.is_started());
+        assert_eq!(
+            Board::with_clues(5, &[(0, 0, 1), (1, 0, 3)]).err(),
+            Some(BoardError::BadAssignment { value: 3 })
+        );
+        assert!(Board::with_clues(5, &[(0, 0, 1), (4, 4, 3)]).is_ok());
+        assert!(Board::new(5)
+            .block(2, 2)
+            .unwrap()
+            .add_clue(2, 2, 4)
+            .is_err());
+    }
+
+    #[test]
+    // Peeking at a move reports where it lands without making it, and
+    // with_move makes it on a copy.
+    fn peek_move() {
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        assert_eq!(board.peek_move(Direction::Right), Some((3, 0)));
+        assert_eq!(board.peek_move(Direction::Left), None);
+        assert_eq!(Board::new(5).peek_move(Direction::Right), None);
+        let moved = board.with_move(Direction::Right).unwrap();
+        assert_eq!(moved, board.next_move(Direction::Right).unwrap());
+        assert_eq!(moved.current_position(), (3, 0));
+        assert_eq!(board.score(), 1);
+        assert_eq!(moved.peek_move(Direction::Left), None);
+        assert!(board.with_move(Direction::Up).is_err());
+    }
+
+    #[test]
+    // A reset board is the board it started as, clues and all, and a
+    // restarted board matches one started afresh.
+    fn reset() {
+        let fresh = Board::with_clues(6, &[(5, 5, 36)]).unwrap();
+        let mut board = fresh.start_at(0, 0).unwrap();
+        board
+            .apply_moves(&[Direction::Right, Direction::Down, Direction::Left])
+            .unwrap();
+        board.undo().unwrap();
+        board.reset();
+        assert_eq!(board, fresh);
+        assert_eq!(board.degrees(), fresh.degrees());
+        assert_eq!(board.zobrist(), fresh.zobrist());
+        assert!(board.history().is_empty());
+        assert_eq!(board.redo(), Err(BoardError::NothingToRedo));
+        board.restart_at(2, 1).unwrap();
+        let started = fresh.start_at(2, 1).unwrap();
+        assert_eq!(board, started);
+        assert_eq!(board.zobrist(), started.zobrist());
+        assert_eq!(board.path(), vec![(2, 1)]);
+        assert!(board.restart_at(5, 5).is_err());
+        assert!(!board.is_started());
+    }
+
+    #[test]
+    // Undoing moves restores the board to the state before them, down to an
+    // unstarted board.
+    fn undo() {
+        let mut board = Board::new(5);
+        assert_eq!(board.undo(), Err(BoardError::NotStarted));
+        let start = board.start_at(0, 0).unwrap();
+        let mut board = start.next_move(Direction::Right).unwrap();
+        board = board.next_move(Direction::Down).unwrap();
+        board.undo().unwrap();
+        assert_eq!(board.current_position(), (3, 0));
+        assert_eq!(board.score(), 2);
+        assert_eq!(board.value_at(3, 3), 0);
+        assert_eq!(board.free_neighbors(3, 3), 2);
+        board.undo().unwrap();
+        assert_eq!(board.values, start.values);
+        assert_eq!(board.degrees, start.degrees);
+        assert_eq!(board.current_position(), (0, 0));
+        board.undo().unwrap();
+        assert!(!board.is_started());
+        assert_eq!(board.score(), 0);
+        assert_eq!(board.degrees, Board::new(5).degrees);
+    }
+
+    #[test]
+    // The history records each move, undo and redo step back and forth
+    // through it, and a new move discards the moves that could be redone.
+    fn history_redo() {
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        assert_eq!(board.redo(), Err(BoardError::NothingToRedo));
+        board = board.next_move(Direction::Right).unwrap();
+        board = board.next_move(Direction::Down).unwrap();
+        let done = board.clone();
+        assert_eq!(board.history(), &[Direction::Right, Direction::Down]);
+        board.undo().unwrap();
+        board.undo().unwrap();
+        assert!(board.history().is_empty());
+        board.redo().unwrap();
+        assert_eq!(board.history(), &[Direction::Right]);
+        board.redo().unwrap();
+        assert_eq!(board.values, done.values);
+        assert_eq!(board.current_position(), (3, 3));
+        assert_eq!(board.redo(), Err(BoardError::NothingToRedo));
+        board.undo().unwrap();
+        board = board.next_move(Direction::DownLeft).unwrap();
+        assert_eq!(board.history(), &[Direction::Right, Direction::DownLeft]);
+        assert_eq!(board.redo(), Err(BoardError::NothingToRedo));
+    }
+
+    #[test]
+    // A sequence of moves is applied in full, or not at all.
+    fn apply_moves() {
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        board
+            .apply_moves(&[Direction::Right, Direction::Down])
+            .unwrap();
+        assert_eq!(board.current_position(), (3, 3));
+        let before = board.clone();
+        let err = board
+            .apply_moves(&[Direction::Left, Direction::Up])
+            .unwrap_err();
+        assert_eq!(
+            err.kind(),
+            &BoardError::BadDirection {
+                dir: Direction::Up,
+                x: 0,
+                y: 3
+            }
+        );
+        assert_eq!(
+            err.context(),
+            Some(&ErrorContext {
+                position: Some((0, 3)),
+                score: 4,
+                attempted: Some(Direction::Up),
+                step: Some(1),
+            })
+        );
+        assert_eq!(
+            err.to_string(),
+            "Moving in direction 'Up' from [0,3] is invalid \
+             (move 2, score 4 at [0,3], trying Up)"
+        );
+        assert_eq!(board.values, before.values);
+        assert_eq!(board.history(), before.history());
+        assert_eq!(board.current_position(), (3, 3));
+    }
+
+    #[test]
+    // The accessors report the state of the board, and `get` rejects
+    // locations off the board.
+    fn accessors() {
+        let mut board = Board::new(6);
+        assert_eq!(board.cells(), 36);
+        assert_eq!(board.last_value(), 0);
+        board = board.start_at(2, 1).unwrap();
+        board = board.next_move(Direction::Down).unwrap();
+        assert_eq!(board.last_value(), 2);
+        assert_eq!(board.get(2, 4), Some(2));
+        assert_eq!(board.get(0, 0), Some(0));
+        assert_eq!(board.get(6, 0), None);
+        assert_eq!(board.get(0, 6), None);
+        assert_eq!((board.score(), board.remaining()), (2, 34));
+        assert!(!board.is_full());
+        board.undo().unwrap();
+        assert_eq!(board.remaining(), 35);
+        let text = " 1 24 14  2 25
+                   16 21  5  8 20
+                   13 10 18 23 11
+                    4  7 15  3  6
+                   17 22 12  9 19";
+        let won: Board = text.parse().unwrap();
+        assert!(won.is_full());
+        assert_eq!(won.remaining(), 0);
+    }
+
+    #[test]
+    // Indexing reads cells, and placing values follows the rules of a move.
+    fn index_and_place() {
+        let mut board = Board::new(5);
+        board.place(0, 0).unwrap();
+        board.place(3, 0).unwrap();
+        assert_eq!(board[(0, 0)], 1);
+        assert_eq!(board[(3, 0)], 2);
+        assert_eq!(board[(4, 4)], 0);
+        assert_eq!(board.history(), &[Direction::Right]);
+        assert_eq!(
+            board.place(4, 4),
+            Err(BoardError::BadAssignment { value: 3 })
+        );
+        assert_eq!(board.score(), 2);
+    }
+
+    #[test]
+    #[should_panic]
+    // Indexing off the board panics rather than reading another row.
+    fn index_off_board() {
+        let board = Board::new(5);
+        let _ = board[(5, 0)];
+    }
+
+    #[test]
+    // A board prints as the aligned grid in the documentation, with empty
+    // cells shown as dots.
+    fn display() {
+        let moves = [
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+            Direction::UpRight,
+            Direction::DownRight,
+            Direction::Left,
+            Direction::UpRight,
+            Direction::Down,
+            Direction::UpLeft,
+            Direction::Right,
+            Direction::DownLeft,
+            Direction::UpLeft,
+            Direction::UpRight,
+            Direction::Down,
+            Direction::UpLeft,
+            Direction::Down,
+            Direction::UpRight,
+            Direction::DownRight,
+            Direction::Up,
+            Direction::Left,
+            Direction::Down,
+            Direction::UpRight,
+            Direction::UpLeft,
+            Direction::Right,
+        ];
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        board.apply_moves(&moves[..1]).unwrap();
+        assert_eq!(
+            board.to_string(),
+            " 1  .  .  2  .\n .  .  .  .  .\n .  .  .  .  .\n .  .  .  .  .\n .  .  .  .  ."
+        );
+        board.apply_moves(&moves[1..]).unwrap();
+        let expected = [
+            " 1 24 14  2 25",
+            "16 21  5  8 20",
+            "13 10 18 23 11",
+            " 4  7 15  3  6",
+            "17 22 12  9 19",
+        ];
+        assert_eq!(board.to_string(), expected.join("\n"));
+        let board = Board::new(10).start_at(9, 9).unwrap();
+        assert!(board.to_string().ends_with("  .   1"));
+    }
+
+    #[test]
+    // A grid is read back into the board it was printed from, and grids that
+    // are not a legal path are rejected.
+    fn from_str() {
+        let text = " 1 24 14  2 25
+                   16 21  5  8 20
+                   13 10 18 23 11
+                    4  7 15  3  6
+                   17 22 12  9 19";
+        let board: Board = text.parse().unwrap();
+        assert!(board.is_won());
+        assert_eq!(board.current_position(), (4, 0));
+        assert_eq!(board.history().len(), 24);
+        assert_eq!(board.history()[0], Direction::Right);
+        let mut partial = Board::new(6).start_at(1, 1).unwrap();
+        partial = partial.next_move(Direction::DownRight).unwrap();
+        let read: Board = partial.to_string().parse().unwrap();
+        assert_eq!(read.to_string(), partial.to_string());
+        assert_eq!(read.history(), partial.history());
+        let empty: Board = "0 0 0 0 0\n".repeat(5).parse().unwrap();
+        assert!(!empty.is_started());
+
+        assert_eq!(
+            "1 2 . . .\n".repeat(5).parse::<Board>().err(),
+            Some(BoardError::ValueUsed {
+                x: 0,
+                y: 1,
+                value: 1
+            })
+        );
+        assert_eq!(
+            "1 . . 3 .\n. . . . .\n. . . . .\n. . . . .\n. . . . ."
+                .parse::<Board>()
+                .err(),
+            Some(BoardError::InvalidGrid {
+                reason: "value 2 is missing".to_string()
+            })
+        );
+        assert_eq!(
+            "1 2 . . .\n. . . . .\n. . . . .\n. . . . .\n. . . . ."
+                .parse::<Board>()
+                .err(),
+            Some(BoardError::BadAssignment { value: 2 })
+        );
+        assert!("1 . .\n. . .\n. . .".parse::<Board>().is_err());
+        assert!("1 . . . x\n".repeat(5).parse::<Board>().is_err());
+    }
+
+    #[test]
+    // Errors can be matched on by kind, and are standard errors with
+    // messages naming the cells involved.
+    fn errors() {
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let err = board.next_move(Direction::Up).unwrap_err();
+        assert!(matches!(
+            err,
+            BoardError::BadDirection {
+                dir: Direction::Up,
+                x: 0,
+                y: 0
+            }
+        ));
+        let err: Box<dyn std::error::Error> = Box::new(err);
+        assert_eq!(
+            err.to_string(),
+            "Moving in direction 'Up' from [0,0] is invalid"
+        );
+        assert_eq!(
+            Board::try_with_dimensions(3, 5).unwrap_err().to_string(),
+            "Board size 3x5 is not supported"
+        );
+    }
+
+    #[test]
+    // A diff lists the cells that changed between two boards, and reverses
+    // when the boards are swapped.
+    fn diff() {
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let board = start.next_move(Direction::Right).unwrap();
+        let after = board.next_move(Direction::DownLeft).unwrap();
+        assert!(board.diff(&board).is_empty());
+        assert_eq!(
+            start.diff(&after),
+            vec![
+                CellChange {
+                    at: Pos::new(3, 0),
+                    from: 0,
+                    to: 2
+                },
+                CellChange {
+                    at: Pos::new(1, 2),
+                    from: 0,
+                    to: 3
+                },
+            ]
+        );
+        let back: Vec<CellChange> = after
+            .diff(&start)
+            .iter()
+            .map(|c| CellChange {
+                from: c.to,
+                to: c.from,
+                ..*c
+            })
+            .collect();
+        assert_eq!(back, start.diff(&after));
+        let wide = Board::with_dimensions(6, 5).start_at(5, 4).unwrap();
+        assert_eq!(
+            Board::new(5).diff(&wide),
+            vec![CellChange {
+                at: Pos::new(5, 4),
+                from: 0,
+                to: 1
+            }]
+        );
+    }
+
+    #[test]
+    // A board is rebuilt from its raw values, with its history, and values
+    // that are not a legal path or do not fill the board are rejected.
+    fn from_values() {
+        use std::convert::TryFrom;
+        let mut board = Board::new(5).start_at(2, 4).unwrap();
+        board
+            .apply_moves(&[Direction::Up, Direction::DownRight])
+            .unwrap();
+        let values: Vec<u16> = board.iter_cells().map(|(_, value)| value).collect();
+        let read = Board::from_values(5, &values).unwrap();
+        assert_eq!(read, board);
+        assert_eq!(read.history(), board.history());
+        assert_eq!(read.current_position(), (4, 3));
+        let bytes: Vec<u8> = values.iter().map(|&value| value as u8).collect();
+        assert_eq!(Board::try_from((5, bytes)).unwrap(), board);
+        assert_eq!(Board::try_from((5, values.clone())).unwrap(), board);
+        assert!(!Board::from_values(5, &[0; 25]).unwrap().is_started());
+
+        assert_eq!(
+            Board::from_values(6, &values).err(),
+            Some(BoardError::InvalidGrid {
+                reason: "25 values, not 36".to_string()
+            })
+        );
+        assert_eq!(
+            Board::from_values(4, &[0; 16]).err(),
+            Some(BoardError::BadSize {
+                width: 4,
+                height: 4
+            })
+        );
+        let mut gap = values;
+        gap[7] = 0;
+        assert!(Board::try_from((5, gap)).is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    // A board survives a round trip through serde, and data that does not
+    // describe a legal game is rejected.
+    fn serde_round_trip() {
+        let mut board = Board::with_clues(5, &[(4, 4, 25)])
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap();
+        board
+            .apply_moves(&[Direction::Right, Direction::Down])
+            .unwrap();
+        board.undo().unwrap();
+        let json = serde_json::to_string(&board).unwrap();
+        let mut read: Board = serde_json::from_str(&json).unwrap();
+        assert_eq!(read.to_string(), board.to_string());
+        assert_eq!(read.clues(), board.clues());
+        read.redo().unwrap();
+        assert_eq!(read.current_position(), (3, 3));
+
+        let bad = json.replace("\"history\":[\"Right\"]", "\"history\":[\"Down\"]");
+        assert!(serde_json::from_str::<Board>(&bad).is_err());
+    }
+
+    #[test]
+    // Boards reached by the same moves are equal and hash alike, whatever
+    // their history; boards that differ in a value or clue are not.
+    fn equality() {
+        use std::collections::HashSet;
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let a = start.next_move(Direction::Right).unwrap();
+        let mut b = a.next_move(Direction::Down).unwrap();
+        b.undo().unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, start);
+        assert_ne!(
+            a,
+            Board::with_clues(5, &[(4, 4, 25)])
+                .unwrap()
+                .start_at(0, 0)
+                .unwrap()
+                .next_move(Direction::Right)
+                .unwrap()
+        );
+        let mut unstarted = start.clone();
+        unstarted.undo().unwrap();
+        assert_eq!(unstarted, Board::new(5));
+        let set: HashSet<Board> = vec![a.clone(), b, start].into_iter().collect();
+        assert_eq!(set.len(), 2);
+        assert!(set.contains(&a));
+    }
+
+    #[test]
+    // The Zobrist hash matches for transposed move orders, differs for
+    // different states, and is restored by undo.
+    fn zobrist() {
+        use Direction::*;
+        let start = Board::new(6).start_at(0, 0).unwrap();
+        assert_ne!(start.zobrist(), Board::new(6).zobrist());
+        let mut a = start.clone();
+        a.apply_moves(&[Down, DownRight, Right, UpLeft, Up, DownRight])
+            .unwrap();
+        let mut b = start.clone();
+        b.apply_moves(&[Right, Down, Left, DownRight, Right, Up])
+            .unwrap();
+        assert_eq!(a.zobrist(), b.zobrist());
+        let before = b.zobrist();
+        b.undo().unwrap();
+        assert_ne!(b.zobrist(), before);
+        b.redo().unwrap();
+        assert_eq!(b.zobrist(), before);
+        for _ in 0..7 {
+            b.undo().unwrap();
+        }
+        assert_eq!(b.zobrist(), Board::new(6).zobrist());
+    }
+
+    #[test]
+    // Moves land where the board's rule puts them, and the rule is kept by
+    // transforms and checked against the clues when it is changed.
+    fn move_rule() {
+        let king = MoveRule::Jump {
+            straight: 1,
+            diagonal: 1,
+        };
+        assert_eq!(Board::new(5).rule(), MoveRule::default());
+        let board = Board::new(5).with_rule(king).unwrap();
+        assert_eq!(board.rule(), king);
+        assert_eq!(board.free_neighbors(0, 0), 3);
+        assert_eq!(board.free_neighbors(2, 2), 8);
+        let board = board.start_at(0, 0).unwrap();
+        assert_eq!(board.peek_move(Direction::Right), Some((1, 0)));
+        assert_eq!(board.move_count(), 3);
+        assert_eq!(board.rotate90().rule(), king);
+        assert_ne!(board, Board::new(5).start_at(0, 0).unwrap());
+        assert_eq!(board.with_rule(king), Err(BoardError::AlreadyStarted));
+        let rook = MoveRule::Jump {
+            straight: 1,
+            diagonal: 0,
+        };
+        assert_eq!(
+            Board::new(5).with_rule(rook).unwrap().free_neighbors(2, 2),
+            4
+        );
+        let clued = Board::new(5)
+            .with_rule(king)
+            .and_then(|b| b.add_clues(&[(0, 0, 1), (1, 1, 2)]))
+            .unwrap();
+        assert!(clued.with_rule(king).is_ok());
+        assert_eq!(
+            clued.with_rule(rook).err(),
+            Some(BoardError::BadAssignment { value: 2 })
+        );
+        assert_eq!(king.to_string(), "straight 1, diagonal 1");
+    }
+
+    #[test]
+    // Under the knight's rule each direction is one of the knight's moves,
+    // and the image of a knight's game is a knight's game.
+    fn knight() {
+        let board = Board::new(5).with_rule(MoveRule::Knight).unwrap();
+        assert_eq!(board.free_neighbors(0, 0), 2);
+        assert_eq!(board.free_neighbors(2, 2), 8);
+        let mut board = board.start_at(0, 0).unwrap();
+        assert_eq!(board.peek_move(Direction::Down), Some((1, 2)));
+        assert_eq!(board.peek_move(Direction::DownRight), Some((2, 1)));
+        board
+            .apply_moves(&[Direction::Down, Direction::Right, Direction::UpLeft])
+            .unwrap();
+        assert_eq!(board.current_position(), (1, 0));
+        for &s in Symmetry::all().iter() {
+            let image = board.transform(s);
+            assert_eq!(image.rule(), MoveRule::Knight);
+            assert_eq!(image.transform(s.inverse()), board);
+        }
+        assert_eq!(MoveRule::Knight.to_string(), "knight");
+    }
+
+    #[test]
+    // The table of neighbors agrees with working out each jump, whatever
+    // the rule, wrap mode and blocked cells, and is shared between copies.
+    fn neighbors() {
+        let boards = [
+            Board::with_dimensions(7, 5),
+            Board::new(6).with_rule(MoveRule::Knight).unwrap(),
+            Board::new(5).with_wrap(true).unwrap(),
+            Board::new(6).block(3, 3).unwrap().block(0, 3).unwrap(),
+            Board::new(6).with_wrap(true).unwrap().block(2, 2).unwrap(),
+        ];
+        for board in &boards {
+            for (x, y) in (0..board.height).flat_map(|y| (0..board.width).map(move |x| (x, y))) {
+                for &dir in Direction::iterator() {
+                    assert_eq!(board.target(x, y, dir), board.jump(x, y, dir));
+                }
+            }
+        }
+        let board = Board::new(5);
+        let next = board.start_at(0, 0).unwrap();
+        assert!(Arc::ptr_eq(&board.neighbors, &next.neighbors));
+        assert_eq!(board.target(5, 0, Direction::Left), None);
+    }
+
+    #[test]
+    // Jumps off one edge of a board that wraps come back on at the opposite
+    // edge, and the wrap mode is kept by transforms.
+    fn wrap() {
+        assert!(!Board::new(5).wraps());
+        let board = Board::new(5).with_wrap(true).unwrap();
+        assert!(board.wraps());
+        for i in 0..25 {
+            assert_eq!(board.free_neighbors(i % 5, i / 5), 8);
+        }
+        let board = board.start_at(0, 0).unwrap();
+        assert_eq!(board.peek_move(Direction::Left), Some((2, 0)));
+        assert_eq!(board.peek_move(Direction::UpLeft), Some((3, 3)));
+        assert_eq!(board.move_count(), 8);
+        assert!(board.flip_h().wraps());
+        let moved = board.next_move(Direction::UpLeft).unwrap();
+        assert_eq!(moved.rotate90().rotate90().rotate180(), moved);
+        assert_ne!(board, Board::new(5).start_at(0, 0).unwrap());
+        assert_eq!(board.with_wrap(false), Err(BoardError::AlreadyStarted));
+        let board = Board::new(6)
+            .with_wrap(true)
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap();
+        assert_eq!(board.peek_move(Direction::Left), Some((3, 0)));
+        assert_eq!(board.peek_move(Direction::Right), Some((3, 0)));
+        let straight = MoveRule::Jump {
+            straight: 5,
+            diagonal: 2,
+        };
+        let board = Board::new(5).with_rule(straight).unwrap();
+        let board = board.with_wrap(true).unwrap();
+        assert_eq!(board.target(0, 0, Direction::Right), None);
+        assert_eq!(board.free_neighbors(0, 0), 4);
+    }
+
+    #[test]
+    // Transforms move every value, the position and the history to their
+    // images, and compose like the symmetries they apply.
+    fn transforms() {
+        let mut board = Board::with_clues(5, &[(4, 4, 25)])
+            .unwrap()
+            .start_at(0, 1)
+            .unwrap();
+        board
+            .apply_moves(&[Direction::Right, Direction::DownLeft])
+            .unwrap();
+        let turned = board.rotate90();
+        for y in 0..5 {
+            for x in 0..5 {
+                assert_eq!(turned[(4 - y, x)], board[(x, y)]);
+            }
+        }
+        assert_eq!(turned.current_position(), (1, 1));
+        assert_eq!(turned.clues(), vec![(0, 4, 25)]);
+        assert_eq!(turned.history(), &[Direction::Down, Direction::UpLeft]);
+        assert_eq!(turned.rotate90(), board.rotate180());
+        assert_eq!(board.flip_h().flip_h(), board);
+        assert_eq!(board.flip_h().flip_v(), board.rotate180());
+        assert_eq!(Board::new(5).rotate90(), Board::new(5));
+    }
+
+    #[test]
+    // Every image of a board has the same canonical form, which is the
+    // smallest of them.
+    fn canonical() {
+        let mut board = Board::new(6).start_at(2, 1).unwrap();
+        board
+            .apply_moves(&[Direction::Down, Direction::UpRight])
+            .unwrap();
+        let canonical = board.canonical();
+        for &s in Symmetry::all().iter() {
+            let image = board.transform(s);
+            assert_eq!(image.canonical(), canonical);
+            assert!(canonical.values <= image.values);
+        }
+        assert_ne!(canonical, board);
+        assert_eq!(canonical.history().len(), 2);
+    }
+
+    #[test]
+    // A rectangular board keeps its moves inside both dimensions, prints
+    // and parses with its own shape, and turns into its transpose.
+    fn rectangular() {
+        let mut board = Board::with_dimensions(8, 12).start_at(7, 11).unwrap();
+        assert_eq!((board.width(), board.height(), board.cells()), (8, 12, 96));
+        assert!(!board.is_square());
+        assert_eq!(board.target(7, 2, Direction::Right), None);
+        assert_eq!(board.target(7, 8, Direction::Down), Some((7, 11)));
+        assert_eq!(board.free_neighbors(7, 11), 3);
+        assert!(board.start_at(8, 0).is_err());
+        board = board.next_move(Direction::Up).unwrap();
+        assert_eq!(board.to_string().lines().count(), 12);
+        assert_eq!(board.to_string().parse::<Board>().unwrap(), board);
+        let turned = board.rotate90();
+        assert_eq!((turned.width(), turned.height()), (12, 8));
+        assert_eq!(turned.current_position(), (3, 7));
+        assert_eq!(turned.rotate90().rotate90().rotate90(), board);
+        assert_eq!(board.symmetries().len(), 4);
+        assert_eq!(board.canonical().canonical(), board.canonical());
+    }
+
+    #[test]
+    // Blocked cells are never entered, are left out of the count of cells to
+    // fill, and print, parse and transform with the rest of the board.
+    fn blocked_cells() {
+        let board = Board::new(5).block(3, 0).unwrap().block(3, 0).unwrap();
+        assert_eq!(board.cells(), 24);
+        assert!(board.blocked_at(3, 0));
+        assert_eq!(board.blocked(), vec![(3, 0)]);
+        assert_eq!(board.target(0, 0, Direction::Right), None);
+        assert_eq!(board.free_neighbors(0, 0), 2);
+        assert_eq!(
+            board.start_at(3, 0).err(),
+            Some(BoardError::NotEmpty { x: 3, y: 0 })
+        );
+        assert!(board.block(5, 0).is_err());
+        let started = board.start_at(0, 0).unwrap();
+        assert_eq!(started.block(1, 1).err(), Some(BoardError::AlreadyStarted));
+        assert!(!started.possible_moves().contains(&&Direction::Right));
+        assert!(started.to_string().starts_with(" 1  .  .  #  .\n"));
+        assert_eq!(started.to_string().parse::<Board>().unwrap(), started);
+        assert_eq!(board.flip_h().blocked(), vec![(1, 0)]);
+        assert_ne!(board, Board::new(5));
+        let clued = Board::with_clues(5, &[(3, 0, 25)]).unwrap();
+        assert!(clued.block(3, 0).is_err());
+        assert!(clued.block(1, 1).is_err());
+    }
+
+    #[test]
+    // Boards larger than 16x16 hold values past 255, and sizes out of range
+    // are rejected rather than clamped.
+    fn large_boards() {
+        let mut board = Board::new(25).start_at(24, 24).unwrap();
+        assert_eq!(board.cells(), 625);
+        board = board.next_move(Direction::Up).unwrap();
+        assert_eq!(board.value_at(24, 21), 2);
+        assert_eq!(
+            Board::try_with_dimensions(0, 5).err(),
+            Some(BoardError::BadSize {
+                width: 0,
+                height: 5
+            })
+        );
+        assert!(Board::try_with_dimensions(MAX_SIZE, MAX_SIZE + 1).is_err());
+        assert!("1 . .\n. . .\n. . .".parse::<Board>().is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    // Creating a board too large to number its cells panics.
+    fn new_too_large() {
+        Board::new(MAX_SIZE + 1);
     }
 
     #[test]
     fn bad_starting_location() {
         const MAX: usize = 5;
-        let mut board = Board::new(MAX);
-        assert_eq!(board.is_started(), false);
+        let board = Board::new(MAX);
+        assert!(!board.is_started());
         let ret = board.start_at(MAX + 1, 1);
-        assert_eq!(ret.is_err(), true);
+        assert!(ret.is_err());
         assert_eq!(
             ret.err(),
             Some(BoardError::IndexOutOfRange {
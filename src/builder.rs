@@ -0,0 +1,142 @@
+//! A builder for boards that need more than a size to set up.
+//!
+//! A board with blocked cells, clues and a starting cell can be made by
+//! chaining `Board::block()`, `Board::add_clue()` and `Board::start_at()`,
+//! but each step must be made in the right order and checked on its own.
+//! [`BoardBuilder`] collects the whole setup first and makes the board in
+//! one step, returning the first error found.
+
+use crate::board::{Board, BoardError, MoveRule};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// BoardBuilder collects the setup of a board for [`BoardBuilder::build`].
+pub struct BoardBuilder {
+    width: usize,
+    height: usize,
+    blocked: Vec<(usize, usize)>,
+    clues: Vec<(usize, usize, u16)>,
+    start: Option<(usize, usize)>,
+    rule: MoveRule,
+    wrap: bool,
+}
+
+impl BoardBuilder {
+    /// Start building a board with the dimensions `size` x `size`.
+    pub fn new(size: usize) -> Self {
+        BoardBuilder {
+            width: size,
+            height: size,
+            blocked: Vec::new(),
+            clues: Vec::new(),
+            start: None,
+            rule: MoveRule::default(),
+            wrap: false,
+        }
+    }
+
+    /// Make the board `width` cells wide and `height` cells high.
+    pub fn dimensions(mut self, width: usize, height: usize) -> Self {
+        self.width = width;
+        self.height = height;
+        self
+    }
+
+    /// Block the cell at (x, y), so that the path never visits it.
+    pub fn block(mut self, x: usize, y: usize) -> Self {
+        self.blocked.push((x, y));
+        self
+    }
+
+    /// Fix `value` as a clue at (x, y).
+    pub fn clue(mut self, x: usize, y: usize, value: u16) -> Self {
+        self.clues.push((x, y, value));
+        self
+    }
+
+    /// Make the moves of the board follow `rule`.
+    pub fn rule(mut self, rule: MoveRule) -> Self {
+        self.rule = rule;
+        self
+    }
+
+    /// Make jumps off one edge of the board come back on at the opposite
+    /// edge if `wrap` is `true`.
+    pub fn wrap(mut self, wrap: bool) -> Self {
+        self.wrap = wrap;
+        self
+    }
+
+    /// Start the board by placing a 1 at (x, y).
+    pub fn start_at(mut self, x: usize, y: usize) -> Self {
+        self.start = Some((x, y));
+        self
+    }
+
+    /// Make the board, setting its move rule and wrap mode and blocking its
+    /// cells before adding its clues and then starting it, and check the
+    /// result with `Board::validate()`. Returns the first error any step
+    /// raises.
+    pub fn build(&self) -> Result<Board, BoardError> {
+        let mut board = self
+            .blocked
+            .iter()
+            .try_fold(
+                Board::try_with_dimensions(self.width, self.height)?
+                    .with_rule(self.rule)?
+                    .with_wrap(self.wrap)?,
+                |board, &(x, y)| board.block(x, y),
+            )?
+            .add_clues(&self.clues)?;
+        if let Some((x, y)) = self.start {
+            board = board.start_at(x, y)?;
+        }
+        board.validate()?;
+        Ok(board)
+    }
+}
+
+impl Board {
+    /// Start building a board with the dimensions `size` x `size`; see
+    /// [`BoardBuilder`].
+    pub fn builder(size: usize) -> BoardBuilder {
+        BoardBuilder::new(size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A built board matches the one made step by step, and a setup that
+    // cannot be made reports the step that failed.
+    fn build() {
+        let built = Board::builder(6)
+            .dimensions(6, 8)
+            .clue(5, 7, 47)
+            .block(2, 2)
+            .start_at(0, 0)
+            .build()
+            .unwrap();
+        let made = Board::with_dimensions(6, 8)
+            .block(2, 2)
+            .and_then(|b| b.add_clue(5, 7, 47))
+            .and_then(|b| b.start_at(0, 0))
+            .unwrap();
+        assert_eq!(built, made);
+        assert_eq!(built.cells(), 47);
+        assert_eq!(
+            Board::builder(5).block(1, 1).clue(1, 1, 4).build().err(),
+            Some(BoardError::NotEmpty { x: 1, y: 1 })
+        );
+        assert!(Board::builder(3).build().is_err());
+        assert!(!Board::builder(5).build().unwrap().is_started());
+        let king = MoveRule::Jump {
+            straight: 1,
+            diagonal: 1,
+        };
+        let built = Board::builder(5).rule(king).clue(1, 1, 2).start_at(0, 0);
+        assert_eq!(built.build().unwrap().rule(), king);
+        assert!(built.wrap(true).build().unwrap().wraps());
+    }
+}
@@ -0,0 +1,222 @@
+//! DIMACS CNF export, for handing hard boards to an external SAT solver.
+//!
+//! The puzzle is encoded with one variable for every (cell, value) pair. The
+//! cells that are not blocked are numbered from 0 in row-major order, and the
+//! variable for cell `c` holding value `v` is numbered `c * cells + v`, where
+//! `cells` is the number of cells that are not blocked. Variables above
+//! `cells * cells` are auxiliary. The clauses say that:
+//!
+//! * every cell holds at least one value,
+//! * every value is held by at most one cell, which together with the above
+//!   means each cell holds exactly one value,
+//! * the cell holding value `v` is one jump from the cell holding `v + 1`, and
+//! * the cells already filled on the board, and its clues, keep their values.
+
+use crate::board::{Board, BoardError, Direction};
+use std::fmt::Write;
+
+impl Board {
+    /// Encode the problem of completing this board as a CNF formula in
+    /// DIMACS format. A satisfying assignment can be turned back into a
+    /// completed board with [`Board::from_cnf_model`].
+    pub fn to_cnf(&self) -> String {
+        let width = self.width();
+        let cells = self.cells();
+        let (open, index) = self.open_cells();
+        let var = |cell: usize, value: usize| (cell * cells + value) as i64;
+        let mut next_aux = var(cells - 1, cells) + 1;
+        let mut clauses: Vec<Vec<i64>> = Vec::new();
+
+        for cell in 0..cells {
+            clauses.push((1..=cells).map(|value| var(cell, value)).collect());
+        }
+        for value in 1..=cells {
+            let lits: Vec<i64> = (0..cells).map(|cell| var(cell, value)).collect();
+            at_most_one(&lits, &mut next_aux, &mut clauses);
+        }
+        for (cell, &(x, y)) in open.iter().enumerate() {
+            let neighbors: Vec<usize> = Direction::iterator()
+                .filter_map(|&dir| self.target(x, y, dir))
+                .map(|(x, y)| index[y * width + x])
+                .collect();
+            for value in 1..cells {
+                let mut clause = vec![-var(cell, value)];
+                clause.extend(neighbors.iter().map(|&n| var(n, value + 1)));
+                clauses.push(clause);
+            }
+            for value in [self.value_at(x, y), self.clue_at(x, y)] {
+                if value > 0 {
+                    clauses.push(vec![var(cell, value as usize)]);
+                }
+            }
+        }
+
+        let mut out = String::new();
+        writeln!(out, "c square100 {}x{}", width, self.height()).unwrap();
+        writeln!(out, "p cnf {} {}", next_aux - 1, clauses.len()).unwrap();
+        for clause in clauses {
+            for lit in clause {
+                write!(out, "{} ", lit).unwrap();
+            }
+            out.push_str("0\n");
+        }
+        out
+    }
+
+    /// Decode a satisfying assignment of the formula returned by
+    /// [`Board::to_cnf`] into a completed board of the same size. `model`
+    /// lists the literals of the assignment as printed by a SAT solver;
+    /// negative and auxiliary literals are ignored. Returns an error if the
+    /// assignment does not describe a legal sequence of jumps.
+    pub fn from_cnf_model(&self, model: &[i64]) -> Result<Board, BoardError> {
+        let cells = self.cells();
+        let (open, _) = self.open_cells();
+        let mut positions = vec![None; cells + 1];
+        for &lit in model {
+            if lit > 0 && (lit as usize) <= cells * cells {
+                let (cell, value) = ((lit as usize - 1) / cells, (lit as usize - 1) % cells + 1);
+                positions[value] = Some(open[cell]);
+            }
+        }
+
+        let mut prev = positions[1].ok_or(BoardError::BadAssignment { value: 1 })?;
+        let mut board = self
+            .blocked()
+            .iter()
+            .try_fold(
+                Board::with_dimensions(self.width(), self.height())
+                    .with_rule(self.rule())?
+                    .with_wrap(self.wraps())?,
+                |board, &(x, y)| board.block(x, y),
+            )?
+            .add_clues(&self.clues())?
+            .start_at(prev.0, prev.1)?;
+        for (value, &position) in positions.iter().enumerate().skip(2) {
+            let next = position.ok_or(BoardError::BadAssignment { value })?;
+            let dir = Direction::iterator()
+                .find(|&&dir| board.target(prev.0, prev.1, dir) == Some(next))
+                .ok_or(BoardError::BadAssignment { value })?;
+            board = board.next_move(*dir)?;
+            prev = next;
+        }
+        Ok(board)
+    }
+}
+
+/// Add clauses to `clauses` stating that at most one of `lits` is true, using
+/// the sequential counter encoding. Auxiliary variables are allocated from
+/// `next_aux`.
+fn at_most_one(lits: &[i64], next_aux: &mut i64, clauses: &mut Vec<Vec<i64>>) {
+    let n = lits.len();
+    if n < 2 {
+        return;
+    }
+    // s[i] is true if any of lits[0..=i] is true.
+    let s: Vec<i64> = (0..n - 1).map(|i| *next_aux + i as i64).collect();
+    *next_aux += (n - 1) as i64;
+    clauses.push(vec![-lits[0], s[0]]);
+    for i in 1..n - 1 {
+        clauses.push(vec![-lits[i], s[i]]);
+        clauses.push(vec![-s[i - 1], s[i]]);
+        clauses.push(vec![-lits[i], -s[i - 1]]);
+    }
+    clauses.push(vec![-lits[n - 1], -s[n - 2]]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve;
+
+    /// Return the positive literals describing the values on `board`.
+    fn model_of(board: &Board) -> Vec<i64> {
+        let width = board.width();
+        let cells = board.cells();
+        (0..cells)
+            .map(|cell| {
+                let value = board.value_at(cell % width, cell / width) as usize;
+                (cell * cells + value) as i64
+            })
+            .collect()
+    }
+
+    #[test]
+    // The header matches the clauses that follow it, and the starting cell
+    // is fixed by a unit clause.
+    fn cnf_header() {
+        let board = Board::new(5).start_at(1, 2).unwrap();
+        let cnf = board.to_cnf();
+        let mut lines = cnf.lines().filter(|l| !l.starts_with('c'));
+        let header: Vec<usize> = lines
+            .next()
+            .unwrap()
+            .split_whitespace()
+            .skip(2)
+            .map(|n| n.parse().unwrap())
+            .collect();
+        let clauses: Vec<&str> = lines.collect();
+        assert_eq!(clauses.len(), header[1]);
+        assert!(clauses.iter().all(|c| c.ends_with(" 0")));
+        let start = format!("{} 0", (2 * 5 + 1) * 25 + 1);
+        assert!(clauses.contains(&start.as_str()));
+        let max = clauses
+            .iter()
+            .flat_map(|c| c.split_whitespace())
+            .map(|l| l.parse::<i64>().unwrap().abs())
+            .max()
+            .unwrap();
+        assert_eq!(max as usize, header[0]);
+    }
+
+    #[test]
+    // A model describing a solution decodes back into that solution.
+    fn decode_model() {
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let mut solved = start.clone();
+        for m in solve(&start).unwrap() {
+            solved = solved.next_move(m).unwrap();
+        }
+        let mut model = model_of(&solved);
+        model.push(-3);
+        model.push(25 * 25 + 7);
+        let decoded = start.from_cnf_model(&model).unwrap();
+        assert!(decoded.is_won());
+        assert_eq!(model_of(&decoded), model_of(&solved));
+    }
+
+    #[test]
+    // A model with a missing value or an illegal jump is rejected.
+    fn decode_bad_model() {
+        let board = Board::new(5);
+        assert_eq!(
+            board.from_cnf_model(&[]).err(),
+            Some(BoardError::BadAssignment { value: 1 })
+        );
+        // Values 1 and 2 in adjacent cells are not one jump apart.
+        assert_eq!(
+            board.from_cnf_model(&[1, 25 + 2]).err(),
+            Some(BoardError::BadAssignment { value: 2 })
+        );
+    }
+
+    #[test]
+    // Blocked cells get no variables, and a model over the open cells
+    // decodes into a board with the same cells blocked.
+    fn cnf_blocked() {
+        let board = Board::new(5).block(2, 2).unwrap();
+        assert!(board.to_cnf().starts_with("c square100 5x5\n"));
+        let start = board.start_at(0, 0).unwrap();
+        let mut solved = start.clone();
+        for m in solve(&start).unwrap() {
+            solved = solved.next_move(m).unwrap();
+        }
+        let model: Vec<i64> = (0..25)
+            .filter(|&cell| cell != 12)
+            .enumerate()
+            .map(|(i, cell)| (i * 24 + solved.value_at(cell % 5, cell / 5) as usize) as i64)
+            .collect();
+        let decoded = start.from_cnf_model(&model).unwrap();
+        assert!(decoded.is_won());
+        assert_eq!(decoded, solved);
+    }
+}
@@ -0,0 +1,241 @@
+//! Three-dimensional boards.
+//!
+//! A [`Cube`] board is a box of cells `width` wide, `height` high and `depth`
+//! deep, made of `depth` layers, each a grid like a [`Board`]. A cell is
+//! located by (x, y, z), with z counting layers from the front.
+//!
+//! The moves of a [`MoveRule`] are extended into the z axis. A jump rule
+//! moves in each of the 26 directions from a cell to the cells around it:
+//! `straight` cells along one axis, or `diagonal` cells along each of two or
+//! three axes at once. A knight moves two cells along one axis and one along
+//! another, in 24 ways. The game is played on a [`Board3D`] and solved by
+//! [`solve_geometry`](crate::solver::solve_geometry).
+//!
+//! [`Board`]: crate::board::Board
+
+use crate::board::{BoardError, MoveRule, MIN_SIZE};
+use crate::geometry::{Geometry, GeometryBoard};
+use std::fmt;
+
+/// A board played in three dimensions; see [`Cube`].
+pub type Board3D = GeometryBoard<Cube>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Cube is the geometry of a three-dimensional board. Cells are numbered
+/// layer by layer, and in row-major order within a layer, so that (x, y, z)
+/// is cell `(z * height + y) * width + x`. Jump moves are numbered in the
+/// order of their steps (dx, dy, dz) from (-1, -1, -1) to (1, 1, 1), and
+/// knight moves in the order of their offsets.
+pub struct Cube {
+    width: usize,
+    height: usize,
+    depth: usize,
+    rule: MoveRule,
+    /// The change in location made by each move.
+    offsets: Vec<(i32, i32, i32)>,
+}
+
+impl Cube {
+    /// The most cells along any side; a larger cube has too many cells for
+    /// its values.
+    pub const MAX_SIZE: usize = 40;
+
+    /// Return a cube `size` cells along each side, with the default rule.
+    /// Returns an error if `size` is not between `MIN_SIZE` and `MAX_SIZE`.
+    pub fn new(size: usize) -> Result<Cube, BoardError> {
+        Cube::with_dimensions(size, size, size)
+    }
+
+    /// Return a box `width` x `height` x `depth` cells, with the default
+    /// rule.
+    pub fn with_dimensions(width: usize, height: usize, depth: usize) -> Result<Cube, BoardError> {
+        Cube::with_rule(width, height, depth, MoveRule::default())
+    }
+
+    /// Return a box `width` x `height` x `depth` cells whose moves follow
+    /// `rule`.
+    pub fn with_rule(
+        width: usize,
+        height: usize,
+        depth: usize,
+        rule: MoveRule,
+    ) -> Result<Cube, BoardError> {
+        let range = MIN_SIZE..=Cube::MAX_SIZE;
+        if !range.contains(&width) || !range.contains(&height) || !range.contains(&depth) {
+            return Err(BoardError::InvalidGrid {
+                reason: format!("board size {}x{}x{} is not supported", width, height, depth),
+            });
+        }
+        let steps = || {
+            (-1..=1)
+                .flat_map(|dx| (-1..=1).flat_map(move |dy| (-1..=1).map(move |dz| (dx, dy, dz))))
+        };
+        let offsets = match rule {
+            MoveRule::Jump { straight, diagonal } => steps()
+                .filter_map(|(dx, dy, dz): (i32, i32, i32)| {
+                    let axes = dx.abs() + dy.abs() + dz.abs();
+                    let by = if axes == 1 { straight } else { diagonal } as i32;
+                    Some((dx * by, dy * by, dz * by)).filter(|_| axes > 0 && by > 0)
+                })
+                .collect(),
+            MoveRule::Knight => {
+                let mut offsets: Vec<_> = steps()
+                    .flat_map(|(dx, dy, dz)| {
+                        // Double one of the two axes a step moves along.
+                        [(2 * dx, dy, dz), (dx, 2 * dy, dz), (dx, dy, 2 * dz)]
+                    })
+                    .filter(|&(dx, dy, dz): &(i32, i32, i32)| {
+                        dx.abs() + dy.abs() + dz.abs() == 3 && (dx * dy * dz) == 0
+                    })
+                    .collect();
+                offsets.sort_unstable();
+                offsets.dedup();
+                offsets
+            }
+        };
+        Ok(Cube {
+            width,
+            height,
+            depth,
+            rule,
+            offsets,
+        })
+    }
+
+    #[inline]
+    /// Return the width of each layer.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    /// Return the height of each layer.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    /// Return the number of layers.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    #[inline]
+    /// Return the rule that decides where moves land.
+    pub fn rule(&self) -> MoveRule {
+        self.rule
+    }
+
+    /// Return the cell at (x, y, z), or `None` if it is off the board.
+    pub fn index(&self, x: i32, y: i32, z: i32) -> Option<usize> {
+        let inside = |v: i32, size: usize| v >= 0 && (v as usize) < size;
+        if !inside(x, self.width) || !inside(y, self.height) || !inside(z, self.depth) {
+            return None;
+        }
+        Some((z as usize * self.height + y as usize) * self.width + x as usize)
+    }
+
+    #[inline]
+    /// Return the location (x, y, z) of `cell`.
+    pub fn coords(&self, cell: usize) -> (usize, usize, usize) {
+        let layer = self.width * self.height;
+        (cell % self.width, cell % layer / self.width, cell / layer)
+    }
+}
+
+impl Geometry for Cube {
+    fn cells(&self) -> usize {
+        self.width * self.height * self.depth
+    }
+
+    fn directions(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn target(&self, cell: usize, dir: usize) -> Option<usize> {
+        let (x, y, z) = self.coords(cell);
+        let (dx, dy, dz) = *self.offsets.get(dir)?;
+        self.index(x as i32 + dx, y as i32 + dy, z as i32 + dz)
+    }
+}
+
+/// Draw the board one layer at a time from the front, with a blank line
+/// between layers and empty cells shown as `.`.
+impl fmt::Display for Board3D {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let cube = self.geometry();
+        let width = self.cells().to_string().len();
+        for cell in 0..cube.cells() {
+            let (x, y, z) = cube.coords(cell);
+            if x > 0 {
+                write!(f, " ")?;
+            } else if y > 0 {
+                writeln!(f)?;
+            } else if z > 0 {
+                write!(f, "\n\n")?;
+            }
+            match self.value_at(cell) {
+                0 => write!(f, "{:>width$}", ".", width = width)?,
+                value => write!(f, "{:>width$}", value, width = width)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Cells are numbered layer by layer and found again from their
+    // locations, and moves jump in every direction through the layers.
+    fn cube_cells() {
+        let cube = Cube::with_dimensions(5, 6, 7).unwrap();
+        assert_eq!(cube.cells(), 210);
+        assert_eq!(cube.directions(), 26);
+        assert_eq!(cube.coords(209), (4, 5, 6));
+        for cell in 0..210 {
+            let (x, y, z) = cube.coords(cell);
+            assert_eq!(cube.index(x as i32, y as i32, z as i32), Some(cell));
+        }
+        assert_eq!(cube.index(5, 0, 0), None);
+        // From (0, 0, 0), a jump lands 3 cells along an axis or 2 along each
+        // of two or three.
+        let targets: Vec<_> = (0..26)
+            .filter_map(|dir| cube.target(0, dir))
+            .map(|cell| cube.coords(cell))
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                (0, 0, 3),
+                (0, 3, 0),
+                (0, 2, 2),
+                (3, 0, 0),
+                (2, 0, 2),
+                (2, 2, 0),
+                (2, 2, 2)
+            ]
+        );
+        let knight = Cube::with_rule(5, 5, 5, MoveRule::Knight).unwrap();
+        assert_eq!(knight.directions(), 24);
+        let board = Board3D::new(knight);
+        assert_eq!(board.free_neighbors(0), 6);
+        assert_eq!(board.free_neighbors(62), 24);
+        assert!(Cube::new(4).is_err());
+        assert!(Cube::new(41).is_err());
+    }
+
+    #[test]
+    // A board is drawn one layer at a time.
+    fn cube_display() {
+        let board = Board3D::new(Cube::new(5).unwrap());
+        let board = board.start_at(0).unwrap().next_move(13).unwrap();
+        let text = board.to_string();
+        let layers: Vec<&str> = text.split("\n\n").collect();
+        assert_eq!(layers.len(), 5);
+        assert_eq!(layers[0].lines().next(), Some("  1   .   .   .   ."));
+        assert_eq!(layers[3].lines().next(), Some("  2   .   .   .   ."));
+    }
+}
@@ -0,0 +1,153 @@
+//! Error reporting for bindings from other languages.
+//!
+//! A C caller cannot match on a [`BoardError`], and should not have to parse
+//! its message to tell one kind from another. Each kind of error has a
+//! stable number, [`BoardError::code()`], that will not change between
+//! releases, with 0 meaning success. A binding records the error of a call
+//! it makes with [`set_last_error()`] and returns its code; the caller can
+//! then read the message of the last error on its thread with
+//! [`last_error_message()`], or through the C functions
+//! [`square100_last_error_code`] and [`square100_last_error_message`].
+
+use crate::board::BoardError;
+use std::cell::RefCell;
+use std::os::raw::{c_char, c_int};
+
+thread_local! {
+    /// The last error recorded on this thread.
+    static LAST_ERROR: RefCell<Option<BoardError>> = const { RefCell::new(None) };
+}
+
+impl BoardError {
+    /// Return the stable number of the kind of this error, which is never 0.
+    /// An error with context attached has the number of its kind.
+    pub fn code(&self) -> i32 {
+        match self {
+            BoardError::NotStarted => 1,
+            BoardError::BadDirection { .. } => 2,
+            BoardError::IndexOutOfRange { .. } => 3,
+            BoardError::CannotClear { .. } => 4,
+            BoardError::ValueUsed { .. } => 5,
+            BoardError::ValueOutOfRange { .. } => 6,
+            BoardError::NotEmpty { .. } => 7,
+            BoardError::BadAssignment { .. } => 8,
+            BoardError::ClueConflict { .. } => 9,
+            BoardError::NothingToRedo => 10,
+            BoardError::InvalidGrid { .. } => 11,
+            BoardError::BadSize { .. } => 12,
+            BoardError::AlreadyStarted => 13,
+            BoardError::BadCell { .. } => 14,
+            BoardError::BadMove { .. } => 15,
+            BoardError::BadNotation { .. } => 16,
+            BoardError::InContext { error, .. } => error.code(),
+        }
+    }
+}
+
+/// Record `err` as the last error on this thread, and return its code.
+pub fn set_last_error(err: BoardError) -> i32 {
+    let code = err.code();
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(err));
+    code
+}
+
+/// Forget the last error on this thread.
+pub fn clear_last_error() {
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+}
+
+/// Return the code of the last error on this thread, or 0 if there is none.
+pub fn last_error_code() -> i32 {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map_or(0, BoardError::code))
+}
+
+/// Return the message of the last error on this thread, or `None` if there
+/// is none.
+pub fn last_error_message() -> Option<String> {
+    LAST_ERROR.with(|last| last.borrow().as_ref().map(BoardError::to_string))
+}
+
+#[no_mangle]
+/// Return the code of the last error on the calling thread, or 0 if there
+/// is none.
+pub extern "C" fn square100_last_error_code() -> c_int {
+    last_error_code()
+}
+
+#[no_mangle]
+/// Copy the message of the last error on the calling thread into `buf`, as
+/// a string ending in NUL, and return its length without the NUL. Returns 0
+/// and writes nothing if there is no error. If `buf` is null or `len` is too
+/// small, nothing is written and the negated length of the buffer the
+/// message needs, with its NUL, is returned.
+///
+/// # Safety
+///
+/// `buf` must be null or point to at least `len` bytes that can be written.
+pub unsafe extern "C" fn square100_last_error_message(buf: *mut c_char, len: usize) -> c_int {
+    let message = match last_error_message() {
+        Some(message) => message,
+        None => return 0,
+    };
+    let bytes = message.as_bytes();
+    if buf.is_null() || len <= bytes.len() {
+        return -((bytes.len() + 1) as c_int);
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buf as *mut u8, bytes.len());
+    *buf.add(bytes.len()) = 0;
+    bytes.len() as c_int
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, Direction, ErrorContext};
+    use std::ffi::CStr;
+
+    #[test]
+    // The last error is kept per thread with the code of its kind, and its
+    // message is copied out for C only into a buffer large enough for it.
+    fn last_error() {
+        clear_last_error();
+        assert_eq!(last_error_code(), 0);
+        assert_eq!(last_error_message(), None);
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        let err = board.apply_moves(&[Direction::Up]).unwrap_err();
+        assert_eq!(err.code(), 2);
+        let context = ErrorContext::new(&board);
+        let nested = BoardError::InContext {
+            error: Box::new(BoardError::NotStarted.with_context(context.clone())),
+            context: Box::new(context),
+        };
+        assert_eq!(nested.code(), 1);
+        let message = err.to_string();
+        assert_eq!(set_last_error(err), 2);
+        assert_eq!(square100_last_error_code(), 2);
+        assert_eq!(last_error_message().as_ref(), Some(&message));
+        std::thread::spawn(|| assert_eq!(last_error_code(), 0))
+            .join()
+            .unwrap();
+
+        let mut buf = vec![1 as c_char; message.len() + 1];
+        unsafe {
+            let needed = -(message.len() as c_int + 1);
+            assert_eq!(
+                square100_last_error_message(std::ptr::null_mut(), 0),
+                needed
+            );
+            assert_eq!(
+                square100_last_error_message(buf.as_mut_ptr(), message.len()),
+                needed
+            );
+            assert_eq!(buf[0], 1);
+            let written = square100_last_error_message(buf.as_mut_ptr(), buf.len());
+            assert_eq!(written, message.len() as c_int);
+            assert_eq!(CStr::from_ptr(buf.as_ptr()).to_str().unwrap(), message);
+        }
+        clear_last_error();
+        assert_eq!(
+            unsafe { square100_last_error_message(buf.as_mut_ptr(), 1) },
+            0
+        );
+    }
+}
@@ -0,0 +1,148 @@
+//! A board whose size is fixed at compile time.
+//!
+//! [`FixedBoard<N>`] plays the same game as [`Board`] on an `N` x `N` grid,
+//! but keeps its cells in arrays inside the struct rather than on the heap.
+//! Copying one is a plain memory copy with no allocation, and with `N`
+//! known the compiler can fold the index math into constants, which matters
+//! to a search that copies boards millions of times. It plays the game of a
+//! [`PlainBoard`], and keeps the free neighbors of every cell up to date so
+//! that a search can look them up.
+
+use crate::board::{Board, BoardError, Direction, MAX_SIZE, MIN_SIZE};
+use crate::plain::{Place, PlainBoard};
+use crate::pos::Pos;
+use std::convert::TryFrom;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// FixedBoard is an `N` x `N` puzzle board stored inline.
+pub struct FixedBoard<const N: usize> {
+    /// The values of the cells, indexed by `[y][x]`, with 0 for empty.
+    values: [[u16; N]; N],
+    /// The number of empty cells one jump away from each cell.
+    degrees: [[u8; N]; N],
+    /// The x location of the last cell set in the board.
+    x: usize,
+    /// The y location of the last cell set in the board.
+    y: usize,
+    /// The value of the last cell set, or 0 if the board is not started.
+    score: u16,
+}
+
+impl<const N: usize> FixedBoard<N> {
+    /// Create a new empty board.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is not between `MIN_SIZE` and `MAX_SIZE`.
+    pub fn new() -> Self {
+        FixedBoard::try_with_dimensions(N, N).unwrap_or_else(|err| panic!("{}", err))
+    }
+}
+
+impl<const N: usize> Place for FixedBoard<N> {
+    fn place(&self, x: usize, y: usize, _: Option<Direction>) -> Self {
+        let mut board = *self;
+        board.score += 1;
+        board.values[y][x] = board.score;
+        board.x = x;
+        board.y = y;
+        for &dir in Direction::iterator() {
+            if let Some((x, y)) = self.target(x, y, dir) {
+                board.degrees[y][x] -= 1;
+            }
+        }
+        board
+    }
+}
+
+/// The free neighbors of every cell are kept up to date as moves are made,
+/// so counting them is a lookup.
+impl<const N: usize> PlainBoard for FixedBoard<N> {
+    fn try_with_dimensions(width: usize, height: usize) -> Result<Self, BoardError> {
+        if width != N || height != N || !(MIN_SIZE..=MAX_SIZE).contains(&N) {
+            return Err(BoardError::BadSize { width, height });
+        }
+        let mut degrees = [[0; N]; N];
+        for (y, row) in degrees.iter_mut().enumerate() {
+            for (x, degree) in row.iter_mut().enumerate() {
+                *degree = Direction::iterator()
+                    .filter_map(|&dir| Pos::new(x, y).offset(dir))
+                    .filter(|p| p.in_bounds(N, N))
+                    .count() as u8;
+            }
+        }
+        Ok(FixedBoard {
+            values: [[0; N]; N],
+            degrees,
+            x: 0,
+            y: 0,
+            score: 0,
+        })
+    }
+
+    #[inline]
+    fn width(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        N
+    }
+
+    #[inline]
+    fn value_at(&self, x: usize, y: usize) -> u16 {
+        self.values[y][x]
+    }
+
+    #[inline]
+    fn current_position(&self) -> (usize, usize) {
+        (self.x, self.y)
+    }
+
+    #[inline]
+    fn score(&self) -> usize {
+        self.score as usize
+    }
+
+    #[inline]
+    fn free_neighbors(&self, x: usize, y: usize) -> u8 {
+        self.degrees[y][x]
+    }
+}
+
+impl<const N: usize> Default for FixedBoard<N> {
+    fn default() -> Self {
+        FixedBoard::new()
+    }
+}
+
+impl<const N: usize> From<FixedBoard<N>> for Board {
+    fn from(board: FixedBoard<N>) -> Board {
+        board.to_board()
+    }
+}
+
+/// Convert a board of dimensions `N` x `N` that plays the plain game.
+impl<const N: usize> TryFrom<&Board> for FixedBoard<N> {
+    type Error = BoardError;
+
+    fn try_from(board: &Board) -> Result<Self, Self::Error> {
+        FixedBoard::from_board(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A fixed board only converts to and from a board of its own size.
+    fn fixed_size() {
+        let board = Board::new(6).start_at(1, 0).unwrap();
+        let fixed = FixedBoard::<6>::try_from(&board).unwrap();
+        assert_eq!(Board::from(fixed), board);
+        assert!(FixedBoard::<5>::try_from(&board).is_err());
+        assert!(FixedBoard::<6>::try_with_dimensions(6, 7).is_err());
+    }
+}
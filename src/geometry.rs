@@ -0,0 +1,333 @@
+//! Boards of any shape, described by the jumps between their cells.
+//!
+//! [`Board`] plays the game on a rectangular grid. Other shapes, such as the
+//! hexagonal boards of [`hex`](crate::hex) and the three-dimensional boards
+//! of [`cube`](crate::cube), only need to say which cell each move reaches
+//! from each cell. A [`Geometry`] numbers the cells of a board
+//! and its moves, and [`GeometryBoard`] plays the game on any geometry, so
+//! that the same search, [`solve_geometry`](crate::solver::solve_geometry),
+//! solves them all. A `Board` is a geometry too, which lets that search be
+//! checked against the solver of the grid.
+
+use crate::bitset::BitSet;
+use crate::board::{Board, BoardError, Direction};
+use crate::pos::Pos;
+use std::sync::Arc;
+
+/// Geometry describes the cells of a board and the moves between them.
+/// Cells are numbered from 0 to `cells() - 1`, and moves from 0 to
+/// `directions() - 1`. Moves between open cells must be reversible and
+/// lead to distinct cells: if a move leads from one open cell to another,
+/// no other move does, and exactly one move leads back. The count of free
+/// neighbors kept by a [`GeometryBoard`] relies on this.
+pub trait Geometry {
+    /// Return the number of cells, including any that are closed.
+    fn cells(&self) -> usize;
+
+    /// Return the number of moves a cell can have.
+    fn directions(&self) -> usize;
+
+    /// Return the cell reached by making move `dir` from `cell`, or `None`
+    /// if the move would leave the board or land on a closed cell.
+    fn target(&self, cell: usize, dir: usize) -> Option<usize>;
+
+    /// Return `false` if `cell` is closed, so that the path never visits it.
+    fn is_open(&self, cell: usize) -> bool {
+        cell < self.cells()
+    }
+}
+
+/// The geometry of a board is its grid, with cells numbered in row-major
+/// order and moves in the order of `Direction::iterator()`. Its move rule,
+/// wrap mode and blocked cells are followed, but its clues are not.
+impl Geometry for Board {
+    fn cells(&self) -> usize {
+        self.width() * self.height()
+    }
+
+    fn directions(&self) -> usize {
+        Direction::iterator().len()
+    }
+
+    fn target(&self, cell: usize, dir: usize) -> Option<usize> {
+        let (x, y) = Pos::from_index(cell, self.width()).into();
+        let dir = *Direction::iterator().nth(dir)?;
+        Board::target(self, x, y, dir).map(|to| Pos::from(to).index(self.width()))
+    }
+
+    fn is_open(&self, cell: usize) -> bool {
+        let (x, y) = Pos::from_index(cell, self.width()).into();
+        cell < Geometry::cells(self) && !self.blocked_at(x, y)
+    }
+}
+
+/// GeometryBoard is a puzzle board on the cells of a [`Geometry`]. It has no
+/// clues, history or undo; the moves of a game are the moves made to reach
+/// it.
+pub struct GeometryBoard<G> {
+    geometry: Arc<G>,
+    /// The values of the cells, with 0 for empty.
+    values: Vec<u16>,
+    /// The filled cells.
+    occupied: BitSet,
+    /// The number of empty cells one move away from each cell.
+    degrees: Vec<u8>,
+    /// The cell holding each value on the path, with the cell holding `v` at
+    /// index `v - 1`.
+    path: Vec<u16>,
+    /// The number of open cells, which the path must visit.
+    open: usize,
+}
+
+impl<G: Geometry> GeometryBoard<G> {
+    /// Create a new empty board on the cells of `geometry`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the geometry has more cells than a value can number, or
+    /// more moves than a degree can count. In debug builds, also panics if
+    /// its moves are not reversible or do not lead to distinct cells.
+    pub fn new(geometry: G) -> Self {
+        let cells = geometry.cells();
+        assert!(
+            cells < u16::MAX as usize && geometry.directions() <= u8::MAX as usize,
+            "a geometry of {} cells and {} moves is not supported",
+            cells,
+            geometry.directions()
+        );
+        let moves = |from: usize, to: usize| {
+            (0..geometry.directions())
+                .filter(|&dir| geometry.target(from, dir) == Some(to))
+                .count()
+        };
+        debug_assert!(
+            (0..cells)
+                .filter(|&cell| geometry.is_open(cell))
+                .all(|cell| {
+                    (0..geometry.directions())
+                        .filter_map(|dir| geometry.target(cell, dir))
+                        .all(|to| moves(cell, to) == 1 && moves(to, cell) == 1)
+                }),
+            "the moves of a geometry must be reversible and lead to distinct cells"
+        );
+        let degrees = (0..cells)
+            .map(|cell| {
+                (0..geometry.directions())
+                    .filter(|&dir| geometry.target(cell, dir).is_some())
+                    .count() as u8
+            })
+            .collect();
+        let open = (0..cells).filter(|&cell| geometry.is_open(cell)).count();
+        GeometryBoard {
+            geometry: Arc::new(geometry),
+            values: vec![0; cells],
+            occupied: BitSet::new(cells),
+            degrees,
+            path: Vec::new(),
+            open,
+        }
+    }
+
+    #[inline]
+    /// Return the geometry of the board.
+    pub fn geometry(&self) -> &G {
+        &self.geometry
+    }
+
+    #[inline]
+    /// Return the number of cells the path must visit.
+    pub fn cells(&self) -> usize {
+        self.open
+    }
+
+    #[inline]
+    /// Return the value of `cell`, where 0 is an empty cell.
+    pub fn value_at(&self, cell: usize) -> u16 {
+        self.values[cell]
+    }
+
+    #[inline]
+    /// Return the number of empty cells one move away from `cell`.
+    pub fn free_neighbors(&self, cell: usize) -> u8 {
+        self.degrees[cell]
+    }
+
+    #[inline]
+    /// Return the cell holding the highest value, or `None` if the board
+    /// has not been started.
+    pub fn current(&self) -> Option<usize> {
+        self.path.last().map(|&cell| cell as usize)
+    }
+
+    #[inline]
+    /// Return the highest value on the board.
+    pub fn score(&self) -> usize {
+        self.path.len()
+    }
+
+    #[inline]
+    /// Return `true` if the board has been started.
+    pub fn is_started(&self) -> bool {
+        !self.path.is_empty()
+    }
+
+    #[inline]
+    /// Return `true` if every open cell has been filled.
+    pub fn is_won(&self) -> bool {
+        self.path.len() == self.open
+    }
+
+    /// Return the cells of the path in the order they were filled.
+    pub fn path(&self) -> Vec<usize> {
+        self.path.iter().map(|&cell| cell as usize).collect()
+    }
+
+    /// Return the moves that can be made from the current position, in
+    /// increasing order.
+    pub fn possible_moves(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.geometry.directions()).filter(move |&dir| self.valid_move(dir).is_some())
+    }
+
+    /// Return the cell move `dir` lands on, if it is legal.
+    fn valid_move(&self, dir: usize) -> Option<usize> {
+        self.geometry
+            .target(self.current()?, dir)
+            .filter(|&cell| !self.occupied.contains(cell))
+    }
+
+    /// Return a copy of the board started by placing a 1 in `cell`.
+    pub fn start_at(&self, cell: usize) -> Result<Self, BoardError> {
+        if !self.geometry.is_open(cell) {
+            return Err(BoardError::BadCell { cell });
+        }
+        if self.is_started() {
+            return Err(BoardError::AlreadyStarted);
+        }
+        Ok(self.set(cell))
+    }
+
+    /// Return a copy of the board with move `dir` made from the current
+    /// position.
+    pub fn next_move(&self, dir: usize) -> Result<Self, BoardError> {
+        let cell = self.current().ok_or(BoardError::NotStarted)?;
+        match self.valid_move(dir) {
+            Some(to) => Ok(self.set(to)),
+            None => Err(BoardError::BadMove { dir, cell }),
+        }
+    }
+
+    /// Return a copy of the board with the next value placed in `cell`,
+    /// which must be empty.
+    fn set(&self, cell: usize) -> Self {
+        let mut board = self.clone();
+        board.path.push(cell as u16);
+        board.values[cell] = board.path.len() as u16;
+        board.occupied.insert(cell);
+        for dir in 0..self.geometry.directions() {
+            if let Some(to) = self.geometry.target(cell, dir) {
+                board.degrees[to] = board.degrees[to].saturating_sub(1);
+            }
+        }
+        board
+    }
+}
+
+impl<G> Clone for GeometryBoard<G> {
+    fn clone(&self) -> Self {
+        GeometryBoard {
+            geometry: Arc::clone(&self.geometry),
+            values: self.values.clone(),
+            occupied: self.occupied.clone(),
+            degrees: self.degrees.clone(),
+            path: self.path.clone(),
+            open: self.open,
+        }
+    }
+}
+
+/// Boards are equal when they have equal geometries and hold the same
+/// values.
+impl<G: PartialEq> PartialEq for GeometryBoard<G> {
+    fn eq(&self, other: &Self) -> bool {
+        self.values == other.values && self.geometry == other.geometry
+    }
+}
+
+impl<G: std::fmt::Debug> std::fmt::Debug for GeometryBoard<G> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.debug_struct("GeometryBoard")
+            .field("geometry", &self.geometry)
+            .field("values", &self.values)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A board played through its geometry fills the same cells as the
+    // board itself, and blocked cells are left out of the count.
+    fn board_geometry() {
+        let board = Board::new(5).block(4, 4).unwrap();
+        let mut played = board.start_at(0, 0).unwrap();
+        let mut geometric = GeometryBoard::new(board.clone()).start_at(0).unwrap();
+        assert_eq!(geometric.cells(), 24);
+        for dir in [0, 2, 5] {
+            played = played
+                .next_move(Direction::iterator().as_slice()[dir])
+                .unwrap();
+            geometric = geometric.next_move(dir).unwrap();
+            let expected: Vec<usize> = played
+                .possible_moves()
+                .iter()
+                .map(|d| Direction::iterator().position(|e| e == *d).unwrap())
+                .collect();
+            assert_eq!(geometric.possible_moves().collect::<Vec<_>>(), expected);
+            assert_eq!(geometric.free_neighbors(12), played.free_neighbors(2, 2));
+        }
+        let path: Vec<usize> = played
+            .path()
+            .iter()
+            .map(|&p| Pos::from(p).index(5))
+            .collect();
+        assert_eq!(geometric.path(), path);
+        assert_eq!(geometric.score(), 4);
+        assert_eq!(
+            geometric.next_move(4).err(),
+            Some(BoardError::BadMove { dir: 4, cell: 6 })
+        );
+        let empty = GeometryBoard::new(board);
+        assert_eq!(
+            empty.start_at(24).err(),
+            Some(BoardError::BadCell { cell: 24 })
+        );
+        assert_eq!(empty.next_move(0).err(), Some(BoardError::NotStarted));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "must be reversible")]
+    // A geometry with two moves from one cell to another, but only one
+    // back, is rejected rather than miscounting free neighbors.
+    fn duplicate_moves() {
+        struct Doubled;
+        impl Geometry for Doubled {
+            fn cells(&self) -> usize {
+                2
+            }
+            fn directions(&self) -> usize {
+                2
+            }
+            fn target(&self, cell: usize, dir: usize) -> Option<usize> {
+                match (cell, dir) {
+                    (0, _) => Some(1),
+                    (_, 0) => Some(0),
+                    _ => None,
+                }
+            }
+        }
+        GeometryBoard::new(Doubled);
+    }
+}
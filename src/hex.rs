@@ -0,0 +1,242 @@
+//! Hexagonal boards.
+//!
+//! A [`Hex`] board is a regular hexagon of hexagonal cells, `size` cells to
+//! a side. Cells are located by axial coordinates (q, r): the centre cell is
+//! (0, 0), q counts cells to the right along a row, and r counts rows down,
+//! so that (q, r + 1) is the cell below and to the right of (q, r). A cell
+//! at distance `n` from the centre has `|q|`, `|r|` and `|q + r|` all at
+//! most `n`.
+//!
+//! A move jumps along one of the six rows through a cell, or along one of
+//! the six diagonals between them, as set by a [`HexRule`]. With both kinds
+//! of move there are twelve directions; with one, six. The game is played
+//! on a [`HexBoard`] and solved by
+//! [`solve_geometry`](crate::solver::solve_geometry).
+
+use crate::board::BoardError;
+use crate::geometry::{Geometry, GeometryBoard};
+use std::fmt;
+
+/// A board played on a hexagon; see [`Hex`].
+pub type HexBoard = GeometryBoard<Hex>;
+
+/// The six steps to the next cell along a row, in axial coordinates, turning
+/// clockwise from the step to the right.
+const STRAIGHT: [(i32, i32); 6] = [(1, 0), (0, 1), (-1, 1), (-1, 0), (0, -1), (1, -1)];
+
+/// The six steps to the nearest cell along a diagonal, which passes between
+/// two cells, turning clockwise from down and to the right.
+const DIAGONAL: [(i32, i32); 6] = [(1, 1), (-1, 2), (-2, 1), (-1, -1), (1, -2), (2, -1)];
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// HexRule decides how far the moves on a hexagonal board jump: `straight`
+/// steps along a row, or `diagonal` steps along a diagonal. A distance of 0
+/// rules out those moves. The default jumps over one cell along a row, or
+/// to the nearest cell along a diagonal.
+///
+/// The cells of a hexagon can be coloured with three colours so that no two
+/// cells that touch share one. A diagonal move of any length, or a straight
+/// move of a multiple of 3, stays on its colour, so a rule with only those
+/// moves, such as jumping 3 along a row and 2 along a diagonal as on a
+/// square board, reaches a third of the cells at most.
+pub struct HexRule {
+    pub straight: u8,
+    pub diagonal: u8,
+}
+
+impl Default for HexRule {
+    fn default() -> Self {
+        HexRule {
+            straight: 2,
+            diagonal: 1,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// Hex is the geometry of a hexagonal board. Cells are numbered row by row
+/// from the top, and left to right within a row. The moves along rows come
+/// first, then those along diagonals, each turning clockwise.
+pub struct Hex {
+    size: usize,
+    rule: HexRule,
+    /// The axial coordinates of each cell.
+    coords: Vec<(i32, i32)>,
+    /// The index of the first cell of each row, and of the end of the last.
+    rows: Vec<usize>,
+    /// The change in coordinates made by each move.
+    offsets: Vec<(i32, i32)>,
+}
+
+impl Hex {
+    /// The most cells a side can have; a larger board has too many cells for
+    /// its values.
+    pub const MAX_SIZE: usize = 148;
+
+    /// Return a hexagon `size` cells to a side, with the default rule.
+    /// Returns an error if `size` is less than 2 or more than `MAX_SIZE`.
+    pub fn new(size: usize) -> Result<Hex, BoardError> {
+        Hex::with_rule(size, HexRule::default())
+    }
+
+    /// Return a hexagon `size` cells to a side whose moves follow `rule`.
+    pub fn with_rule(size: usize, rule: HexRule) -> Result<Hex, BoardError> {
+        if !(2..=Hex::MAX_SIZE).contains(&size) {
+            return Err(BoardError::BadSize {
+                width: size,
+                height: size,
+            });
+        }
+        let n = size as i32 - 1;
+        let mut coords = Vec::new();
+        let mut rows = Vec::new();
+        for r in -n..=n {
+            rows.push(coords.len());
+            let (low, high) = ((-n).max(-n - r), n.min(n - r));
+            coords.extend((low..=high).map(|q| (q, r)));
+        }
+        rows.push(coords.len());
+        let scale = |steps: &[(i32, i32)], by: u8| {
+            let by = by as i32;
+            steps
+                .iter()
+                .map(move |&(dq, dr)| (dq * by, dr * by))
+                .collect::<Vec<_>>()
+        };
+        let mut offsets = Vec::new();
+        if rule.straight > 0 {
+            offsets.extend(scale(&STRAIGHT, rule.straight));
+        }
+        if rule.diagonal > 0 {
+            offsets.extend(scale(&DIAGONAL, rule.diagonal));
+        }
+        Ok(Hex {
+            size,
+            rule,
+            coords,
+            rows,
+            offsets,
+        })
+    }
+
+    #[inline]
+    /// Return the number of cells along each side.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    #[inline]
+    /// Return the rule that decides where moves land.
+    pub fn rule(&self) -> HexRule {
+        self.rule
+    }
+
+    /// Return the cell at axial coordinates (q, r), or `None` if it is off
+    /// the board.
+    pub fn index(&self, q: i32, r: i32) -> Option<usize> {
+        let n = self.size as i32 - 1;
+        if r.abs() > n || q.abs() > n || (q + r).abs() > n {
+            return None;
+        }
+        let row = self.rows[(r + n) as usize];
+        Some(row + (q - self.coords[row].0) as usize)
+    }
+
+    #[inline]
+    /// Return the axial coordinates (q, r) of `cell`.
+    pub fn coords(&self, cell: usize) -> (i32, i32) {
+        self.coords[cell]
+    }
+}
+
+impl Geometry for Hex {
+    fn cells(&self) -> usize {
+        self.coords.len()
+    }
+
+    fn directions(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn target(&self, cell: usize, dir: usize) -> Option<usize> {
+        let (q, r) = self.coords[cell];
+        let (dq, dr) = *self.offsets.get(dir)?;
+        self.index(q + dq, r + dr)
+    }
+}
+
+/// Draw the board as a hexagon, one row per line, with each row shifted
+/// half a cell from the next and empty cells shown as `.`.
+impl fmt::Display for HexBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let hex = self.geometry();
+        let n = hex.size() as i32 - 1;
+        // An even pitch lets each row be shifted by exactly half a cell.
+        let pitch = (self.cells().to_string().len() + 2) / 2 * 2;
+        for (row, cells) in hex.rows.windows(2).enumerate() {
+            if row > 0 {
+                writeln!(f)?;
+            }
+            let shift = (row as i32 - n).unsigned_abs() as usize * pitch / 2;
+            write!(f, "{:shift$}", "", shift = shift)?;
+            for cell in cells[0]..cells[1] {
+                if cell > cells[0] {
+                    write!(f, " ")?;
+                }
+                match self.value_at(cell) {
+                    0 => write!(f, "{:>width$}", ".", width = pitch - 1)?,
+                    value => write!(f, "{:>width$}", value, width = pitch - 1)?,
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Cells are numbered row by row and found again from their coordinates,
+    // and moves jump along the rows and diagonals of the hexagon.
+    fn hex_cells() {
+        let hex = Hex::new(3).unwrap();
+        assert_eq!(hex.cells(), 19);
+        assert_eq!(hex.directions(), 12);
+        assert_eq!(hex.coords(0), (0, -2));
+        assert_eq!(hex.coords(9), (0, 0));
+        assert_eq!(hex.coords(18), (0, 2));
+        for cell in 0..19 {
+            let (q, r) = hex.coords(cell);
+            assert_eq!(hex.index(q, r), Some(cell));
+        }
+        assert_eq!(hex.index(2, 1), None);
+        let hex = Hex::with_rule(
+            3,
+            HexRule {
+                straight: 1,
+                diagonal: 0,
+            },
+        )
+        .unwrap();
+        assert_eq!(hex.directions(), 6);
+        let board = HexBoard::new(hex);
+        assert_eq!(board.free_neighbors(9), 6);
+        assert_eq!(board.free_neighbors(0), 3);
+        assert!(Hex::new(1).is_err());
+    }
+
+    #[test]
+    // A board is drawn as a hexagon, each row shifted half a cell.
+    fn hex_display() {
+        let rule = HexRule {
+            straight: 1,
+            diagonal: 0,
+        };
+        let board = HexBoard::new(Hex::with_rule(2, rule).unwrap());
+        let board = board.start_at(3).unwrap().next_move(0).unwrap();
+        assert_eq!(board.to_string(), " . .\n. 1 2\n . .");
+    }
+}
@@ -0,0 +1,111 @@
+//! JSON import and export, enabled by the `json` feature.
+//!
+//! A board is written as an object with these fields:
+//!
+//! * `size`: the number of cells along each side of the board, or in each
+//!   row of a rectangular board.
+//! * `height`: the number of rows of a rectangular board. It is left out
+//!   for a square board.
+//! * `values`: the value of every cell in row-major order, so the cell at
+//!   (x, y) is at index `y * size + x`, with 0 for an empty cell.
+//! * `position`: the `[x, y]` location of the last value placed, or `null`
+//!   if the board has not been started.
+//! * `history`: the moves made since the board was started, as direction
+//!   names such as `"DownRight"`.
+//! * `redo`: the moves taken back by `Board::undo()`, most recently undone
+//!   last.
+//! * `clues`: the clues of the board as `[x, y, value]`.
+//!
+//! For example, a 5x5 board started at (0, 0) with one move to the right is:
+//!
+//! ```text
+//! {"size":5,"values":[1,0,0,2,0,0,...,0],"position":[3,0],
+//!  "history":["Right"],"redo":[],"clues":[]}
+//! ```
+//!
+//! When a board is read, it is rebuilt by replaying `history` from the cell
+//! holding 1, and `values` and `position` must agree with the result.
+
+use crate::board::{Board, BoardError};
+
+impl Board {
+    /// Encode the board as JSON in the format described in the
+    /// [module documentation](self).
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("a board can always be written as JSON")
+    }
+
+    /// Decode a board from JSON written by [`Board::to_json`]. Returns an
+    /// error if the text is not valid JSON in that format, or if it does not
+    /// describe a legal game.
+    pub fn from_json(json: &str) -> Result<Board, BoardError> {
+        serde_json::from_str(json).map_err(|e| BoardError::InvalidGrid {
+            reason: e.to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::board::{Board, Direction, MoveRule};
+
+    #[test]
+    // A board is written in the documented format and read back.
+    fn json_round_trip() {
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        board = board.next_move(Direction::Right).unwrap();
+        let json = board.to_json();
+        assert!(json.starts_with("{\"size\":5,\"values\":[1,0,0,2,0,0,"));
+        assert!(
+            json.ends_with("\"position\":[3,0],\"history\":[\"Right\"],\"redo\":[],\"clues\":[]}")
+        );
+        let read = Board::from_json(&json).unwrap();
+        assert_eq!(read.to_string(), board.to_string());
+        assert_eq!(read.history(), board.history());
+        assert!(Board::from_json("{\"size\":5}").is_err());
+        let moved = json.replace("[3,0]", "[0,3]");
+        assert!(Board::from_json(&moved).is_err());
+    }
+
+    #[test]
+    // A rectangular board writes its height, and is read back with it.
+    fn json_rectangular() {
+        let board = Board::with_dimensions(6, 8).start_at(5, 7).unwrap();
+        let json = board.to_json();
+        assert!(json.starts_with("{\"size\":6,\"height\":8,"));
+        let read = Board::from_json(&json).unwrap();
+        assert_eq!(read, board);
+        assert_eq!((read.width(), read.height()), (6, 8));
+    }
+
+    #[test]
+    // Blocked cells are written only when there are some, and read back.
+    fn json_blocked() {
+        let board = Board::new(5).block(1, 2).unwrap().start_at(0, 0).unwrap();
+        let json = board.to_json();
+        assert!(json.ends_with(",\"blocked\":[[1,2]]}"));
+        assert_eq!(Board::from_json(&json).unwrap(), board);
+        assert!(!Board::new(5).to_json().contains("blocked"));
+    }
+
+    #[test]
+    // A move rule and wrap mode are written only when they are not the
+    // defaults, and read back.
+    fn json_rule() {
+        let rule = MoveRule::Jump {
+            straight: 1,
+            diagonal: 1,
+        };
+        let board = Board::new(5)
+            .with_rule(rule)
+            .and_then(|b| b.with_wrap(true))
+            .and_then(|b| b.start_at(0, 0))
+            .unwrap();
+        let json = board.to_json();
+        assert!(json.ends_with(",\"wrap\":true}"));
+        assert!(json.contains("\"rule\":{\"Jump\":{\"straight\":1,\"diagonal\":1}}"));
+        assert_eq!(Board::from_json(&json).unwrap(), board);
+        assert!(!Board::new(5).to_json().contains("rule"));
+        assert!(!Board::new(5).to_json().contains("wrap"));
+    }
+}
@@ -0,0 +1,67 @@
+/*!
+
+A Rust solver for a simple numerical game.
+
+See the inital article at [simple-number].
+
+# Rules
+
+The rules are simple: on an empty 10x10 grid (100 squares in total) you put a
+number 1 on an arbitrary square. Starting from that square you can move
+horizontally or vertically jumping over two squares or diagonally jumping over
+one square. There you can place number 2. Your task is to reach number 100,
+filling all squares. You can not visit already visited squares.
+
+Here is an example of a solved game with a reduced 5x5 grid, starting at
+top-left corner:
+
+```text
+ 1 24 14  2 25
+16 21  5  8 20
+13 10 18 23 11
+ 4  7 15  3  6
+17 22 12  9 19
+```
+
+Variants with other jump distances, or knight's tours, can be played by
+giving a board another [`MoveRule`](board::MoveRule).
+
+# Features
+
+* `color`: add `render::ansi()`, which colours a board for a terminal.
+* `gif`: add `render::gif()`, which animates a board's path being filled
+  in as a GIF image.
+* `image`: add `render::png()`, which draws a board as a PNG image.
+* `json`: add `Board::to_json()` and `Board::from_json()`, built on
+  `serde`.
+* `serde`: derive `Serialize` and `Deserialize` for `Board`, `Direction`
+  and the solver's result types. A board is checked when it is
+  deserialized, so data that does not describe a legal game is rejected.
+* `z3`: add a solver backend that hands the puzzle to the Z3 SMT solver,
+  which must be installed.
+
+[simple-number]: https://www.nurkiewicz.com/2018/09/brute-forcing-seemingly-simple-number.html
+ */
+
+pub mod binary;
+mod bitset;
+pub mod board;
+pub mod builder;
+pub mod cnf;
+pub mod cube;
+pub mod ffi;
+pub mod fixed;
+pub mod geometry;
+pub mod hex;
+#[cfg(feature = "json")]
+pub mod json;
+pub mod minizinc;
+pub mod notation;
+pub mod persistent;
+pub mod plain;
+pub mod pos;
+pub mod record;
+pub mod render;
+pub mod small;
+pub mod solver;
+pub mod symmetry;
@@ -1,35 +1,705 @@
-/*!
+//! The `square100` command, which solves boards from the command line or
+//! lets them be played at the terminal.
+//!
+//! ```text
+//! square100 solve --size 10 --start 0,0 --strategy warnsdorff
+//! square100 play --size 6
+//! square100 generate --size 10 --clues 12
+//! ```
 
-A Rust solver for a simple numerical game.
+use rand::rngs::StdRng;
+use rand::seq::{index, SliceRandom};
+use rand::SeedableRng;
+use square100::board::{Board, CellChange, Direction};
+use square100::notation::MoveList;
+use square100::pos::Pos;
+use square100::record::GameRecord;
+use square100::solver::{
+    solve_with_config, MoveOrder, Outcome, Solver, SolverConfig, SolverLimits,
+};
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write as _;
+use std::io::{self, Read, Write};
+use std::process;
 
-See the inital article at [simple-number].
+const USAGE: &str = "\
+usage: square100 solve [--size N|WxH] [--start X,Y] [--strategy NAME] [--seed N]
+       square100 play [--size N|WxH] [--start X,Y]
+       square100 generate [--size N|WxH] [--clues N] [--seed N]
 
-# Rules
+The strategies are natural, warnsdorff (the default), center-out and random.";
 
-The rules are simple: on an empty 10x10 grid (100 squares in total) you put a
-number 1 on an arbitrary square. Starting from that square you can move
-horizontally or vertically jumping over two squares or diagonally jumping over
-one square. There you can place number 2. Your task is to reach number 100,
-filling all squares. You can not visit already visited squares.
+/// The keys that make each move in `play`, laid out as on a numeric keypad.
+const KEYS: [(u8, Direction); 8] = [
+    (b'1', Direction::DownLeft),
+    (b'2', Direction::Down),
+    (b'3', Direction::DownRight),
+    (b'4', Direction::Left),
+    (b'6', Direction::Right),
+    (b'7', Direction::UpLeft),
+    (b'8', Direction::Up),
+    (b'9', Direction::UpRight),
+];
 
-Here is an example of a solved game with a reduced 5x5 grid, starting at
-top-left corner:
+/// The moves clockwise from `Up`, the order in which the arrow keys step
+/// through the legal moves in `play`.
+const CLOCKWISE: [Direction; 8] = [
+    Direction::Up,
+    Direction::UpRight,
+    Direction::Right,
+    Direction::DownRight,
+    Direction::Down,
+    Direction::DownLeft,
+    Direction::Left,
+    Direction::UpLeft,
+];
 
-     1 24 14  2 25
-    16 21  5  8 20
-    13 10 18 23 11
-     4  7 15  3  6
-    17 22 12  9 19
+const HELP: &str = "\
+Arrow keys choose a move and Enter makes it, or a key shown on the grid
+makes its move at once. u takes back a move, q quits.
+";
 
+/// Options holds the `--name value` pairs given after a subcommand.
+struct Options(HashMap<String, String>);
 
+impl Options {
+    /// Return the options in `args`, or an error naming an option that is
+    /// not one of `known` or has no value.
+    fn parse(args: &[String], known: &[&str]) -> Result<Options, String> {
+        let mut options = HashMap::new();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let name = arg
+                .strip_prefix("--")
+                .filter(|name| known.contains(name))
+                .ok_or_else(|| format!("unknown option '{}'", arg))?;
+            let value = args
+                .next()
+                .ok_or_else(|| format!("option '{}' needs a value", arg))?;
+            options.insert(name.to_string(), value.clone());
+        }
+        Ok(Options(options))
+    }
 
-[simple-number]: https://www.nurkiewicz.com/2018/09/brute-forcing-seemingly-simple-number.html
- */
+    /// Return the value of option `name` read by `parse`, or `default` if
+    /// it was not given.
+    fn get<T>(
+        &self,
+        name: &str,
+        default: T,
+        parse: impl Fn(&str) -> Option<T>,
+    ) -> Result<T, String> {
+        match self.0.get(name) {
+            Some(value) => {
+                parse(value).ok_or_else(|| format!("bad value '{}' for --{}", value, name))
+            }
+            None => Ok(default),
+        }
+    }
+}
+
+/// Return the width and height in `text`, given as "N" or "WxH".
+fn parse_size(text: &str) -> Option<(usize, usize)> {
+    match text.split_once('x') {
+        Some((width, height)) => Some((width.parse().ok()?, height.parse().ok()?)),
+        None => text.parse().ok().map(|size| (size, size)),
+    }
+}
+
+/// Return the cell in `text`, given as "X,Y".
+fn parse_cell(text: &str) -> Option<(usize, usize)> {
+    let (x, y) = text.split_once(',')?;
+    Some((x.trim().parse().ok()?, y.trim().parse().ok()?))
+}
+
+/// Return the move order named `name`, seeded with `seed` if it is random.
+fn parse_strategy(name: &str, seed: u64) -> Option<MoveOrder> {
+    match name {
+        "natural" => Some(MoveOrder::Natural),
+        "warnsdorff" => Some(MoveOrder::Warnsdorff),
+        "center-out" => Some(MoveOrder::CenterOut),
+        "random" => Some(MoveOrder::Random { seed }),
+        _ => None,
+    }
+}
+
+/// Run `square100 solve` with `args`, printing the solved grid and the
+/// moves. Returns `Ok(false)` if the board has no solution.
+fn solve(args: &[String]) -> Result<bool, String> {
+    let options = Options::parse(args, &["size", "start", "strategy", "seed"])?;
+    let (width, height) = options.get("size", (10, 10), parse_size)?;
+    let (x, y) = options.get("start", (0, 0), parse_cell)?;
+    let seed = options.get("seed", 0, |text| text.parse().ok())?;
+    let order = options.get("strategy", MoveOrder::Warnsdorff, |name| {
+        parse_strategy(name, seed)
+    })?;
+
+    let board = Board::try_with_dimensions(width, height)
+        .and_then(|board| board.start_at(x, y))
+        .map_err(|e| e.to_string())?;
+    let config = SolverConfig {
+        order,
+        ..SolverConfig::default()
+    };
+    match solve_with_config(&board, &config) {
+        Some(moves) => {
+            let mut solved = board;
+            solved.apply_moves(&moves).map_err(|e| e.to_string())?;
+            println!("{}", solved);
+            println!("{}", MoveList(moves));
+            Ok(true)
+        }
+        None => {
+            eprintln!("no solution from {},{}", x, y);
+            Ok(false)
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Key is a key pressed in `play`, by what it does.
+enum Key {
+    /// Make this move.
+    Move(Direction),
+    /// Select the next legal move clockwise.
+    Next,
+    /// Select the next legal move anticlockwise.
+    Previous,
+    /// Make the selected move.
+    Play,
+    /// Take back the last move.
+    Undo,
+    /// Stop playing.
+    Quit,
+    /// A key that does nothing.
+    Other,
+}
+
+/// Read the next key from `input`, or `None` at the end of the input. The
+/// arrow keys arrive as the escape sequences `ESC [ A` to `ESC [ D`; right
+/// and down step clockwise, left and up anticlockwise.
+fn read_key<I: Iterator<Item = io::Result<u8>>>(input: &mut I) -> io::Result<Option<Key>> {
+    let byte = match input.next() {
+        Some(byte) => byte?,
+        None => return Ok(None),
+    };
+    let key = match byte {
+        b'\x1b' => match (input.next().transpose()?, input.next().transpose()?) {
+            (Some(b'['), Some(b'B')) | (Some(b'['), Some(b'C')) => Key::Next,
+            (Some(b'['), Some(b'A')) | (Some(b'['), Some(b'D')) => Key::Previous,
+            _ => Key::Other,
+        },
+        b'\r' | b'\n' | b' ' => Key::Play,
+        b'u' | b'\x7f' | b'\x08' => Key::Undo,
+        // Ctrl-C and Ctrl-D, which arrive as keys in raw mode.
+        b'q' | b'\x03' | b'\x04' => Key::Quit,
+        byte => KEYS
+            .iter()
+            .find(|&&(key, _)| key == byte)
+            .map_or(Key::Other, |&(_, dir)| Key::Move(dir)),
+    };
+    Ok(Some(key))
+}
+
+/// Game is a board being played in `play`, with the legal move the arrow
+/// keys have selected and a message about the last key, if it did nothing.
+struct Game {
+    board: Board,
+    selected: usize,
+    message: Option<&'static str>,
+}
+
+impl Game {
+    fn new(board: Board) -> Self {
+        Game {
+            board,
+            selected: 0,
+            message: None,
+        }
+    }
 
-#![feature(custom_attribute)]
+    /// Return the legal moves, clockwise from `Up`.
+    fn moves(&self) -> Vec<Direction> {
+        let possible = self.board.possible_moves();
+        CLOCKWISE
+            .iter()
+            .copied()
+            .filter(|dir| possible.contains(&dir))
+            .collect()
+    }
 
-mod board;
+    /// Act on `key`. Returns `false` if it stops the game.
+    fn press(&mut self, key: Key) -> bool {
+        self.message = None;
+        let count = self.moves().len();
+        match key {
+            Key::Move(dir) => self.make(dir),
+            Key::Next if count > 0 => self.selected = (self.selected + 1) % count,
+            Key::Previous if count > 0 => self.selected = (self.selected + count - 1) % count,
+            Key::Play => match self.moves().get(self.selected) {
+                Some(&dir) => self.make(dir),
+                None => self.message = Some("There is no move to make."),
+            },
+            Key::Undo if self.board.score() > 1 => {
+                self.board.undo().unwrap();
+                self.selected = 0;
+            }
+            Key::Undo => self.message = Some("There is no move to undo."),
+            Key::Quit => return false,
+            _ => {}
+        }
+        true
+    }
+
+    /// Make the move `dir`, if it is legal.
+    fn make(&mut self, dir: Direction) {
+        match self.board.next_move(dir) {
+            Ok(board) => {
+                self.board = board;
+                self.selected = 0;
+            }
+            Err(_) => self.message = Some("That move is not legal here."),
+        }
+    }
+
+    /// Return the screen showing the game: the grid, with the current
+    /// position in bold on yellow and each cell a legal move can reach
+    /// showing the key for that move on green, or in reverse video if it is
+    /// selected; then the help, and a banner once the game is won or
+    /// blocked.
+    fn draw(&self) -> String {
+        let board = &self.board;
+        let width = board.cells().to_string().len();
+        let (x, y) = board.current_position();
+        let moves = self.moves();
+        let mut out = String::from("\x1b[H\x1b[2J");
+        writeln!(
+            out,
+            "square100 {}x{}: {} of {} cells filled\n",
+            board.width(),
+            board.height(),
+            board.score(),
+            board.cells()
+        )
+        .unwrap();
+        for row in 0..board.height() {
+            for column in 0..board.width() {
+                let target = moves
+                    .iter()
+                    .position(|&dir| board.target(x, y, dir) == Some((column, row)));
+                let (style, text) = match target {
+                    Some(i) => {
+                        let &(key, _) = KEYS.iter().find(|&&(_, dir)| dir == moves[i]).unwrap();
+                        let style = if i == self.selected { "1;7" } else { "30;42" };
+                        (style, (key as char).to_string())
+                    }
+                    None if board.blocked_at(column, row) => ("", "#".to_string()),
+                    None => match board.value_at(column, row) {
+                        0 => ("", ".".to_string()),
+                        value if (column, row) == (x, y) => ("1;30;43", value.to_string()),
+                        value => ("", value.to_string()),
+                    },
+                };
+                if style.is_empty() {
+                    write!(out, " {:>width$} ", text, width = width).unwrap();
+                } else {
+                    write!(
+                        out,
+                        "\x1b[{}m {:>width$} \x1b[0m",
+                        style,
+                        text,
+                        width = width
+                    )
+                    .unwrap();
+                }
+            }
+            out.push('\n');
+        }
+        out.push('\n');
+        out.push_str(HELP);
+        if board.is_won() {
+            writeln!(out, "\n*** Solved: all {} cells filled ***", board.cells()).unwrap();
+        } else if moves.is_empty() {
+            writeln!(
+                out,
+                "\n*** Blocked at {} of {}: press u to undo ***",
+                board.score(),
+                board.cells()
+            )
+            .unwrap();
+        }
+        if let Some(message) = self.message {
+            writeln!(out, "\n{}", message).unwrap();
+        }
+        out
+    }
+}
+
+/// Play `board` with the keys read from `input`, drawing the screen to
+/// `out` after each one, until the board is solved, the player quits or
+/// the input ends. Returns the board as it was left.
+fn play_with<I, W>(board: Board, mut input: I, out: &mut W) -> io::Result<Board>
+where
+    I: Iterator<Item = io::Result<u8>>,
+    W: Write,
+{
+    let mut game = Game::new(board);
+    loop {
+        write!(out, "{}", game.draw())?;
+        out.flush()?;
+        if game.board.is_won() {
+            return Ok(game.board);
+        }
+        match read_key(&mut input)? {
+            Some(key) if game.press(key) => {}
+            _ => return Ok(game.board),
+        }
+    }
+}
+
+#[cfg(unix)]
+/// RawMode has the terminal on standard input pass on each key as it is
+/// pressed, without echoing it, until it is dropped.
+struct RawMode {
+    saved: libc::termios,
+}
+
+#[cfg(unix)]
+impl RawMode {
+    /// Put the terminal in raw mode, or return `None` if standard input is
+    /// not a terminal.
+    fn enter() -> Option<RawMode> {
+        // The termios structure is plain data, filled in by `tcgetattr`.
+        unsafe {
+            if libc::isatty(libc::STDIN_FILENO) == 0 {
+                return None;
+            }
+            let mut termios: libc::termios = std::mem::zeroed();
+            if libc::tcgetattr(libc::STDIN_FILENO, &mut termios) != 0 {
+                return None;
+            }
+            let saved = termios;
+            // Without ISIG, Ctrl-C arrives as a key rather than killing the
+            // process, so the terminal is always restored.
+            termios.c_lflag &= !(libc::ICANON | libc::ECHO | libc::ISIG);
+            termios.c_cc[libc::VMIN] = 1;
+            termios.c_cc[libc::VTIME] = 0;
+            if libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &termios) != 0 {
+                return None;
+            }
+            Some(RawMode { saved })
+        }
+    }
+}
+
+#[cfg(unix)]
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        unsafe {
+            libc::tcsetattr(libc::STDIN_FILENO, libc::TCSANOW, &self.saved);
+        }
+    }
+}
+
+#[cfg(not(unix))]
+/// RawMode is not supported here, so keys are read once Enter is pressed.
+struct RawMode;
+
+#[cfg(not(unix))]
+impl RawMode {
+    fn enter() -> Option<RawMode> {
+        None
+    }
+}
+
+/// Run `square100 play` with `args`, playing a board at the terminal.
+fn play(args: &[String]) -> Result<bool, String> {
+    let options = Options::parse(args, &["size", "start"])?;
+    let (width, height) = options.get("size", (10, 10), parse_size)?;
+    let (x, y) = options.get("start", (0, 0), parse_cell)?;
+    let board = Board::try_with_dimensions(width, height)
+        .and_then(|board| board.start_at(x, y))
+        .map_err(|e| e.to_string())?;
+    let raw = RawMode::enter();
+    // Hide the cursor while playing.
+    print!("\x1b[?25l");
+    let result = play_with(board, io::stdin().lock().bytes(), &mut io::stdout());
+    print!("\x1b[?25h");
+    drop(raw);
+    Ok(result.map_err(|e| e.to_string())?.is_won())
+}
+
+/// The number of boards `generate` searches between solutions when showing
+/// that a puzzle has only one. A puzzle that takes longer is given another
+/// clue instead.
+const PROOF_NODES: u64 = 20_000;
+
+/// Return a puzzle on a `width` x `height` board with at least `count`
+/// clues besides the start, chosen with the random numbers seeded by
+/// `seed`. Clues are added until the puzzle has only one solution. The
+/// puzzle is returned as the record of that solution, with the clues in a
+/// `Clues` tag as "x y value" separated by commas. Returns an error if the
+/// board cannot be made or has too few cells for the clues.
+fn generate_puzzle(
+    width: usize,
+    height: usize,
+    count: usize,
+    seed: u64,
+) -> Result<GameRecord, String> {
+    let board = Board::try_with_dimensions(width, height).map_err(|e| e.to_string())?;
+    if count >= board.cells() {
+        return Err(format!(
+            "a {}x{} board has room for at most {} clues",
+            width,
+            height,
+            board.cells() - 1
+        ));
+    }
+    let config = SolverConfig {
+        order: MoveOrder::Warnsdorff,
+        ..SolverConfig::default()
+    };
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut starts: Vec<(usize, usize)> = board.empty_cells().collect();
+    starts.shuffle(&mut rng);
+    let solved = starts
+        .into_iter()
+        .find_map(|(x, y)| {
+            let start = board.start_at(x, y).ok()?;
+            solve_with_config(&start, &config).map(|moves| (start, moves))
+        })
+        .map(|(mut start, moves)| {
+            start.apply_moves(&moves).unwrap();
+            start
+        })
+        .ok_or_else(|| format!("no {}x{} board could be solved", width, height))?;
+
+    // Keep the values of `count` cells other than the start, then while the
+    // puzzle has another solution, add the value of a cell where that
+    // solution differs from this one.
+    let mut clues: Vec<(usize, usize, u16)> = index::sample(&mut rng, board.cells() - 1, count)
+        .into_iter()
+        .map(|i| {
+            let value = i as u16 + 2;
+            let (x, y) = solved.position_of(value).unwrap();
+            (x, y, value)
+        })
+        .collect();
+    clues.sort_by_key(|&(_, _, value)| value);
+    let (x, y) = solved.start_position().unwrap();
+    let mut puzzle = clues
+        .iter()
+        .try_fold(board, |puzzle, &(x, y, value)| puzzle.add_clue(x, y, value))
+        .and_then(|puzzle| puzzle.start_at(x, y))
+        .map_err(|e| e.to_string())?;
+    let limits = SolverLimits {
+        max_nodes: Some(PROOF_NODES),
+        ..SolverLimits::default()
+    };
+    loop {
+        let mut solver = Solver::new(&puzzle, &config);
+        let changes = loop {
+            match solver.run(&limits) {
+                Outcome::Solved(moves) => {
+                    let mut other = puzzle.clone();
+                    other.apply_moves(&moves).unwrap();
+                    let changes = other.diff(&solved);
+                    if !changes.is_empty() {
+                        break changes;
+                    }
+                }
+                Outcome::Exhausted => break Vec::new(),
+                // Uniqueness could not be shown in time, so any value not
+                // yet given will do.
+                _ => {
+                    break puzzle
+                        .empty_cells()
+                        .filter(|&(x, y)| puzzle.clue_at(x, y) == 0)
+                        .map(|(x, y)| CellChange {
+                            at: Pos { x, y },
+                            from: 0,
+                            to: solved.value_at(x, y),
+                        })
+                        .collect()
+                }
+            }
+        };
+        let change = match changes.choose(&mut rng) {
+            Some(&change) => change,
+            None => break,
+        };
+        puzzle = puzzle
+            .add_clue(change.at.x, change.at.y, change.to)
+            .map_err(|e| e.to_string())?;
+        clues.push((change.at.x, change.at.y, change.to));
+    }
+    clues.sort_by_key(|&(_, _, value)| value);
+
+    let mut record = GameRecord::new(&solved).map_err(|e| e.to_string())?;
+    let clues: Vec<String> = clues
+        .iter()
+        .map(|(x, y, value)| format!("{} {} {}", x, y, value))
+        .collect();
+    record.tags.push(("Clues".to_string(), clues.join(", ")));
+    record.tags.push(("Seed".to_string(), seed.to_string()));
+    Ok(record)
+}
+
+/// Run `square100 generate` with `args`, printing a puzzle and its
+/// solution as a game record.
+fn generate(args: &[String]) -> Result<bool, String> {
+    let options = Options::parse(args, &["size", "clues", "seed"])?;
+    let (width, height) = options.get("size", (10, 10), parse_size)?;
+    let count = options.get("clues", 12, |text| text.parse().ok())?;
+    let seed = options.get("seed", rand::random(), |text| text.parse().ok())?;
+    print!("{}", generate_puzzle(width, height, count, seed)?);
+    Ok(true)
+}
 
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().skip(1).collect();
+    let result = match args.first().map(String::as_str) {
+        Some("solve") => solve(&args[1..]),
+        Some("play") => play(&args[1..]),
+        Some("generate") => generate(&args[1..]),
+        _ => Err(USAGE.to_string()),
+    };
+    match result {
+        Ok(true) => {}
+        Ok(false) => process::exit(1),
+        Err(message) => {
+            eprintln!("{}", message);
+            process::exit(2);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Options are read as name and value pairs, and bad ones are reported.
+    fn options() {
+        let args: Vec<String> = ["--size", "6x7", "--start", "1,2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = Options::parse(&args, &["size", "start"]).unwrap();
+        assert_eq!(options.get("size", (10, 10), parse_size), Ok((6, 7)));
+        assert_eq!(options.get("start", (0, 0), parse_cell), Ok((1, 2)));
+        assert_eq!(options.get("seed", 3, |text| text.parse().ok()), Ok(3u64));
+        assert_eq!(parse_size("8"), Some((8, 8)));
+        assert_eq!(parse_cell("1"), None);
+        assert!(matches!(
+            parse_strategy("random", 5),
+            Some(MoveOrder::Random { seed: 5 })
+        ));
+        assert!(parse_strategy("fastest", 0).is_none());
+
+        assert!(Options::parse(&args, &["size"]).is_err());
+        assert!(Options::parse(&args[..1], &["size"]).is_err());
+        let options = Options::parse(&args, &["size", "start"]).unwrap();
+        assert!(options.get("start", (0, 0), parse_size).is_err());
+    }
+
+    #[test]
+    // Keys are read as they arrive, including the arrow keys' escape
+    // sequences.
+    fn play_keys() {
+        let mut input = b"6\x1b[C\x1b[Dx\nuq".iter().map(|&b| Ok(b));
+        let keys: Vec<Key> = std::iter::from_fn(|| read_key(&mut input).unwrap()).collect();
+        assert_eq!(
+            keys,
+            [
+                Key::Move(Direction::Right),
+                Key::Next,
+                Key::Previous,
+                Key::Other,
+                Key::Play,
+                Key::Undo,
+                Key::Quit
+            ]
+        );
+    }
+
+    #[test]
+    // The arrow keys step through the legal moves clockwise from Up, whose
+    // keys are shown on the grid, and a game is played until it is solved
+    // or the player quits.
+    fn play_moves() {
+        let mut game = Game::new(Board::new(5).start_at(0, 0).unwrap());
+        assert_eq!(
+            game.moves(),
+            [Direction::Right, Direction::DownRight, Direction::Down]
+        );
+        let screen = game.draw();
+        assert!(screen.contains("\x1b[1;7m  6 \x1b[0m"));
+        assert!(screen.contains("\x1b[30;42m  3 \x1b[0m"));
+        assert!(screen.contains("\x1b[1;30;43m  1 \x1b[0m"));
+        assert!(game.press(Key::Previous));
+        assert!(game.press(Key::Play));
+        assert_eq!(game.board.path(), [(0, 0), (0, 3)]);
+        assert!(game.press(Key::Move(Direction::Down)));
+        assert_eq!(game.message, Some("That move is not legal here."));
+        assert!(game.press(Key::Undo));
+        assert!(game.press(Key::Undo));
+        assert_eq!(game.message, Some("There is no move to undo."));
+        assert!(!game.press(Key::Quit));
+
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let mut out = Vec::new();
+        let board = play_with(board, b"6q8".iter().map(|&b| Ok(b)), &mut out).unwrap();
+        assert_eq!(board.path(), [(0, 0), (3, 0)]);
+
+        let moves: MoveList = "D R U DL DR U DL R UL D UR UL DL R UL R DL DR L U R DL UL D"
+            .parse()
+            .unwrap();
+        let mut keys: Vec<u8> = moves
+            .iter()
+            .map(|&dir| KEYS.iter().find(|&&(_, d)| d == dir).unwrap().0)
+            .collect();
+        keys.push(b'u');
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let mut out = Vec::new();
+        let board = play_with(board, keys.into_iter().map(Ok), &mut out).unwrap();
+        assert!(board.is_won());
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .ends_with("*** Solved: all 25 cells filled ***\n"));
+    }
+
+    #[test]
+    // A generated puzzle is the same for the same seed, its record replays
+    // to a solved board, the clues are values of that board, and they leave
+    // the puzzle no other solution.
+    fn generate_puzzles() {
+        use square100::solver::Solutions;
+
+        let record = generate_puzzle(6, 6, 5, 7).unwrap();
+        assert_eq!(record, generate_puzzle(6, 6, 5, 7).unwrap());
+        let solved = record.replay().unwrap();
+        assert!(solved.is_won());
+        let (name, clues) = &record.tags[0];
+        assert_eq!(name, "Clues");
+        let clues: Vec<Vec<usize>> = clues
+            .split(", ")
+            .map(|clue| clue.split(' ').map(|n| n.parse().unwrap()).collect())
+            .collect();
+        assert!(clues.len() >= 5);
+        let mut puzzle = Board::new(6);
+        for clue in &clues {
+            assert!(clue[2] > 1);
+            assert_eq!(solved.value_at(clue[0], clue[1]) as usize, clue[2]);
+            puzzle = puzzle.add_clue(clue[0], clue[1], clue[2] as u16).unwrap();
+        }
+        let (x, y) = record.start;
+        let puzzle = puzzle.start_at(x, y).unwrap();
+        assert_eq!(Solutions::new(&puzzle, &SolverConfig::default()).count(), 1);
+        assert_eq!(record.tags[1], ("Seed".to_string(), "7".to_string()));
+
+        assert!(generate_puzzle(5, 5, 25, 0).is_err());
+        assert!(generate_puzzle(5, 5, 24, 0).is_ok());
+    }
 }
@@ -0,0 +1,126 @@
+//! MiniZinc export, for handing hard boards to a constraint solver such as
+//! Gecode or Chuffed.
+//!
+//! The model numbers the cells that are not blocked from 1 in row-major order
+//! and has one variable per value giving the cell that holds it. The
+//! constraints say that:
+//!
+//! * no two values are held by the same cell,
+//! * the cell holding value `v` is one jump from the cell holding `v + 1`,
+//!   using a table of every legal jump on the board, and
+//! * the cells already filled on the board, and its clues, keep their values.
+//!
+//! The jumps are listed from `Board::target()`, so the model follows the same
+//! move rule as the board it was made from.
+
+use crate::board::{Board, Direction};
+use std::fmt::Write;
+
+impl Board {
+    /// Encode the problem of completing this board as a MiniZinc model. The
+    /// model prints the completed board as a grid of values, one row per
+    /// line.
+    pub fn to_minizinc(&self) -> String {
+        let (width, height) = (self.width(), self.height());
+        let cells = self.cells();
+        let (open, index) = self.open_cells();
+        let mut jumps = Vec::new();
+        let mut fixed = Vec::new();
+        for (cell, &(x, y)) in open.iter().enumerate() {
+            for &dir in Direction::iterator() {
+                if let Some((tx, ty)) = self.target(x, y, dir) {
+                    jumps.push((cell + 1, index[ty * width + tx] + 1));
+                }
+            }
+            for value in [self.value_at(x, y), self.clue_at(x, y)] {
+                if value > 0 {
+                    fixed.push((value, cell + 1));
+                }
+            }
+        }
+        // The cell number of each square of the grid, or 0 if it is blocked.
+        let ids: Vec<String> = (0..width * height)
+            .map(|i| {
+                let (x, y) = (i % width, i / width);
+                if self.blocked_at(x, y) {
+                    "0".to_string()
+                } else {
+                    (index[i] + 1).to_string()
+                }
+            })
+            .collect();
+
+        let mut out = String::new();
+        writeln!(out, "% square100 {}x{}", width, height).unwrap();
+        writeln!(out, "include \"globals.mzn\";").unwrap();
+        writeln!(out, "int: width = {};", width).unwrap();
+        writeln!(out, "int: n = {};", cells).unwrap();
+        writeln!(out, "set of int: Cell = 1..n;").unwrap();
+        writeln!(out, "set of int: Value = 1..n;").unwrap();
+        writeln!(
+            out,
+            "array[1..{}] of 0..n: id = [{}];",
+            width * height,
+            ids.join(", ")
+        )
+        .unwrap();
+        writeln!(out, "array[1..{}, 1..2] of Cell: jumps = [|", jumps.len()).unwrap();
+        for (i, (from, to)) in jumps.iter().enumerate() {
+            let end = if i + 1 == jumps.len() { "];" } else { "" };
+            writeln!(out, "  {}, {} |{}", from, to, end).unwrap();
+        }
+        writeln!(out, "array[Value] of var Cell: cell;").unwrap();
+        writeln!(out, "array[Cell] of var Value: value;").unwrap();
+        writeln!(out, "constraint inverse(cell, value);").unwrap();
+        writeln!(
+            out,
+            "constraint forall(v in 1..n-1)(table([cell[v], cell[v + 1]], jumps));"
+        )
+        .unwrap();
+        for (value, cell) in fixed {
+            writeln!(out, "constraint cell[{}] = {};", value, cell).unwrap();
+        }
+        writeln!(out, "solve satisfy;").unwrap();
+        writeln!(
+            out,
+            "output [if id[g] = 0 then \"  #\" else show_int(3, value[id[g]]) endif ++ if g mod width = 0 then \"\\n\" else \"\" endif | g in index_set(id)];"
+        )
+        .unwrap();
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // The model lists every legal jump once, fixes the starting cell and
+    // the clues, and leaves blocked cells unnumbered.
+    fn minizinc_model() {
+        let board = Board::with_clues(5, &[(4, 4, 25)])
+            .unwrap()
+            .start_at(1, 2)
+            .unwrap();
+        let model = board.to_minizinc();
+        let jumps = model
+            .lines()
+            .filter(|l| l.ends_with(" |") || l.ends_with(" |];"))
+            .count();
+        let expected: usize = (0..25)
+            .map(|c| {
+                Direction::iterator()
+                    .filter(|&&dir| board.target(c % 5, c / 5, dir).is_some())
+                    .count()
+            })
+            .sum();
+        assert_eq!(jumps, expected);
+        assert!(model.contains(&format!("array[1..{}, 1..2] of Cell", expected)));
+        assert!(model.contains("constraint cell[1] = 12;"));
+        assert!(model.contains("constraint cell[25] = 25;"));
+
+        let model = Board::new(5).block(0, 0).unwrap().to_minizinc();
+        assert!(model.contains("int: n = 24;"));
+        assert!(model.contains("id = [0, 1, 2,"));
+    }
+}
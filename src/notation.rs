@@ -0,0 +1,112 @@
+//! A compact notation for sequences of moves.
+//!
+//! A [`MoveList`] prints as the abbreviations of its directions separated by
+//! spaces, such as `R DL D UR`, or with the alternate flag (`{:#}`) as
+//! points of the compass with up as north, such as `E SW S NE`. It reads
+//! back from either, or from the full names of the directions, in any case
+//! and separated by spaces or commas, so that a solution can be shared as a
+//! line of text and replayed with `Board::apply_moves()`.
+
+use crate::board::{BoardError, Direction};
+use std::fmt;
+use std::ops::Deref;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// MoveList is a sequence of moves that prints and reads as text.
+pub struct MoveList(pub Vec<Direction>);
+
+impl Deref for MoveList {
+    type Target = [Direction];
+
+    fn deref(&self) -> &[Direction] {
+        &self.0
+    }
+}
+
+impl From<Vec<Direction>> for MoveList {
+    fn from(moves: Vec<Direction>) -> Self {
+        MoveList(moves)
+    }
+}
+
+impl From<&[Direction]> for MoveList {
+    fn from(moves: &[Direction]) -> Self {
+        MoveList(moves.to_vec())
+    }
+}
+
+impl From<MoveList> for Vec<Direction> {
+    fn from(moves: MoveList) -> Self {
+        moves.0
+    }
+}
+
+/// Print the moves as abbreviations, or with `{:#}` as points of the
+/// compass, separated by single spaces.
+impl fmt::Display for MoveList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, dir) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            if f.alternate() {
+                write!(f, "{}", dir.compass())?;
+            } else {
+                write!(f, "{}", dir.abbreviation())?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Read moves written in any of the forms read by `Direction::from_str()`,
+/// separated by whitespace or commas. Returns an error naming the first word
+/// that is not a move.
+impl FromStr for MoveList {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(|c: char| c.is_whitespace() || c == ',')
+            .filter(|word| !word.is_empty())
+            .map(Direction::from_str)
+            .collect::<Result<_, _>>()
+            .map(MoveList)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    // A move list prints in either notation, reads back from both, and
+    // replays on a board.
+    fn notation() {
+        let moves = MoveList(vec![
+            Direction::Right,
+            Direction::DownLeft,
+            Direction::Down,
+            Direction::UpRight,
+        ]);
+        assert_eq!(moves.to_string(), "R DL D UR");
+        assert_eq!(format!("{:#}", moves), "E SW S NE");
+        assert_eq!("R DL D UR".parse::<MoveList>().unwrap(), moves);
+        assert_eq!("e, sw,s ,NE\n".parse::<MoveList>().unwrap(), moves);
+        assert_eq!("Right DownLeft down ur".parse::<MoveList>().unwrap(), moves);
+        assert_eq!("".parse::<MoveList>().unwrap(), MoveList::default());
+        assert_eq!(
+            "R X D".parse::<MoveList>(),
+            Err(BoardError::BadNotation {
+                word: "X".to_string()
+            })
+        );
+        let mut board = Board::new(6).start_at(0, 0).unwrap();
+        board.apply_moves(&moves).unwrap();
+        assert_eq!(MoveList::from(board.history()), moves);
+        let all: MoveList = Direction::iterator().copied().collect::<Vec<_>>().into();
+        assert_eq!(all.to_string().parse::<MoveList>().unwrap(), all);
+        assert_eq!(format!("{:#}", all).parse::<MoveList>().unwrap(), all);
+    }
+}
@@ -0,0 +1,234 @@
+//! A board that shares its storage with the boards it was made from.
+//!
+//! Every move on a [`Board`] copies all of its cells, which is what a depth
+//! first search wants but is wasteful for a search that holds thousands of
+//! sibling states that differ from their parent in a single cell.
+//! [`PersistentBoard`] keeps its values in fixed-size chunks behind reference
+//! counts, and its path as a list linked back to the start. A move copies
+//! only the table of chunk pointers and the one chunk it writes to; the other
+//! chunks and the whole path are shared with the parent.
+//!
+//! Like [`FixedBoard`](crate::fixed::FixedBoard), it plays the game of a
+//! [`PlainBoard`].
+
+use crate::board::{Board, BoardError, Direction, MAX_SIZE, MIN_SIZE};
+use crate::plain::{Place, PlainBoard};
+use crate::pos::Pos;
+use std::convert::TryFrom;
+use std::fmt;
+use std::sync::Arc;
+
+/// The number of cells in each chunk of values.
+const CHUNK: usize = 16;
+
+/// A chunk of values, shared between boards until one of them writes to it.
+type Chunk = Arc<[u16; CHUNK]>;
+
+/// One step of the path, linked back to the step before it.
+struct Step {
+    /// The row-major index of the cell the step filled.
+    index: u16,
+    /// The move that reached the cell, or `None` for the starting cell.
+    dir: Option<Direction>,
+    prev: Option<Arc<Step>>,
+}
+
+impl Drop for Step {
+    // Unlink the steps one at a time, so that dropping a long path that is
+    // not shared does not recurse once per step.
+    fn drop(&mut self) {
+        let mut prev = self.prev.take();
+        while let Some(step) = prev {
+            match Arc::try_unwrap(step) {
+                Ok(mut step) => prev = step.prev.take(),
+                Err(_) => break,
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+/// PersistentBoard is a puzzle board whose copies share unchanged storage.
+pub struct PersistentBoard {
+    width: usize,
+    height: usize,
+    /// The values of the cells in row-major order, `CHUNK` cells at a time,
+    /// with 0 for empty. The last chunk is padded with zeros.
+    chunks: Vec<Chunk>,
+    /// The last step of the path, or `None` if the board is not started.
+    last: Option<Arc<Step>>,
+    /// The value of the last cell set, or 0 if the board is not started.
+    score: u16,
+}
+
+impl PersistentBoard {
+    /// Create a new empty board with the dimensions `size` x `size`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `size` is not between `MIN_SIZE` and `MAX_SIZE`.
+    pub fn new(size: usize) -> Self {
+        PersistentBoard::try_with_dimensions(size, size).unwrap_or_else(|err| panic!("{}", err))
+    }
+
+    /// Return the moves made since the board was started, oldest first.
+    pub fn history(&self) -> Vec<Direction> {
+        let mut history: Vec<Direction> = self.steps().filter_map(|step| step.dir).collect();
+        history.reverse();
+        history
+    }
+
+    /// Return the locations of the filled cells in the order they were
+    /// filled, so that the cell holding value `v` is at index `v - 1`.
+    pub fn path(&self) -> Vec<(usize, usize)> {
+        let mut path: Vec<(usize, usize)> = self
+            .steps()
+            .map(|step| Pos::from_index(step.index as usize, self.width).into())
+            .collect();
+        path.reverse();
+        path
+    }
+
+    /// Return the steps of the path, newest first.
+    fn steps(&self) -> impl Iterator<Item = &Step> {
+        std::iter::successors(self.last.as_deref(), |step| step.prev.as_deref())
+    }
+}
+
+/// Only the chunk that holds the new value is copied.
+impl Place for PersistentBoard {
+    fn place(&self, x: usize, y: usize, dir: Option<Direction>) -> Self {
+        let i = y * self.width + x;
+        let mut board = self.clone();
+        board.score += 1;
+        Arc::make_mut(&mut board.chunks[i / CHUNK])[i % CHUNK] = board.score;
+        board.last = Some(Arc::new(Step {
+            index: i as u16,
+            dir,
+            prev: self.last.clone(),
+        }));
+        board
+    }
+}
+
+/// A persistent board can have the dimensions of any `Board`.
+impl PlainBoard for PersistentBoard {
+    fn try_with_dimensions(width: usize, height: usize) -> Result<Self, BoardError> {
+        let range = MIN_SIZE..=MAX_SIZE;
+        if !range.contains(&width) || !range.contains(&height) {
+            return Err(BoardError::BadSize { width, height });
+        }
+        // Every chunk starts out as the same empty chunk.
+        let empty: Chunk = Arc::new([0; CHUNK]);
+        Ok(PersistentBoard {
+            width,
+            height,
+            chunks: vec![empty; (width * height).div_ceil(CHUNK)],
+            last: None,
+            score: 0,
+        })
+    }
+
+    #[inline]
+    fn width(&self) -> usize {
+        self.width
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.height
+    }
+
+    #[inline]
+    fn value_at(&self, x: usize, y: usize) -> u16 {
+        let i = y * self.width + x;
+        self.chunks[i / CHUNK][i % CHUNK]
+    }
+
+    fn current_position(&self) -> (usize, usize) {
+        self.last.as_ref().map_or((0, 0), |step| {
+            Pos::from_index(step.index as usize, self.width).into()
+        })
+    }
+
+    #[inline]
+    fn score(&self) -> usize {
+        self.score as usize
+    }
+}
+
+impl PartialEq for PersistentBoard {
+    fn eq(&self, other: &Self) -> bool {
+        self.width == other.width
+            && self.height == other.height
+            && self.current_position() == other.current_position()
+            && self
+                .chunks
+                .iter()
+                .zip(&other.chunks)
+                .all(|(a, b)| Arc::ptr_eq(a, b) || a == b)
+    }
+}
+
+impl Eq for PersistentBoard {}
+
+impl fmt::Debug for PersistentBoard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("PersistentBoard")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .field("score", &self.score)
+            .field("position", &self.current_position())
+            .finish()
+    }
+}
+
+impl From<&PersistentBoard> for Board {
+    fn from(board: &PersistentBoard) -> Board {
+        board.to_board()
+    }
+}
+
+/// Convert a board that plays the plain game.
+impl TryFrom<&Board> for PersistentBoard {
+    type Error = BoardError;
+
+    fn try_from(board: &Board) -> Result<Self, Self::Error> {
+        PersistentBoard::from_board(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A move shares every chunk it leaves alone and the whole path with the
+    // board it was made from, and the path replays as a board's does.
+    fn shares_storage() {
+        let moves = [Direction::Right, Direction::DownLeft, Direction::Down];
+        let mut board = Board::with_dimensions(6, 8).start_at(1, 0).unwrap();
+        let mut persistent = PersistentBoard::try_with_dimensions(6, 8)
+            .unwrap()
+            .start_at(1, 0)
+            .unwrap();
+        for &dir in &moves {
+            board = board.next_move(dir).unwrap();
+            let next = persistent.next_move(dir).unwrap();
+            let (x, y) = next.current_position();
+            let written = (y * 6 + x) / CHUNK;
+            for (i, (a, b)) in persistent.chunks.iter().zip(&next.chunks).enumerate() {
+                assert_eq!(Arc::ptr_eq(a, b), i != written);
+            }
+            assert!(Arc::ptr_eq(
+                persistent.last.as_ref().unwrap(),
+                next.last.as_ref().unwrap().prev.as_ref().unwrap()
+            ));
+            persistent = next;
+        }
+        assert_eq!(persistent.history(), board.history());
+        assert_eq!(persistent.path(), board.path());
+        assert_eq!(Board::from(&persistent), board);
+        assert!(PersistentBoard::new(5).next_move(Direction::Up).is_err());
+    }
+}
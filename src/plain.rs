@@ -0,0 +1,197 @@
+//! Boards that play the plain game.
+//!
+//! [`Board`] can hold clues, blocked cells and a history to undo, and follow
+//! any move rule. The boards of [`fixed`](crate::fixed),
+//! [`small`](crate::small) and [`persistent`](crate::persistent) drop all of
+//! that in exchange for cheaper storage, and play only the plain game: a
+//! rectangle of open cells, the default move rule without wrapping, and no
+//! undo. [`PlainBoard`] holds the rules of that game, so each of them only
+//! says how its cells are stored. Convert to a `Board` for the rest.
+
+use crate::board::{Board, BoardError, Direction};
+use crate::pos::Pos;
+
+mod private {
+    use crate::board::Direction;
+
+    /// Place is how a plain board fills a cell. It is kept out of reach of
+    /// other crates, since it does not check that the move is legal.
+    pub trait Place: Sized {
+        /// Return a copy of the board with the next value placed at (x, y),
+        /// which must be empty, reached by the move `dir`, or `None` for the
+        /// first cell.
+        fn place(&self, x: usize, y: usize, dir: Option<Direction>) -> Self;
+    }
+}
+
+pub(crate) use private::Place;
+
+/// PlainBoard is a board that plays the plain game. Its cells are numbered
+/// like those of a `Board`, and its moves are checked in the same way.
+pub trait PlainBoard: Place {
+    /// Create a new empty board `width` cells wide and `height` cells high,
+    /// or return an error if the board cannot have those dimensions.
+    fn try_with_dimensions(width: usize, height: usize) -> Result<Self, BoardError>;
+
+    /// Return the width of the board.
+    fn width(&self) -> usize;
+
+    /// Return the height of the board.
+    fn height(&self) -> usize;
+
+    /// Return the value at (x, y), where 0 is an empty cell.
+    fn value_at(&self, x: usize, y: usize) -> u16;
+
+    /// Return the (x, y) location of the last cell set in the board.
+    fn current_position(&self) -> (usize, usize);
+
+    /// Return the highest value on the board.
+    fn score(&self) -> usize;
+
+    #[inline]
+    /// Return the cell reached by jumping in direction `dir` from (x, y), or
+    /// `None` if the jump would leave the board.
+    fn target(&self, x: usize, y: usize, dir: Direction) -> Option<(usize, usize)> {
+        Pos::new(x, y)
+            .offset(dir)
+            .filter(|p| p.in_bounds(self.width(), self.height()))
+            .map(Pos::into)
+    }
+
+    /// Return the number of empty cells one jump away from (x, y).
+    fn free_neighbors(&self, x: usize, y: usize) -> u8 {
+        Direction::iterator()
+            .filter_map(|&dir| self.target(x, y, dir))
+            .filter(|&(x, y)| self.value_at(x, y) == 0)
+            .count() as u8
+    }
+
+    #[inline]
+    /// Return `true` if the board has been started.
+    fn is_started(&self) -> bool {
+        self.score() > 0
+    }
+
+    #[inline]
+    /// Return `true` if every cell has been filled.
+    fn is_won(&self) -> bool {
+        self.score() == self.width() * self.height()
+    }
+
+    /// Return the moves that can be made from the current position, in the
+    /// order of `Direction::iterator()`. Nothing is allocated.
+    fn possible_moves(&self) -> impl Iterator<Item = Direction> + '_ {
+        Direction::iterator()
+            .copied()
+            .filter(move |&dir| self.valid_move(dir).is_some())
+    }
+
+    /// Return the cell a move in direction `dir` lands on, if it is legal.
+    fn valid_move(&self, dir: Direction) -> Option<(usize, usize)> {
+        if !self.is_started() {
+            return None;
+        }
+        let (x, y) = self.current_position();
+        self.target(x, y, dir)
+            .filter(|&(x, y)| self.value_at(x, y) == 0)
+    }
+
+    /// Return a copy of the board started by placing a 1 at (x, y).
+    fn start_at(&self, x: usize, y: usize) -> Result<Self, BoardError> {
+        if x >= self.width() || y >= self.height() {
+            return Err(BoardError::IndexOutOfRange {
+                x,
+                y,
+                max: self.width().max(self.height()),
+            });
+        }
+        if self.is_started() {
+            return Err(BoardError::ValueUsed { x, y, value: 1 });
+        }
+        Ok(self.place(x, y, None))
+    }
+
+    /// Return a copy of the board with the next move made in direction `dir`.
+    fn next_move(&self, dir: Direction) -> Result<Self, BoardError> {
+        if !self.is_started() {
+            return Err(BoardError::NotStarted);
+        }
+        match self.valid_move(dir) {
+            Some((x, y)) => Ok(self.place(x, y, Some(dir))),
+            None => {
+                let (x, y) = self.current_position();
+                Err(BoardError::BadDirection { dir, x, y })
+            }
+        }
+    }
+
+    /// Return a `Board` holding the same game.
+    fn to_board(&self) -> Board {
+        let values: Vec<u16> = (0..self.height())
+            .flat_map(|y| (0..self.width()).map(move |x| (x, y)))
+            .map(|(x, y)| self.value_at(x, y))
+            .collect();
+        Board::with_dimensions(self.width(), self.height())
+            .replay_values(&values)
+            .expect("a plain board holds a legal game")
+    }
+
+    /// Return a board holding the same game as `board`, or an error if
+    /// `board` does not play the plain game or has dimensions this board
+    /// cannot have. The moves that can be redone are dropped, since a plain
+    /// board has no undo.
+    fn from_board(board: &Board) -> Result<Self, BoardError> {
+        board.check_plain()?;
+        let mut plain = Self::try_with_dimensions(board.width(), board.height())?;
+        if let Some((x, y)) = board.start_position() {
+            plain = plain.start_at(x, y)?;
+            for &dir in board.history() {
+                plain = plain.next_move(dir)?;
+            }
+        }
+        Ok(plain)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixed::FixedBoard;
+    use crate::persistent::PersistentBoard;
+    use crate::small::SmallBoard;
+    use std::fmt::Debug;
+
+    /// Check that a `P` plays the same game as a board `width` cells wide and
+    /// `height` cells high, and converts to and from one.
+    fn matches_board<P: PlainBoard + PartialEq + Debug>(width: usize, height: usize) {
+        let moves = [Direction::Right, Direction::DownLeft, Direction::Down];
+        let mut board = Board::with_dimensions(width, height)
+            .start_at(1, 0)
+            .unwrap();
+        let empty = P::try_with_dimensions(width, height).unwrap();
+        let mut plain = empty.start_at(1, 0).unwrap();
+        for &dir in &moves {
+            board = board.next_move(dir).unwrap();
+            plain = plain.next_move(dir).unwrap();
+            let expected: Vec<Direction> = board.possible_moves().into_iter().copied().collect();
+            assert_eq!(plain.possible_moves().collect::<Vec<_>>(), expected);
+            assert_eq!(plain.current_position(), board.current_position());
+            assert_eq!(plain.free_neighbors(4, 4), board.free_neighbors(4, 4));
+        }
+        assert_eq!(plain.to_board(), board);
+        assert_eq!(P::from_board(&board).unwrap(), plain);
+        let blocked = Board::with_dimensions(width, height).block(5, 5).unwrap();
+        assert!(P::from_board(&blocked).is_err());
+        assert!(plain.next_move(Direction::Up).is_err());
+        assert!(empty.next_move(Direction::Up).is_err());
+    }
+
+    #[test]
+    // Every plain board plays the same game as a board of the same size, and
+    // converts to and from one, but not from a board with blocked cells.
+    fn plain_boards_match_board() {
+        matches_board::<FixedBoard<6>>(6, 6);
+        matches_board::<SmallBoard>(11, 11);
+        matches_board::<PersistentBoard>(6, 8);
+    }
+}
@@ -0,0 +1,139 @@
+//! Cell locations on a board.
+//!
+//! Most of the board API takes and returns locations as `(x, y)` pairs, and
+//! converts between them and indices into the row-major cell vectors. [`Pos`]
+//! names the two halves of a location and keeps that arithmetic, and the
+//! arithmetic of jumping from one cell to another, in one place.
+
+use crate::board::{Direction, MoveRule};
+use std::fmt;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Pos is the location of a cell, with `x` counting columns from the left
+/// and `y` counting rows from the top.
+pub struct Pos {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Pos {
+    /// Return the location (x, y).
+    pub fn new(x: usize, y: usize) -> Self {
+        Pos { x, y }
+    }
+
+    /// Return the location of the cell at index `i` of a row-major grid
+    /// `width` cells wide.
+    pub fn from_index(i: usize, width: usize) -> Self {
+        Pos {
+            x: i % width,
+            y: i / width,
+        }
+    }
+
+    /// Return the index of this cell in a row-major grid `width` cells wide.
+    pub fn index(self, width: usize) -> usize {
+        self.y * width + self.x
+    }
+
+    /// Return `true` if this cell lies on a grid `width` cells wide and
+    /// `height` cells high.
+    pub fn in_bounds(self, width: usize, height: usize) -> bool {
+        self.x < width && self.y < height
+    }
+
+    /// Return the cell reached by jumping from this one in direction `dir`
+    /// under the default move rule, or `None` if the jump would go past the
+    /// top or left edge. The other edges depend on the size of the board;
+    /// see `in_bounds()`.
+    pub fn offset(self, dir: Direction) -> Option<Pos> {
+        self.jump(MoveRule::default(), dir)
+    }
+
+    /// Return the cell reached by moving from this one in direction `dir`
+    /// under `rule`, or `None` if the rule has no such move or it would go
+    /// past the top or left edge.
+    pub fn jump(self, rule: MoveRule, dir: Direction) -> Option<Pos> {
+        let (dx, dy) = rule.offset(dir)?;
+        let x = (self.x as i64).checked_add(dx as i64)?;
+        let y = (self.y as i64).checked_add(dy as i64)?;
+        if x < 0 || y < 0 {
+            None
+        } else {
+            Some(Pos::new(x as usize, y as usize))
+        }
+    }
+
+    /// Return the cell reached by moving from this one in direction `dir`
+    /// under `rule` on a grid `width` cells wide and `height` cells high
+    /// whose opposite edges are joined, or `None` if the rule has no such
+    /// move.
+    pub fn jump_wrapping(
+        self,
+        rule: MoveRule,
+        dir: Direction,
+        width: usize,
+        height: usize,
+    ) -> Option<Pos> {
+        let (dx, dy) = rule.offset(dir)?;
+        let x = (self.x as i64 + dx as i64).rem_euclid(width as i64);
+        let y = (self.y as i64 + dy as i64).rem_euclid(height as i64);
+        Some(Pos::new(x as usize, y as usize))
+    }
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from((x, y): (usize, usize)) -> Self {
+        Pos { x, y }
+    }
+}
+
+impl From<Pos> for (usize, usize) {
+    fn from(pos: Pos) -> Self {
+        (pos.x, pos.y)
+    }
+}
+
+impl fmt::Display for Pos {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Indices and jumps agree with the row-major layout of the board, and
+    // jumps off the top or left edge are refused.
+    fn pos() {
+        let pos = Pos::new(2, 3);
+        assert_eq!(pos.index(5), 17);
+        assert_eq!(Pos::from_index(17, 5), pos);
+        assert_eq!(pos.offset(Direction::Up), Some(Pos::new(2, 0)));
+        assert_eq!(pos.offset(Direction::UpLeft), Some(Pos::new(0, 1)));
+        assert_eq!(pos.offset(Direction::Left), None);
+        assert_eq!(pos.offset(Direction::Down), Some(Pos::new(2, 6)));
+        let rule = MoveRule::Jump {
+            straight: 1,
+            diagonal: 0,
+        };
+        assert_eq!(pos.jump(rule, Direction::Left), Some(Pos::new(1, 3)));
+        assert_eq!(pos.jump(rule, Direction::UpLeft), None);
+        assert_eq!(
+            pos.jump_wrapping(MoveRule::default(), Direction::Left, 5, 5),
+            Some(Pos::new(4, 3))
+        );
+        assert_eq!(
+            pos.jump_wrapping(MoveRule::default(), Direction::DownRight, 4, 5),
+            Some(Pos::new(0, 0))
+        );
+        assert!(!Pos::new(2, 6).in_bounds(5, 5));
+        assert!(Pos::new(2, 6).in_bounds(5, 7));
+        assert_eq!(<(usize, usize)>::from(pos), (2, 3));
+        assert_eq!(Pos::from((2, 3)), pos);
+        assert_eq!(pos.to_string(), "(2, 3)");
+    }
+}
@@ -0,0 +1,318 @@
+//! A text format for archiving finished games.
+//!
+//! A [`GameRecord`] holds what is needed to replay a game, the size of the
+//! board, the starting square, the move rule and the moves, along with
+//! where it came from: the date, the solver that found it and how long that
+//! took. Like a PGN file for chess, it is written as tag pairs, one per
+//! line, followed by the moves in the notation of
+//! [`MoveList`]:
+//!
+//! ```text
+//! [Size "5"]
+//! [Start "0 0"]
+//! [Date "2024-01-31"]
+//! [Solver "warnsdorff"]
+//! [Elapsed "0.0125"]
+//!
+//! D R U DL DR U DL R UL D UR UL DL R UL R DL DR L U R DL UL D
+//! ```
+//!
+//! `Rule` and `Wrap` tags are written only for boards that need them, and
+//! tags the format does not know are kept in [`GameRecord::tags`].
+
+use crate::board::{Board, BoardError, MoveRule};
+use crate::notation::MoveList;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+
+#[derive(Debug, Clone, PartialEq)]
+/// GameRecord is a game with the facts about how it was played. `date` and
+/// `solver` are free text, such as `2024-01-31` and `warnsdorff`.
+pub struct GameRecord {
+    pub width: usize,
+    pub height: usize,
+    pub start: (usize, usize),
+    pub rule: MoveRule,
+    pub wrap: bool,
+    pub date: Option<String>,
+    pub solver: Option<String>,
+    pub elapsed: Option<Duration>,
+    pub moves: MoveList,
+    /// Other tags, as (name, value), in the order they were read.
+    pub tags: Vec<(String, String)>,
+}
+
+impl GameRecord {
+    /// Return a record of the game played on `board`, with no date, solver
+    /// or time. Returns an error if the board has not been started, or has
+    /// clues or blocked cells, which a record cannot hold.
+    pub fn new(board: &Board) -> Result<GameRecord, BoardError> {
+        if board.has_clues() || !board.blocked().is_empty() {
+            return Err(BoardError::InvalidGrid {
+                reason: "a record cannot hold clues or blocked cells".to_string(),
+            });
+        }
+        Ok(GameRecord {
+            width: board.width(),
+            height: board.height(),
+            start: board.start_position().ok_or(BoardError::NotStarted)?,
+            rule: board.rule(),
+            wrap: board.wraps(),
+            date: None,
+            solver: None,
+            elapsed: None,
+            moves: MoveList::from(board.history()),
+            tags: Vec::new(),
+        })
+    }
+
+    /// Play the game again, returning the board it ends on.
+    pub fn replay(&self) -> Result<Board, BoardError> {
+        let (x, y) = self.start;
+        let mut board = Board::try_with_dimensions(self.width, self.height)?
+            .with_rule(self.rule)?
+            .with_wrap(self.wrap)?
+            .start_at(x, y)?;
+        board.apply_moves(&self.moves)?;
+        Ok(board)
+    }
+
+    /// Write the record to the file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.to_string())
+    }
+
+    /// Read a record written by [`save`](GameRecord::save).
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<GameRecord> {
+        fs::read_to_string(path)?
+            .parse()
+            .map_err(|e: BoardError| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))
+    }
+}
+
+/// Write the value of a tag in quotes, with quotes and backslashes escaped.
+fn quote(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// Write `elapsed` as seconds, with as many decimals as it needs.
+fn seconds(elapsed: Duration) -> String {
+    let fraction = format!("{:09}", elapsed.subsec_nanos());
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        elapsed.as_secs().to_string()
+    } else {
+        format!("{}.{}", elapsed.as_secs(), fraction)
+    }
+}
+
+/// Read a number of seconds written by [`seconds`].
+fn parse_seconds(s: &str) -> Option<Duration> {
+    let (whole, fraction) = s.split_once('.').unwrap_or((s, ""));
+    if fraction.len() > 9 || !fraction.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let nanos = format!("{:0<9}", fraction).parse().ok()?;
+    Some(Duration::new(whole.parse().ok()?, nanos))
+}
+
+impl fmt::Display for GameRecord {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut tag = |name: &str, value: &str| writeln!(f, "[{} \"{}\"]", name, quote(value));
+        if self.width == self.height {
+            tag("Size", &self.width.to_string())?;
+        } else {
+            tag("Size", &format!("{}x{}", self.width, self.height))?;
+        }
+        tag("Start", &format!("{} {}", self.start.0, self.start.1))?;
+        match self.rule {
+            rule if rule == MoveRule::default() => {}
+            MoveRule::Jump { straight, diagonal } => {
+                tag("Rule", &format!("jump {} {}", straight, diagonal))?
+            }
+            MoveRule::Knight => tag("Rule", "knight")?,
+        }
+        if self.wrap {
+            tag("Wrap", "true")?;
+        }
+        if let Some(date) = &self.date {
+            tag("Date", date)?;
+        }
+        if let Some(solver) = &self.solver {
+            tag("Solver", solver)?;
+        }
+        if let Some(elapsed) = self.elapsed {
+            tag("Elapsed", &seconds(elapsed))?;
+        }
+        for (name, value) in &self.tags {
+            tag(name, value)?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{}", self.moves)
+    }
+}
+
+/// Read a record in the format written by `Display`. The tags may come in
+/// any order, but `Size` and `Start` are required, and the moves follow the
+/// tags, over as many lines as they need.
+impl FromStr for GameRecord {
+    type Err = BoardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = |reason: String| BoardError::InvalidGrid { reason };
+        let mut tags = Vec::new();
+        let mut lines = s.lines().map(str::trim).peekable();
+        while let Some(line) = lines.next_if(|line| line.is_empty() || line.starts_with('[')) {
+            if line.is_empty() {
+                continue;
+            }
+            let pair = line
+                .strip_prefix('[')
+                .and_then(|line| line.strip_suffix(']'))
+                .and_then(|line| line.split_once(' '))
+                .and_then(|(name, value)| {
+                    let value = value.trim().strip_prefix('"')?.strip_suffix('"')?;
+                    let mut unquoted = String::new();
+                    let mut chars = value.chars();
+                    while let Some(c) = chars.next() {
+                        unquoted.push(if c == '\\' { chars.next()? } else { c });
+                    }
+                    Some((name.to_string(), unquoted))
+                })
+                .ok_or_else(|| invalid(format!("malformed tag '{}'", line)))?;
+            tags.push(pair);
+        }
+        let mut take = |name: &str| {
+            tags.iter()
+                .position(|(tag, _)| tag == name)
+                .map(|i| tags.remove(i).1)
+        };
+        let number = |word: &str| {
+            word.parse::<usize>()
+                .map_err(|_| invalid(format!("'{}' is not a number", word)))
+        };
+        let byte = |word: &str| {
+            word.parse::<u8>()
+                .map_err(|_| invalid(format!("'{}' is not a number from 0 to 255", word)))
+        };
+        let size = take("Size").ok_or_else(|| invalid("missing tag 'Size'".to_string()))?;
+        let (width, height) = match size.split_once('x') {
+            Some((width, height)) => (number(width)?, number(height)?),
+            None => (number(&size)?, number(&size)?),
+        };
+        let start = take("Start").ok_or_else(|| invalid("missing tag 'Start'".to_string()))?;
+        let start = match start.split_whitespace().collect::<Vec<_>>().as_slice() {
+            [x, y] => (number(x)?, number(y)?),
+            _ => return Err(invalid(format!("malformed start '{}'", start))),
+        };
+        let rule = match take("Rule") {
+            None => MoveRule::default(),
+            Some(rule) => match rule.split_whitespace().collect::<Vec<_>>().as_slice() {
+                ["jump", straight, diagonal] => MoveRule::Jump {
+                    straight: byte(straight)?,
+                    diagonal: byte(diagonal)?,
+                },
+                ["knight"] => MoveRule::Knight,
+                _ => return Err(invalid(format!("unknown move rule '{}'", rule))),
+            },
+        };
+        let wrap = match take("Wrap").as_deref() {
+            None | Some("false") => false,
+            Some("true") => true,
+            Some(wrap) => return Err(invalid(format!("malformed wrap '{}'", wrap))),
+        };
+        let elapsed = match take("Elapsed") {
+            None => None,
+            Some(elapsed) => Some(
+                parse_seconds(&elapsed)
+                    .ok_or_else(|| invalid(format!("malformed time '{}'", elapsed)))?,
+            ),
+        };
+        let (date, solver) = (take("Date"), take("Solver"));
+        let moves = lines.collect::<Vec<_>>().join(" ").parse()?;
+        Ok(GameRecord {
+            width,
+            height,
+            start,
+            rule,
+            wrap,
+            date,
+            solver,
+            elapsed,
+            moves,
+            tags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::solve;
+
+    #[test]
+    // A solved game survives a round trip through a file, metadata and
+    // all, and replays to the same board.
+    fn record_round_trip() {
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let mut board = start.clone();
+        board.apply_moves(&solve(&start).unwrap()).unwrap();
+        let record = GameRecord {
+            date: Some("2024-01-31".to_string()),
+            solver: Some("depth-first \"plain\"".to_string()),
+            elapsed: Some(Duration::from_micros(12_500)),
+            tags: vec![("Site".to_string(), "home".to_string())],
+            ..GameRecord::new(&board).unwrap()
+        };
+        let text = record.to_string();
+        assert!(text.starts_with("[Size \"5\"]\n[Start \"0 0\"]\n[Date \"2024-01-31\"]\n"));
+        assert!(text.contains("[Solver \"depth-first \\\"plain\\\"\"]\n"));
+        assert!(text.contains("[Elapsed \"0.0125\"]\n[Site \"home\"]\n\nD "));
+        let path = std::env::temp_dir().join(format!("square100-{}-record", std::process::id()));
+        record.save(&path).unwrap();
+        let read = GameRecord::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(read, record);
+        assert_eq!(read.replay().unwrap(), board);
+    }
+
+    #[test]
+    // The rule and wrap mode are recorded when they are not the default,
+    // and records that are malformed or do not replay are rejected.
+    fn record_rule() {
+        let board = Board::with_dimensions(6, 7)
+            .with_rule(MoveRule::Knight)
+            .unwrap()
+            .with_wrap(true)
+            .unwrap()
+            .start_at(1, 2)
+            .unwrap();
+        let record = GameRecord::new(&board).unwrap();
+        let text = record.to_string();
+        assert_eq!(
+            text,
+            "[Size \"6x7\"]\n[Start \"1 2\"]\n[Rule \"knight\"]\n[Wrap \"true\"]\n\n\n"
+        );
+        assert_eq!(text.parse::<GameRecord>().unwrap(), record);
+        assert_eq!(record.replay().unwrap(), board);
+
+        assert!(GameRecord::new(&Board::new(5)).is_err());
+        assert!("[Start \"0 0\"]\n\nR".parse::<GameRecord>().is_err());
+        assert!("[Size \"5\"\n[Start \"0 0\"]"
+            .parse::<GameRecord>()
+            .is_err());
+        assert!("[Size \"5\"]\n[Start \"0 0\"]\n[Elapsed \"1.2.3\"]"
+            .parse::<GameRecord>()
+            .is_err());
+        assert!(matches!(
+            "[Size \"5\"]\n[Start \"0 0\"]\n[Rule \"jump 259 2\"]".parse::<GameRecord>(),
+            Err(BoardError::InvalidGrid { .. })
+        ));
+        let bad: GameRecord = "[Size \"5\"]\n[Start \"0 0\"]\n\nU".parse().unwrap();
+        assert!(bad.replay().is_err());
+    }
+}
@@ -0,0 +1,103 @@
+//! Coloured pictures of boards for terminals.
+
+use crate::board::Board;
+
+/// A colour, as red, green and blue.
+type Rgb = [u8; 3];
+
+/// The colours values run through, from 1 to the last value.
+const GRADIENT: [Rgb; 3] = [[70, 110, 220], [80, 190, 90], [220, 70, 60]];
+
+/// The colour of the current position.
+const CURRENT: Rgb = [250, 220, 40];
+
+/// The colour of blocked cells.
+const BLOCKED: Rgb = [60, 60, 60];
+
+/// Return the colour of `value` on a board whose path runs to `last`.
+fn shade(value: u16, last: usize) -> Rgb {
+    let t = if last > 1 {
+        (value as f64 - 1.0) / (last as f64 - 1.0)
+    } else {
+        0.0
+    };
+    let scaled = t * (GRADIENT.len() - 1) as f64;
+    let i = (scaled.floor() as usize).min(GRADIENT.len() - 2);
+    let f = scaled - i as f64;
+    let mut colour = [0; 3];
+    for (c, (&a, &b)) in colour
+        .iter_mut()
+        .zip(GRADIENT[i].iter().zip(&GRADIENT[i + 1]))
+    {
+        *c = (a as f64 + (b as f64 - a as f64) * f).round() as u8;
+    }
+    colour
+}
+
+/// Return the grid of `board` as text for a terminal, one row per line,
+/// with ANSI escape codes colouring each filled cell along a gradient from
+/// blue for 1 through green to red for the last cell of the path. The
+/// current position is shown in bold on yellow, blocked cells as `#` on dark
+/// grey, and empty cells as `.` uncoloured. Needs a terminal that shows
+/// 24-bit colour.
+pub fn ansi(board: &Board) -> String {
+    let width = board.cells().to_string().len();
+    let current = Some(board.current_position()).filter(|_| board.is_started());
+    // Each cell is padded by a space on either side, so that its colour
+    // frames its text.
+    let cell = |text: &str, colour: Option<Rgb>, bold: bool| {
+        let text = format!(" {:>width$} ", text, width = width);
+        match colour {
+            None => text,
+            Some([r, g, b]) => format!(
+                "\x1b[{}38;2;0;0;0;48;2;{};{};{}m{}\x1b[0m",
+                if bold { "1;" } else { "" },
+                r,
+                g,
+                b,
+                text
+            ),
+        }
+    };
+    let mut out = String::new();
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            out.push_str(&match board.value_at(x, y) {
+                _ if board.blocked_at(x, y) => cell("#", Some(BLOCKED), false),
+                0 => cell(".", None, false),
+                value if current == Some((x, y)) => cell(&value.to_string(), Some(CURRENT), true),
+                value => cell(&value.to_string(), Some(shade(value, board.cells())), false),
+            });
+        }
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    // Values run from blue to red, the current position and blocked cells
+    // stand out, and empty cells are left plain.
+    fn ansi_board() {
+        assert_eq!(shade(1, 36), GRADIENT[0]);
+        assert_eq!(shade(36, 36), GRADIENT[2]);
+        assert_eq!(shade(5, 9), GRADIENT[1]);
+        let board = Board::new(6)
+            .block(5, 5)
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap()
+            .next_move(Direction::Right)
+            .unwrap();
+        let text = ansi(&board);
+        assert_eq!(text.lines().count(), 6);
+        let first = text.lines().next().unwrap();
+        assert!(first.starts_with("\x1b[38;2;0;0;0;48;2;70;110;220m  1 \x1b[0m  . "));
+        assert!(first.contains("\x1b[1;38;2;0;0;0;48;2;250;220;40m  2 \x1b[0m"));
+        assert!(text.ends_with("  . \x1b[38;2;0;0;0;48;2;60;60;60m  # \x1b[0m\n"));
+    }
+}
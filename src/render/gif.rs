@@ -0,0 +1,276 @@
+//! Animated GIF pictures of boards being filled.
+
+use super::raster::{draw, Rgb};
+use crate::board::Board;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// The largest code an LZW stream in a GIF may use, plus one.
+const MAX_CODES: u16 = 4096;
+
+/// How much longer than the others the last frame is shown, before the
+/// animation starts again.
+const HOLD: u32 = 10;
+
+/// Write an animated GIF of `board` to the file at `path`, as returned by
+/// [`encode_gif`].
+pub fn gif<P: AsRef<Path>>(board: &Board, path: P, delay: Duration) -> io::Result<()> {
+    fs::write(path, encode_gif(board, delay))
+}
+
+/// Return an animated GIF showing the path of `board` being filled in, one
+/// value per frame and `delay` apart, each frame drawn like
+/// [`svg`](super::svg). The last frame, the board as it is, is shown for
+/// longer before the animation loops.
+pub fn encode_gif(board: &Board, delay: Duration) -> Vec<u8> {
+    let mut frames = vec![draw(board)];
+    let mut board = board.clone();
+    while board.score() > 1 {
+        board.undo().unwrap();
+        frames.push(draw(&board));
+    }
+    frames.reverse();
+
+    let mut palette: Vec<Rgb> = Vec::new();
+    let mut indices: HashMap<Rgb, u8> = HashMap::new();
+    for &pixel in frames.iter().flat_map(|frame| &frame.pixels) {
+        indices.entry(pixel).or_insert_with(|| {
+            palette.push(pixel);
+            (palette.len() - 1) as u8
+        });
+    }
+    // The colour table holds a power of two colours, at least 4 as the
+    // smallest LZW code size is 2.
+    let bits = (usize::BITS - (palette.len() - 1).leading_zeros()).max(2) as u8;
+    palette.resize(1 << bits, [0, 0, 0]);
+
+    let (width, height) = (frames[0].width as u16, frames[0].height as u16);
+    let mut out = b"GIF89a".to_vec();
+    out.extend_from_slice(&width.to_le_bytes());
+    out.extend_from_slice(&height.to_le_bytes());
+    // A global colour table of 2^bits colours of 8 bits per channel.
+    out.extend_from_slice(&[0xf0 | (bits - 1), 0, 0]);
+    out.extend(palette.iter().flatten());
+    // Loop forever.
+    out.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+    let centis = (delay.as_millis() / 10).min(u16::MAX as u128 / HOLD as u128) as u16;
+    for (i, frame) in frames.iter().enumerate() {
+        let centis = if i + 1 == frames.len() {
+            centis * HOLD as u16
+        } else {
+            centis
+        };
+        out.extend_from_slice(&[0x21, 0xf9, 4, 0]);
+        out.extend_from_slice(&centis.to_le_bytes());
+        out.extend_from_slice(&[0, 0]);
+        out.extend_from_slice(&[0x2c, 0, 0, 0, 0]);
+        out.extend_from_slice(&width.to_le_bytes());
+        out.extend_from_slice(&height.to_le_bytes());
+        out.push(0);
+        out.push(bits);
+        let pixels: Vec<u8> = frame.pixels.iter().map(|pixel| indices[pixel]).collect();
+        for block in lzw(bits, &pixels).chunks(255) {
+            out.push(block.len() as u8);
+            out.extend_from_slice(block);
+        }
+        out.push(0);
+    }
+    out.push(0x3b);
+    out
+}
+
+/// Return `indices` compressed as the image data of a GIF, with codes
+/// starting `min_size` + 1 bits wide.
+fn lzw(min_size: u8, indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << min_size;
+    let end = clear + 1;
+    let mut out = Vec::new();
+    let (mut buffer, mut buffered) = (0u32, 0);
+    let mut emit = |code: u16, width: u8| {
+        buffer |= (code as u32) << buffered;
+        buffered += width;
+        while buffered >= 8 {
+            out.push(buffer as u8);
+            buffer >>= 8;
+            buffered -= 8;
+        }
+    };
+
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next = end + 1;
+    let mut width = min_size + 1;
+    emit(clear, width);
+    let mut prefix = indices[0] as u16;
+    for &index in &indices[1..] {
+        if let Some(&code) = table.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+        emit(prefix, width);
+        if next < MAX_CODES {
+            table.insert((prefix, index), next);
+            next += 1;
+            // The reader adds each entry one code later than this, so
+            // widens its codes only once it could have read `next` - 1.
+            if next > 1 << width && width < 12 {
+                width += 1;
+            }
+        } else {
+            emit(clear, width);
+            table.clear();
+            next = end + 1;
+            width = min_size + 1;
+        }
+        prefix = index as u16;
+    }
+    emit(prefix, width);
+    if next == 1 << width && width < 12 {
+        width += 1;
+    }
+    emit(end, width);
+    emit(0, 7);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{center, CELL, MARGIN};
+    use super::*;
+    use crate::board::Direction;
+
+    /// Return the indices held in `data`, compressed by [`lzw`].
+    fn unlzw(min_size: u8, data: &[u8]) -> Vec<u8> {
+        let clear = 1usize << min_size;
+        let mut table: Vec<Vec<u8>> = Vec::new();
+        let mut width = min_size + 1;
+        let mut previous: Option<Vec<u8>> = None;
+        let mut out = Vec::new();
+        let (mut buffer, mut buffered, mut bytes) = (0u32, 0, data.iter());
+        loop {
+            while buffered < width {
+                buffer |= (*bytes.next().unwrap() as u32) << buffered;
+                buffered += 8;
+            }
+            let code = (buffer & ((1 << width) - 1)) as usize;
+            buffer >>= width;
+            buffered -= width;
+            if code == clear {
+                table = (0..clear).map(|i| vec![i as u8]).collect();
+                table.extend([vec![], vec![]]);
+                width = min_size + 1;
+                previous = None;
+                continue;
+            }
+            if code == clear + 1 {
+                return out;
+            }
+            let entry = match (table.get(code), &previous) {
+                (Some(entry), _) => entry.clone(),
+                (None, Some(previous)) => [&previous[..], &previous[..1]].concat(),
+                (None, None) => panic!("code {} is not in the table", code),
+            };
+            out.extend_from_slice(&entry);
+            if let Some(previous) = previous {
+                if table.len() < MAX_CODES as usize {
+                    table.push([&previous[..], &entry[..1]].concat());
+                }
+            }
+            if table.len() == 1 << width && width < 12 {
+                width += 1;
+            }
+            previous = Some(entry);
+        }
+    }
+
+    #[test]
+    // Compressed data reads back as it was, for short runs and for runs
+    // long enough to fill the table.
+    fn lzw_round_trip() {
+        assert_eq!(unlzw(2, &lzw(2, &[1])), [1]);
+        assert_eq!(unlzw(2, &lzw(2, &[0, 0, 0, 0, 0, 0, 0])), [0; 7]);
+        let mut seed = 1u32;
+        let indices: Vec<u8> = (0..100_000)
+            .map(|i| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+                if i % 3 == 0 {
+                    (seed >> 16) as u8 % 8
+                } else {
+                    (i / 50 % 8) as u8
+                }
+            })
+            .collect();
+        assert_eq!(unlzw(3, &lzw(3, &indices)), indices);
+    }
+
+    #[test]
+    // The animation has a frame for each value, the first showing only 1,
+    // the last the board as it is and shown for longer.
+    fn gif_board() {
+        let board = Board::new(5)
+            .block(2, 2)
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap()
+            .next_move(Direction::Right)
+            .unwrap()
+            .next_move(Direction::Down)
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("square100-{}-gif", std::process::id()));
+        gif(&board, &path, Duration::from_millis(200)).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(bytes, encode_gif(&board, Duration::from_millis(200)));
+        assert_eq!(&bytes[..6], b"GIF89a");
+        let size = (5 * CELL + 2 * MARGIN) as u16;
+        assert_eq!(bytes[6..8], size.to_le_bytes());
+        assert_eq!(bytes[8..10], size.to_le_bytes());
+        assert_eq!(bytes.last(), Some(&0x3b));
+
+        let bits = (bytes[10] & 7) + 1;
+        let palette: Vec<&[u8]> = bytes[13..13 + 3 * (1 << bits)].chunks(3).collect();
+        let mut at = 13 + 3 * (1 << bits) + 19;
+        let mut frames = Vec::new();
+        while bytes[at] == 0x21 {
+            let delay = u16::from_le_bytes([bytes[at + 4], bytes[at + 5]]);
+            at += 8;
+            assert_eq!(bytes[at], 0x2c);
+            assert_eq!(bytes[at + 10], bits);
+            at += 11;
+            let mut data = Vec::new();
+            while bytes[at] > 0 {
+                data.extend_from_slice(&bytes[at + 1..at + 1 + bytes[at] as usize]);
+                at += 1 + bytes[at] as usize;
+            }
+            at += 1;
+            let pixels: Vec<&[u8]> = unlzw(bits, &data)
+                .into_iter()
+                .map(|i| palette[i as usize])
+                .collect();
+            assert_eq!(pixels.len(), size as usize * size as usize);
+            frames.push((delay, pixels));
+        }
+        assert_eq!(at, bytes.len() - 1);
+        assert_eq!(frames.len(), 3);
+        assert_eq!(frames[0].0, 20);
+        assert_eq!(frames[2].0, 200);
+
+        let pixel = |frame: usize, x: usize, y: usize| frames[frame].1[y * size as usize + x];
+        assert_eq!(pixel(0, 0, 0), [255, 255, 255]);
+        let (cx, cy) = center(2, 2);
+        assert_eq!(
+            pixel(0, cx as usize + 10, cy as usize + 10),
+            [144, 144, 144]
+        );
+        // The arrow to the second cell appears in the second frame, and the
+        // one to the third in the last.
+        let (_, y) = center(0, 0);
+        assert_eq!(pixel(0, MARGIN + CELL + 5, y as usize), [255, 255, 255]);
+        assert_eq!(pixel(1, MARGIN + CELL + 5, y as usize), [192, 48, 48]);
+        let (x, _) = center(3, 0);
+        assert_eq!(pixel(1, x as usize, MARGIN + CELL + 5), [255, 255, 255]);
+        assert_eq!(pixel(2, x as usize, MARGIN + CELL + 5), [192, 48, 48]);
+    }
+}
@@ -0,0 +1,128 @@
+//! HTML pages of boards.
+
+use crate::board::Board;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// The style of the table, shared by both kinds of page.
+const STYLE: &str = "\
+table.board { border-collapse: collapse; font-family: sans-serif; }
+table.board td { width: 2.2em; height: 2.2em; border: 1px solid #000; text-align: center; }
+table.board td.blocked { background: #909090; }
+table.board td.clue { color: #808080; }
+table.board td.current { background: #fadc28; font-weight: bold; }
+";
+
+/// The extra style of an animated page, in which each filled cell fades in
+/// after the delay set on it.
+const ANIMATION: &str = "\
+table.board td.filled { opacity: 0; animation: fill 0.2s ease-in forwards; }
+@keyframes fill { to { opacity: 1; } }
+";
+
+/// Return a self-contained HTML page showing `board` as a table, with its
+/// style inline: the value of each filled cell, any clue not yet reached in
+/// grey, blocked cells shaded and the current position highlighted.
+pub fn html(board: &Board) -> String {
+    page(board, None)
+}
+
+/// Return a page like [`html`], on which the cells fill in one at a time in
+/// the order of their values, `step` apart, when the page is opened.
+pub fn html_animated(board: &Board, step: Duration) -> String {
+    page(board, Some(step))
+}
+
+/// Return the page for `board`, animated with cells `step` apart if a step
+/// is given.
+fn page(board: &Board, step: Option<Duration>) -> String {
+    let current = Some(board.current_position()).filter(|_| board.is_started());
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    writeln!(
+        out,
+        "<title>Board {}x{}</title>",
+        board.width(),
+        board.height()
+    )
+    .unwrap();
+    out.push_str("<style>\n");
+    out.push_str(STYLE);
+    if step.is_some() {
+        out.push_str(ANIMATION);
+    }
+    out.push_str("</style>\n</head>\n<body>\n<table class=\"board\">\n");
+    for y in 0..board.height() {
+        out.push_str("<tr>");
+        for x in 0..board.width() {
+            let mut classes = Vec::new();
+            let mut text = String::new();
+            match board.value_at(x, y) {
+                _ if board.blocked_at(x, y) => classes.push("blocked"),
+                0 if board.clue_at(x, y) > 0 => {
+                    classes.push("clue");
+                    text = board.clue_at(x, y).to_string();
+                }
+                0 => {}
+                value => {
+                    classes.push("filled");
+                    text = value.to_string();
+                }
+            }
+            if current == Some((x, y)) {
+                classes.push("current");
+            }
+            out.push_str("<td");
+            if !classes.is_empty() {
+                write!(out, " class=\"{}\"", classes.join(" ")).unwrap();
+            }
+            if let (Some(step), value @ 1..) = (step, board.value_at(x, y)) {
+                let delay = step * (value as u32 - 1);
+                write!(out, " style=\"animation-delay: {}ms\"", delay.as_millis()).unwrap();
+            }
+            write!(out, ">{}</td>", text).unwrap();
+        }
+        out.push_str("</tr>\n");
+    }
+    out.push_str("</table>\n</body>\n</html>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    // The table has a cell for each cell of the board, classed by what it
+    // holds, and the animated page delays each filled cell by its value.
+    fn html_board() {
+        let board = Board::with_clues(5, &[(4, 4, 20)])
+            .unwrap()
+            .block(2, 2)
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap()
+            .next_move(Direction::Right)
+            .unwrap();
+        let page = html(&board);
+        assert!(page.starts_with("<!DOCTYPE html>\n"));
+        assert!(page.ends_with("</html>\n"));
+        assert_eq!(page.matches("<tr>").count(), 5);
+        assert_eq!(page.matches("<td").count(), 25);
+        assert!(page.contains(
+            "<tr><td class=\"filled\">1</td><td></td><td></td>\
+             <td class=\"filled current\">2</td><td></td></tr>"
+        ));
+        assert!(page.contains("<td class=\"blocked\"></td>"));
+        assert!(page.contains("<td class=\"clue\">20</td></tr>"));
+        assert!(!page.contains("animation"));
+
+        let page = html_animated(&board, Duration::from_millis(250));
+        assert!(page.contains("@keyframes fill"));
+        assert!(page.contains("<td class=\"filled\" style=\"animation-delay: 0ms\">1</td>"));
+        assert!(
+            page.contains("<td class=\"filled current\" style=\"animation-delay: 250ms\">2</td>")
+        );
+    }
+}
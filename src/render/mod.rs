@@ -0,0 +1,61 @@
+//! Pictures of boards and their paths.
+//!
+//! [`svg`] draws a board as an SVG image: the grid, the value in each
+//! filled cell, and an arrow for each move along the path, in the order the
+//! cells were filled. With the `image` feature enabled, `png` draws the
+//! same picture as a PNG file. [`arrows`] writes the grid as text, with an
+//! arrow after each value pointing the way the path goes next, and with the
+//! `color` feature enabled, `ansi` colours the values of the grid for a
+//! terminal. [`html`] writes a web page holding the grid as a table, and
+//! [`html_animated`] one on which the cells fill in order. With the `gif`
+//! feature enabled, `gif` animates the picture as a GIF file, filling in one
+//! value per frame. [`tikz`] draws the picture for a LaTeX document.
+
+#[cfg(feature = "color")]
+mod ansi;
+#[cfg(feature = "gif")]
+mod gif;
+mod html;
+#[cfg(feature = "image")]
+mod png;
+#[cfg(any(feature = "gif", feature = "image"))]
+mod raster;
+mod svg;
+mod text;
+mod tikz;
+
+#[cfg(feature = "color")]
+pub use self::ansi::ansi;
+#[cfg(feature = "gif")]
+pub use self::gif::{encode_gif, gif};
+pub use self::html::{html, html_animated};
+#[cfg(feature = "image")]
+pub use self::png::{encode_png, png};
+pub use self::svg::svg;
+pub use self::text::arrows;
+pub use self::tikz::tikz;
+
+/// The side of a cell, in pixels.
+const CELL: usize = 40;
+
+/// The space around the grid, in pixels.
+const MARGIN: usize = 10;
+
+/// Return the centre of the cell at (x, y), in pixels from the top left of
+/// the picture.
+fn center(x: usize, y: usize) -> (f64, f64) {
+    let offset = |i: usize| (MARGIN + i * CELL) as f64 + CELL as f64 / 2.0;
+    (offset(x), offset(y))
+}
+
+/// Return the ends of the arrow for the move from `from` to `to`, drawn
+/// between the centres of the cells but stopping short of each so that it
+/// does not cover their values.
+fn arrow(from: (usize, usize), to: (usize, usize)) -> ((f64, f64), (f64, f64)) {
+    let (x1, y1) = center(from.0, from.1);
+    let (x2, y2) = center(to.0, to.1);
+    let length = (x2 - x1).hypot(y2 - y1);
+    let gap = CELL as f64 * 0.3 / length;
+    let (dx, dy) = ((x2 - x1) * gap, (y2 - y1) * gap);
+    ((x1 + dx, y1 + dy), (x2 - dx, y2 - dy))
+}
@@ -0,0 +1,111 @@
+//! PNG pictures of boards.
+
+use super::raster::draw;
+use crate::board::Board;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The bytes every PNG file starts with.
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+
+/// Write a PNG image of `board` to the file at `path`, drawn like
+/// [`svg`](super::svg): the grid, the values, and an arrow for each move.
+pub fn png<P: AsRef<Path>>(board: &Board, path: P) -> io::Result<()> {
+    fs::write(path, encode_png(board))
+}
+
+/// Return a PNG image of `board`, as written by [`png`].
+pub fn encode_png(board: &Board) -> Vec<u8> {
+    let canvas = draw(board);
+    let mut header = Vec::with_capacity(13);
+    header.extend_from_slice(&(canvas.width as u32).to_be_bytes());
+    header.extend_from_slice(&(canvas.height as u32).to_be_bytes());
+    // 8 bits per channel, RGB, with the standard compression, filters and
+    // no interlacing.
+    header.extend_from_slice(&[8, 2, 0, 0, 0]);
+    // Each row starts with the type of its filter, 0 for none.
+    let mut rows = Vec::with_capacity(canvas.height * (canvas.width * 3 + 1));
+    for row in canvas.pixels.chunks(canvas.width) {
+        rows.push(0);
+        rows.extend(row.iter().flatten());
+    }
+    let mut out = SIGNATURE.to_vec();
+    chunk(&mut out, b"IHDR", &header);
+    chunk(
+        &mut out,
+        b"IDAT",
+        &miniz_oxide::deflate::compress_to_vec_zlib(&rows, 6),
+    );
+    chunk(&mut out, b"IEND", &[]);
+    out
+}
+
+/// Append a chunk of type `kind` holding `data` to `out`.
+fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(kind.iter().chain(data));
+    out.extend_from_slice(&crc.to_be_bytes());
+}
+
+/// Return the CRC-32 of `bytes`, as PNG checks its chunks with.
+fn crc32<'a>(bytes: impl Iterator<Item = &'a u8>) -> u32 {
+    !bytes.fold(!0u32, |crc, &byte| {
+        (0..8).fold(crc ^ byte as u32, |crc, _| {
+            if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            }
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{center, CELL, MARGIN};
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    // The image is a well-formed PNG of the right size, whose pixels show
+    // the grid, the blocked cells and the path.
+    fn png_board() {
+        assert_eq!(crc32(b"IEND".iter()), 0xae42_6082);
+        let board = Board::new(5)
+            .block(2, 2)
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap()
+            .next_move(Direction::Right)
+            .unwrap();
+        let path = std::env::temp_dir().join(format!("square100-{}-png", std::process::id()));
+        png(&board, &path).unwrap();
+        let bytes = fs::read(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(bytes, encode_png(&board));
+        assert_eq!(bytes[..8], SIGNATURE);
+        assert_eq!(&bytes[12..16], b"IHDR");
+        let size = (5 * CELL + 2 * MARGIN) as u32;
+        assert_eq!(bytes[16..20], size.to_be_bytes());
+        assert_eq!(bytes[20..24], size.to_be_bytes());
+        assert!(bytes.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+
+        let length = u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]) as usize;
+        assert_eq!(&bytes[37..41], b"IDAT");
+        let rows = miniz_oxide::inflate::decompress_to_vec_zlib(&bytes[41..41 + length]).unwrap();
+        let stride = size as usize * 3 + 1;
+        assert_eq!(rows.len(), stride * size as usize);
+        let pixel = |x: usize, y: usize| -> &[u8] { &rows[y * stride + 1 + x * 3..][..3] };
+        assert_eq!(pixel(0, 0), [255, 255, 255]);
+        assert_eq!(pixel(MARGIN, MARGIN), [0, 0, 0]);
+        let (cx, cy) = center(2, 2);
+        assert_eq!(pixel(cx as usize + 10, cy as usize + 10), [144, 144, 144]);
+        // The arrow from the first cell to the second crosses the grid line
+        // between them.
+        let (_, y) = center(0, 0);
+        assert_eq!(pixel(MARGIN + CELL + 5, y as usize), [192, 48, 48]);
+    }
+}
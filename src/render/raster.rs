@@ -0,0 +1,163 @@
+//! Drawing boards into pixels, for the raster formats.
+
+use super::{arrow, center, CELL, MARGIN};
+use crate::board::Board;
+
+/// A colour, as red, green and blue.
+pub(super) type Rgb = [u8; 3];
+
+pub(super) const WHITE: Rgb = [255, 255, 255];
+pub(super) const BLACK: Rgb = [0, 0, 0];
+pub(super) const SHADE: Rgb = [144, 144, 144];
+pub(super) const GREY: Rgb = [128, 128, 128];
+pub(super) const RED: Rgb = [192, 48, 48];
+
+/// The digits 0 to 9 drawn on a grid 3 pixels wide and 5 high, one row
+/// per entry with the leftmost pixel in the highest of the three bits.
+const DIGITS: [[u8; 5]; 10] = [
+    [0b111, 0b101, 0b101, 0b101, 0b111],
+    [0b010, 0b110, 0b010, 0b010, 0b111],
+    [0b111, 0b001, 0b111, 0b100, 0b111],
+    [0b111, 0b001, 0b011, 0b001, 0b111],
+    [0b101, 0b101, 0b111, 0b001, 0b001],
+    [0b111, 0b100, 0b111, 0b001, 0b111],
+    [0b111, 0b100, 0b111, 0b101, 0b111],
+    [0b111, 0b001, 0b010, 0b010, 0b010],
+    [0b111, 0b101, 0b111, 0b101, 0b111],
+    [0b111, 0b101, 0b111, 0b001, 0b111],
+];
+
+/// Canvas is a picture held as rows of pixels, top row first.
+pub(super) struct Canvas {
+    pub(super) width: usize,
+    pub(super) height: usize,
+    pub(super) pixels: Vec<Rgb>,
+}
+
+impl Canvas {
+    /// Return a canvas of `width` x `height` pixels filled with `colour`.
+    pub(super) fn new(width: usize, height: usize, colour: Rgb) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![colour; width * height],
+        }
+    }
+
+    /// Colour the pixel at (x, y), if it is on the canvas.
+    fn plot(&mut self, x: i64, y: i64, colour: Rgb) {
+        if x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height {
+            self.pixels[y as usize * self.width + x as usize] = colour;
+        }
+    }
+
+    /// Fill the rectangle with its top left corner at (x, y).
+    fn fill(&mut self, x: usize, y: usize, width: usize, height: usize, colour: Rgb) {
+        for py in y..y + height {
+            for px in x..x + width {
+                self.plot(px as i64, py as i64, colour);
+            }
+        }
+    }
+
+    /// Draw a line `thickness` pixels wide from `from` to `to`.
+    fn line(&mut self, from: (f64, f64), to: (f64, f64), thickness: i64, colour: Rgb) {
+        let steps = (to.0 - from.0)
+            .abs()
+            .max((to.1 - from.1).abs())
+            .ceil()
+            .max(1.0);
+        for i in 0..=steps as i64 {
+            let t = i as f64 / steps;
+            let x = (from.0 + (to.0 - from.0) * t).round() as i64;
+            let y = (from.1 + (to.1 - from.1) * t).round() as i64;
+            for dy in 0..thickness {
+                for dx in 0..thickness {
+                    self.plot(x + dx - thickness / 2, y + dy - thickness / 2, colour);
+                }
+            }
+        }
+    }
+
+    /// Fill the triangle with corners `a`, `b` and `c`.
+    fn triangle(&mut self, a: (f64, f64), b: (f64, f64), c: (f64, f64), colour: Rgb) {
+        let side = |p: (f64, f64), q: (f64, f64), r: (f64, f64)| {
+            (q.0 - p.0) * (r.1 - p.1) - (q.1 - p.1) * (r.0 - p.0)
+        };
+        let (left, right) = (a.0.min(b.0).min(c.0), a.0.max(b.0).max(c.0));
+        let (top, bottom) = (a.1.min(b.1).min(c.1), a.1.max(b.1).max(c.1));
+        for y in top.floor() as i64..=bottom.ceil() as i64 {
+            for x in left.floor() as i64..=right.ceil() as i64 {
+                let p = (x as f64, y as f64);
+                let (u, v, w) = (side(a, b, p), side(b, c, p), side(c, a, p));
+                if (u >= 0.0 && v >= 0.0 && w >= 0.0) || (u <= 0.0 && v <= 0.0 && w <= 0.0) {
+                    self.plot(x, y, colour);
+                }
+            }
+        }
+    }
+
+    /// Write `value` centred on (cx, cy), as large as fits in a cell.
+    fn number(&mut self, cx: f64, cy: f64, value: u16, colour: Rgb) {
+        let digits: Vec<usize> = value
+            .to_string()
+            .bytes()
+            .map(|b| (b - b'0') as usize)
+            .collect();
+        let scale = match digits.len() {
+            1..=3 => 3,
+            4 => 2,
+            _ => 1,
+        };
+        let width = digits.len() * 4 * scale - scale;
+        let left = cx.round() as usize - width / 2;
+        let top = cy.round() as usize - 5 * scale / 2;
+        for (i, &digit) in digits.iter().enumerate() {
+            for (row, bits) in DIGITS[digit].iter().enumerate() {
+                for column in 0..3 {
+                    if bits & (0b100 >> column) != 0 {
+                        let x = left + (i * 4 + column) * scale;
+                        self.fill(x, top + row * scale, scale, scale, colour);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Draw `board` as it is drawn by [`svg`](super::svg): its grid, with
+/// blocked cells shaded, the value of each filled cell, any clue not yet
+/// reached in grey, and an arrow for each move along the path.
+pub(super) fn draw(board: &Board) -> Canvas {
+    let (width, height) = (board.width(), board.height());
+    let mut canvas = Canvas::new(width * CELL + 2 * MARGIN, height * CELL + 2 * MARGIN, WHITE);
+    for (x, y) in board.blocked() {
+        canvas.fill(MARGIN + x * CELL, MARGIN + y * CELL, CELL, CELL, SHADE);
+    }
+    for x in 0..=width {
+        canvas.fill(MARGIN + x * CELL, MARGIN, 1, height * CELL + 1, BLACK);
+    }
+    for y in 0..=height {
+        canvas.fill(MARGIN, MARGIN + y * CELL, width * CELL + 1, 1, BLACK);
+    }
+    for pair in board.path().windows(2) {
+        let (from, to) = arrow(pair[0], pair[1]);
+        canvas.line(from, to, 2, RED);
+        // The head is a triangle 8 pixels long and 8 wide at the tip.
+        let length = (to.0 - from.0).hypot(to.1 - from.1);
+        let (ux, uy) = ((to.0 - from.0) / length, (to.1 - from.1) / length);
+        let base = (to.0 - ux * 8.0, to.1 - uy * 8.0);
+        let a = (base.0 - uy * 4.0, base.1 + ux * 4.0);
+        let b = (base.0 + uy * 4.0, base.1 - ux * 4.0);
+        canvas.triangle(to, a, b, RED);
+    }
+    for ((x, y), value) in board.iter_cells() {
+        let (cx, cy) = center(x, y);
+        if value > 0 {
+            canvas.number(cx, cy, value, BLACK);
+        } else if board.clue_at(x, y) > 0 {
+            canvas.number(cx, cy, board.clue_at(x, y), GREY);
+        }
+    }
+    canvas
+}
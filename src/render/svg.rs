@@ -0,0 +1,137 @@
+//! SVG pictures of boards.
+
+use super::{arrow, center, CELL, MARGIN};
+use crate::board::Board;
+use std::fmt::Write;
+
+/// Return an SVG image of `board`: its grid, with blocked cells shaded, the
+/// value of each filled cell, any clue not yet reached in grey, and an arrow
+/// for each move along the path from 1 to the current position. On a board
+/// that wraps, a move across an edge is drawn straight across the board.
+pub fn svg(board: &Board) -> String {
+    let (width, height) = (board.width(), board.height());
+    let (pixels_x, pixels_y) = (width * CELL + 2 * MARGIN, height * CELL + 2 * MARGIN);
+    let font = if board.cells() < 100 { 16 } else { 12 };
+    let mut out = String::new();
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{0}" height="{1}" viewBox="0 0 {0} {1}">"#,
+        pixels_x, pixels_y
+    )
+    .unwrap();
+    out.push_str(concat!(
+        "<defs><marker id=\"head\" viewBox=\"0 0 10 10\" refX=\"10\" refY=\"5\" ",
+        "markerWidth=\"6\" markerHeight=\"6\" orient=\"auto\">",
+        "<path d=\"M 0 0 L 10 5 L 0 10 z\" fill=\"#c03030\"/></marker></defs>\n",
+    ));
+    writeln!(
+        out,
+        r#"<rect width="{}" height="{}" fill="white"/>"#,
+        pixels_x, pixels_y
+    )
+    .unwrap();
+    for (x, y) in board.blocked() {
+        writeln!(
+            out,
+            r##"<rect class="blocked" x="{}" y="{}" width="{}" height="{}" fill="#909090"/>"##,
+            MARGIN + x * CELL,
+            MARGIN + y * CELL,
+            CELL,
+            CELL
+        )
+        .unwrap();
+    }
+    out.push_str("<g stroke=\"black\" stroke-width=\"1\">\n");
+    for x in 0..=width {
+        let at = MARGIN + x * CELL;
+        let bottom = MARGIN + height * CELL;
+        writeln!(
+            out,
+            r#"<line x1="{0}" y1="{1}" x2="{0}" y2="{2}"/>"#,
+            at, MARGIN, bottom
+        )
+        .unwrap();
+    }
+    for y in 0..=height {
+        let at = MARGIN + y * CELL;
+        let right = MARGIN + width * CELL;
+        writeln!(
+            out,
+            r#"<line x1="{1}" y1="{0}" x2="{2}" y2="{0}"/>"#,
+            at, MARGIN, right
+        )
+        .unwrap();
+    }
+    out.push_str("</g>\n");
+    out.push_str(
+        "<g stroke=\"#c03030\" stroke-width=\"2\" stroke-opacity=\"0.7\" marker-end=\"url(#head)\">\n",
+    );
+    for pair in board.path().windows(2) {
+        let ((x1, y1), (x2, y2)) = arrow(pair[0], pair[1]);
+        writeln!(
+            out,
+            r#"<line class="move" x1="{:.1}" y1="{:.1}" x2="{:.1}" y2="{:.1}"/>"#,
+            x1, y1, x2, y2
+        )
+        .unwrap();
+    }
+    out.push_str("</g>\n");
+    writeln!(
+        out,
+        r#"<g font-family="sans-serif" font-size="{}" text-anchor="middle" dominant-baseline="central">"#,
+        font
+    )
+    .unwrap();
+    for ((x, y), value) in board.iter_cells() {
+        let (cx, cy) = center(x, y);
+        if value > 0 {
+            writeln!(out, r#"<text x="{}" y="{}">{}</text>"#, cx, cy, value).unwrap();
+        } else if board.clue_at(x, y) > 0 {
+            writeln!(
+                out,
+                r##"<text class="clue" x="{}" y="{}" fill="#808080">{}</text>"##,
+                cx,
+                cy,
+                board.clue_at(x, y)
+            )
+            .unwrap();
+        }
+    }
+    out.push_str("</g>\n</svg>\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    // The picture has a line for each edge of the grid, an arrow for each
+    // move and a number for each filled cell or clue, with blocked cells
+    // shaded.
+    fn svg_board() {
+        let board = Board::with_clues(5, &[(4, 4, 20)])
+            .unwrap()
+            .block(2, 2)
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap()
+            .next_move(Direction::Right)
+            .unwrap()
+            .next_move(Direction::DownLeft)
+            .unwrap();
+        let image = svg(&board);
+        assert!(image
+            .starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"220\" height=\"220\""));
+        assert!(image.ends_with("</svg>\n"));
+        assert_eq!(image.matches("<line x1").count(), 12);
+        assert_eq!(image.matches("class=\"move\"").count(), 2);
+        assert_eq!(image.matches("class=\"blocked\"").count(), 1);
+        assert_eq!(image.matches("<text").count(), 4);
+        assert!(image.contains(r#"<text x="30" y="30">1</text>"#));
+        assert!(image.contains(r##"<text class="clue" x="190" y="190" fill="#808080">20</text>"##));
+        // The first arrow runs from the first cell towards the second.
+        assert!(image.contains(r#"<line class="move" x1="42.0" y1="30.0" x2="138.0" y2="30.0"/>"#));
+    }
+}
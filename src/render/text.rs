@@ -0,0 +1,64 @@
+//! Text pictures of boards.
+
+use crate::board::Board;
+use std::fmt::Write;
+
+/// Return the grid of `board` as text, one row per line, with each value
+/// followed by an arrow in the direction of the move to the next value, so
+/// that the path can be followed by eye. The last value on the path has no
+/// arrow, empty cells are shown as `.` and blocked cells as `#`. Under the
+/// knight rule, an arrow points the way of the direction the move is
+/// labelled with, not along the move itself.
+pub fn arrows(board: &Board) -> String {
+    let mut next = vec![' '; board.width() * board.height()];
+    for (&(x, y), dir) in board.path().iter().zip(board.history()) {
+        next[y * board.width() + x] = dir.arrow();
+    }
+    let width = board.cells().to_string().len();
+    let mut out = String::new();
+    for y in 0..board.height() {
+        for x in 0..board.width() {
+            if x > 0 {
+                out.push(' ');
+            }
+            match board.value_at(x, y) {
+                _ if board.blocked_at(x, y) => write!(out, "{:>width$} ", "#", width = width),
+                0 => write!(out, "{:>width$} ", ".", width = width),
+                value => {
+                    let arrow = next[y * board.width() + x];
+                    write!(out, "{:>width$}{}", value, arrow, width = width)
+                }
+            }
+            .unwrap();
+        }
+        out.truncate(out.trim_end().len());
+        out.push('\n');
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    // Each value is followed by the way the path leaves it.
+    fn arrows_board() {
+        let mut board = Board::new(6).block(5, 5).unwrap().start_at(0, 0).unwrap();
+        board
+            .apply_moves(&[Direction::Right, Direction::DownLeft, Direction::Down])
+            .unwrap();
+        assert_eq!(
+            arrows(&board),
+            concat!(
+                " 1→  .   .   2↙  .   .\n",
+                " .   .   .   .   .   .\n",
+                " .   3↓  .   .   .   .\n",
+                " .   .   .   .   .   .\n",
+                " .   .   .   .   .   .\n",
+                " .   4   .   .   .   #\n",
+            )
+        );
+    }
+}
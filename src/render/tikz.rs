@@ -0,0 +1,95 @@
+//! TikZ pictures of boards, for LaTeX documents.
+
+use crate::board::Board;
+use std::fmt::Write;
+
+/// Return the centre of the cell at (x, y), in TikZ units of one cell with
+/// y growing upwards from the top edge of the grid.
+fn center(x: usize, y: usize) -> (f64, f64) {
+    (x as f64 + 0.5, -(y as f64) - 0.5)
+}
+
+/// Return a TikZ picture of `board`, drawn like [`svg`](super::svg): its
+/// grid, with blocked cells shaded, the value of each filled cell, any clue
+/// not yet reached in grey, and an arrow for each move along the path. The
+/// picture is one unit per cell, so it can be resized with the `scale`
+/// option of the environment, and needs no TikZ library.
+pub fn tikz(board: &Board) -> String {
+    let (width, height) = (board.width(), board.height());
+    let mut out = String::from("\\begin{tikzpicture}[scale=0.6]\n");
+    for (x, y) in board.blocked() {
+        writeln!(
+            out,
+            "\\fill[gray!60] ({},{}) rectangle ({},{});",
+            x,
+            -(y as i64),
+            x + 1,
+            -(y as i64) - 1
+        )
+        .unwrap();
+    }
+    writeln!(out, "\\draw (0,0) grid ({},{});", width, -(height as i64)).unwrap();
+    for pair in board.path().windows(2) {
+        let (x1, y1) = center(pair[0].0, pair[0].1);
+        let (x2, y2) = center(pair[1].0, pair[1].1);
+        // Stop short of both centres so as not to cover the values.
+        let gap = 0.3 / (x2 - x1).hypot(y2 - y1);
+        let (dx, dy) = ((x2 - x1) * gap, (y2 - y1) * gap);
+        writeln!(
+            out,
+            "\\draw[->, red!75!black, thick] ({:.2},{:.2}) -- ({:.2},{:.2});",
+            x1 + dx,
+            y1 + dy,
+            x2 - dx,
+            y2 - dy
+        )
+        .unwrap();
+    }
+    for y in 0..height {
+        for x in 0..width {
+            let (cx, cy) = center(x, y);
+            match (board.value_at(x, y), board.clue_at(x, y)) {
+                (0, 0) => {}
+                (0, clue) => {
+                    writeln!(out, "\\node[gray] at ({},{}) {{{}}};", cx, cy, clue).unwrap()
+                }
+                (value, _) => writeln!(out, "\\node at ({},{}) {{{}}};", cx, cy, value).unwrap(),
+            }
+        }
+    }
+    out.push_str("\\end{tikzpicture}\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    // The picture has the grid, the blocked cells, a node for each value
+    // and clue, and an arrow for each move.
+    fn tikz_board() {
+        let board = Board::with_clues(5, &[(4, 4, 20)])
+            .unwrap()
+            .block(2, 2)
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap()
+            .next_move(Direction::Right)
+            .unwrap();
+        assert_eq!(
+            tikz(&board),
+            concat!(
+                "\\begin{tikzpicture}[scale=0.6]\n",
+                "\\fill[gray!60] (2,-2) rectangle (3,-3);\n",
+                "\\draw (0,0) grid (5,-5);\n",
+                "\\draw[->, red!75!black, thick] (0.80,-0.50) -- (3.20,-0.50);\n",
+                "\\node at (0.5,-0.5) {1};\n",
+                "\\node at (3.5,-0.5) {2};\n",
+                "\\node[gray] at (4.5,-4.5) {20};\n",
+                "\\end{tikzpicture}\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,169 @@
+//! A board small enough for its filled cells to fit in one integer.
+//!
+//! A board of up to 11x11 has at most 121 cells, so the set of filled cells
+//! fits in a `u128`. [`SmallBoard`] keeps it there, with the values in a
+//! fixed array inside the struct, so that copying a board is a plain memory
+//! copy and the state a search cares about, the filled cells and the current
+//! position, is two integers that hash and compare in constant time. Unlike
+//! [`FixedBoard`](crate::fixed::FixedBoard), its size is chosen at run time.
+//! Like it, it plays the game of a [`PlainBoard`].
+
+use crate::board::{Board, BoardError, Direction, MIN_SIZE};
+use crate::plain::{Place, PlainBoard};
+use crate::pos::Pos;
+use std::convert::TryFrom;
+
+/// The most cells a small board can have.
+const CELLS: usize = 121;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// SmallBoard is a square puzzle board of up to 11x11 stored inline.
+pub struct SmallBoard {
+    size: u8,
+    /// The filled cells, with the cell at row-major index `i` at bit `i`.
+    occupied: u128,
+    /// The values of the cells in row-major order, with 0 for empty. The
+    /// cells past the end of the board are always 0.
+    values: [u8; CELLS],
+    /// The row-major index of the last cell set in the board.
+    position: u8,
+    /// The value of the last cell set, or 0 if the board is not started.
+    score: u8,
+}
+
+impl SmallBoard {
+    /// The largest size of a small board.
+    pub const MAX_SIZE: usize = 11;
+
+    /// Create a new empty board with the dimensions `size` x `size`, or
+    /// return an error if `size` is not between `MIN_SIZE` and `MAX_SIZE`.
+    pub fn new(size: usize) -> Result<Self, BoardError> {
+        SmallBoard::try_with_dimensions(size, size)
+    }
+
+    #[inline]
+    /// Return the size of the board.
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    #[inline]
+    /// Return the filled cells, with the cell at (x, y) at bit
+    /// `y * size + x`.
+    pub fn occupied(&self) -> u128 {
+        self.occupied
+    }
+
+    #[inline]
+    /// Return the state of the game as the filled cells and the row-major
+    /// index of the current position. Two boards with the same key can be
+    /// completed in the same ways, whatever order their cells were filled
+    /// in.
+    pub fn key(&self) -> (u128, u8) {
+        (self.occupied, self.position)
+    }
+}
+
+impl Place for SmallBoard {
+    fn place(&self, x: usize, y: usize, _: Option<Direction>) -> Self {
+        let i = y * self.size() + x;
+        let mut board = *self;
+        board.score += 1;
+        board.values[i] = board.score;
+        board.occupied |= 1 << i;
+        board.position = i as u8;
+        board
+    }
+}
+
+/// A small board must be square.
+impl PlainBoard for SmallBoard {
+    fn try_with_dimensions(width: usize, height: usize) -> Result<Self, BoardError> {
+        if width != height || !(MIN_SIZE..=SmallBoard::MAX_SIZE).contains(&width) {
+            return Err(BoardError::BadSize { width, height });
+        }
+        Ok(SmallBoard {
+            size: width as u8,
+            occupied: 0,
+            values: [0; CELLS],
+            position: 0,
+            score: 0,
+        })
+    }
+
+    #[inline]
+    fn width(&self) -> usize {
+        self.size()
+    }
+
+    #[inline]
+    fn height(&self) -> usize {
+        self.size()
+    }
+
+    #[inline]
+    fn value_at(&self, x: usize, y: usize) -> u16 {
+        self.values[y * self.size() + x] as u16
+    }
+
+    #[inline]
+    fn current_position(&self) -> (usize, usize) {
+        Pos::from_index(self.position as usize, self.size()).into()
+    }
+
+    #[inline]
+    fn score(&self) -> usize {
+        self.score as usize
+    }
+}
+
+impl From<SmallBoard> for Board {
+    fn from(board: SmallBoard) -> Board {
+        board.to_board()
+    }
+}
+
+/// Convert a square board of up to `MAX_SIZE` that plays the plain game.
+impl TryFrom<&Board> for SmallBoard {
+    type Error = BoardError;
+
+    fn try_from(board: &Board) -> Result<Self, Self::Error> {
+        SmallBoard::from_board(board)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A small board is square and at most 11x11, and keys boards by their
+    // state alone.
+    fn small_keys() {
+        assert!(SmallBoard::try_from(&Board::new(12)).is_err());
+        assert!(SmallBoard::try_from(&Board::with_dimensions(5, 6)).is_err());
+        assert!(SmallBoard::new(12).is_err());
+        let moves = [Direction::Right, Direction::DownLeft, Direction::Down];
+        let board = SmallBoard::new(11).unwrap().start_at(1, 0).unwrap();
+        let small = moves
+            .iter()
+            .fold(board, |board, &dir| board.next_move(dir).unwrap());
+        assert_eq!(small.occupied().count_ones(), 4);
+        assert!(small.occupied() & 1 << 120 == 0);
+        assert_eq!(Board::from(small), small.to_board());
+
+        // Two paths to the same cells and position share a key but not
+        // their values.
+        let start = SmallBoard::new(5).unwrap().start_at(0, 0).unwrap();
+        use Direction::*;
+        let play = |moves: &[Direction]| {
+            moves
+                .iter()
+                .fold(start, |board, &dir| board.next_move(dir).unwrap())
+        };
+        let a = play(&[Down, UpRight, Down, UpRight, Left, UpRight, Down]);
+        let b = play(&[Right, DownLeft, Right, DownLeft, Up, DownLeft, Right]);
+        assert_eq!(a.key(), b.key());
+        assert_ne!(a, b);
+    }
+}
@@ -0,0 +1,178 @@
+//! Simulated-annealing solver.
+//!
+//! The candidate solution is a path of moves from the starting board. Each
+//! iteration cuts the path at a random point and repairs it by greedily
+//! extending it with Warnsdorff's rule (breaking ties at random) until it is
+//! blocked. Worse paths are accepted with a probability that shrinks as the
+//! temperature cools, which lets the search escape local maxima on boards too
+//! large for an exhaustive search.
+
+use super::{candidates, replay, Budget, MoveOrder, PartialSolution};
+use crate::board::{Board, Direction};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// CoolingSchedule determines how the temperature falls over a run.
+pub enum CoolingSchedule {
+    /// Multiply the temperature by `factor` after every iteration.
+    Geometric { factor: f64 },
+    /// Lower the temperature by the same amount every iteration, reaching
+    /// zero on the last one.
+    Linear,
+}
+
+#[derive(Debug, Clone)]
+/// Options controlling [`anneal`].
+pub struct AnnealConfig {
+    /// Seed for the random number generator, so runs can be reproduced.
+    pub seed: u64,
+    /// The number of cut-and-repair steps to perform.
+    pub iterations: usize,
+    /// The temperature of the first iteration. A path that scores `d` lower
+    /// than the current one is accepted with probability `exp(-d / t)`.
+    pub initial_temperature: f64,
+    /// How the temperature falls from `initial_temperature`.
+    pub cooling: CoolingSchedule,
+}
+
+impl Default for AnnealConfig {
+    fn default() -> Self {
+        AnnealConfig {
+            seed: 0,
+            iterations: 10_000,
+            initial_temperature: 2.0,
+            cooling: CoolingSchedule::Geometric { factor: 0.999 },
+        }
+    }
+}
+
+impl AnnealConfig {
+    /// Return the temperature at iteration `i`.
+    fn temperature(&self, i: usize) -> f64 {
+        match self.cooling {
+            CoolingSchedule::Geometric { factor } => {
+                self.initial_temperature * factor.powi(i as i32)
+            }
+            CoolingSchedule::Linear => {
+                let remaining = self.iterations.saturating_sub(i) as f64;
+                self.initial_temperature * remaining / self.iterations.max(1) as f64
+            }
+        }
+    }
+}
+
+/// Search for a complete filling of `board` by simulated annealing and return
+/// the highest-scoring board seen. Stops early if the board is won.
+pub fn anneal(board: &Board, config: &AnnealConfig) -> PartialSolution {
+    anneal_within(board, config, &Budget::unlimited())
+}
+
+/// Like [`anneal`], but stops once `budget` runs out.
+pub(super) fn anneal_within(
+    board: &Board,
+    config: &AnnealConfig,
+    budget: &Budget,
+) -> PartialSolution {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut current = repair(board, Vec::new(), &mut rng, budget);
+    let mut best = current.clone();
+    for i in 0..config.iterations {
+        if best.board.is_won() || budget.exceeded() {
+            break;
+        }
+        let cut = rng.gen_range(0..=current.moves.len());
+        let prefix = current.moves[..cut].to_vec();
+        let next = repair(&replay(board, &prefix), prefix, &mut rng, budget);
+
+        let delta = next.board.score() as f64 - current.board.score() as f64;
+        let temperature = config.temperature(i);
+        if delta >= 0.0 || (temperature > 0.0 && rng.gen::<f64>() < (delta / temperature).exp()) {
+            current = next;
+        }
+        if current.board.score() > best.board.score() {
+            best = current.clone();
+        }
+    }
+    best
+}
+
+/// Extend `moves`, which lead from the starting board to `board`, until the
+/// board is blocked. Each step takes a move with the fewest onward moves,
+/// choosing at random between equally good moves. Stops early if `budget`
+/// runs out.
+fn repair(
+    board: &Board,
+    mut moves: Vec<Direction>,
+    rng: &mut StdRng,
+    budget: &Budget,
+) -> PartialSolution {
+    let mut board = board.clone();
+    while budget.spend(&board) {
+        let next = candidates(&board, &MoveOrder::Warnsdorff);
+        let fewest = match next.first() {
+            Some((_, b)) => b.move_count(),
+            None => break,
+        };
+        let ties: Vec<_> = next
+            .into_iter()
+            .take_while(|(_, b)| b.move_count() == fewest)
+            .collect();
+        if let Some((dir, b)) = ties.choose(rng) {
+            moves.push(*dir);
+            board = b.clone();
+        }
+    }
+    PartialSolution { board, moves }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Annealing finds a complete filling of a 10x10 board, and its moves
+    // replay to the board it returns.
+    fn anneal_10() {
+        let start = Board::new(10).start_at(0, 0).unwrap();
+        let best = anneal(&start, &AnnealConfig::default());
+        assert!(best.board.is_won());
+        assert_eq!(replay(&start, &best.moves).score(), best.board.score());
+    }
+
+    #[test]
+    // Both cooling schedules start at the initial temperature and fall.
+    fn cooling_schedules() {
+        for &cooling in &[
+            CoolingSchedule::Geometric { factor: 0.5 },
+            CoolingSchedule::Linear,
+        ] {
+            let config = AnnealConfig {
+                iterations: 10,
+                cooling,
+                ..AnnealConfig::default()
+            };
+            assert_eq!(config.temperature(0), config.initial_temperature);
+            assert!(config.temperature(5) < config.temperature(4));
+        }
+        let linear = AnnealConfig {
+            iterations: 10,
+            cooling: CoolingSchedule::Linear,
+            ..AnnealConfig::default()
+        };
+        assert_eq!(linear.temperature(10), 0.0);
+    }
+
+    #[test]
+    // The same seed always produces the same result.
+    fn seeded_runs_repeat() {
+        let start = Board::new(14).start_at(3, 5).unwrap();
+        let config = AnnealConfig {
+            seed: 7,
+            iterations: 200,
+            ..AnnealConfig::default()
+        };
+        assert_eq!(anneal(&start, &config).moves, anneal(&start, &config).moves);
+    }
+}
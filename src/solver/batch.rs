@@ -0,0 +1,127 @@
+//! Solving many boards in one call.
+
+use super::limits::Budget;
+use super::{run_strategy, Outcome, SolverLimits, Strategy};
+use crate::board::Board;
+use rayon::prelude::*;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Default)]
+/// Options controlling [`solve_batch`].
+pub struct BatchConfig {
+    /// The strategy used for every board.
+    pub strategy: Strategy,
+    /// The limits applied to each board on its own. A cancel token stops
+    /// every board, since clones of a token share its flag.
+    pub limits: SolverLimits,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+/// The result of solving one board of a batch.
+pub struct SolveResult {
+    /// The board as given to [`solve_batch`].
+    pub board: Board,
+    /// How the solve of this board ended.
+    pub outcome: Outcome,
+    /// The number of nodes visited while solving this board.
+    pub nodes: u64,
+    /// The time spent solving this board.
+    pub elapsed: Duration,
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, Default, PartialEq)]
+/// Totals over the results of a batch.
+pub struct BatchSummary {
+    /// The number of boards solved.
+    pub solved: usize,
+    /// The number of boards proven to have no solution.
+    pub exhausted: usize,
+    /// The number of boards stopped by a limit or cancellation, or left
+    /// unsolved by a heuristic strategy.
+    pub stopped: usize,
+    /// The number of nodes visited over every board.
+    pub nodes: u64,
+    /// The time spent over every board. Boards are solved in parallel, so
+    /// this can be more than the time taken by the batch.
+    pub elapsed: Duration,
+}
+
+impl BatchSummary {
+    /// Return the totals over `results`.
+    pub fn of(results: &[SolveResult]) -> Self {
+        let mut summary = BatchSummary::default();
+        for result in results {
+            match result.outcome {
+                Outcome::Solved(_) => summary.solved += 1,
+                Outcome::Exhausted | Outcome::Infeasible(_) => summary.exhausted += 1,
+                _ => summary.stopped += 1,
+            }
+            summary.nodes += result.nodes;
+            summary.elapsed += result.elapsed;
+        }
+        summary
+    }
+}
+
+/// Solve every board in `boards` with the strategy and limits in `config`.
+/// The boards are solved in parallel on the rayon thread pool, and the
+/// results are returned in the order the boards were given.
+pub fn solve_batch<I>(boards: I, config: &BatchConfig) -> Vec<SolveResult>
+where
+    I: IntoIterator<Item = Board>,
+{
+    let boards: Vec<Board> = boards.into_iter().collect();
+    boards
+        .into_par_iter()
+        .map(|board| {
+            let budget = Budget::new(&config.limits);
+            let outcome = run_strategy(&board, &config.strategy, &budget);
+            SolveResult {
+                board,
+                outcome,
+                nodes: budget.nodes(),
+                elapsed: budget.elapsed(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // Every start of a 5x5 board is solved, and the results keep the order
+    // of the boards.
+    fn batch_5() {
+        let boards = (0..25).map(|i| Board::new(5).start_at(i % 5, i / 5).unwrap());
+        let results = solve_batch(boards, &BatchConfig::default());
+        assert_eq!(results.len(), 25);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(result.board.current_position(), (i % 5, i / 5));
+            assert!(result.nodes > 0);
+        }
+        let summary = BatchSummary::of(&results);
+        assert_eq!(summary.solved + summary.exhausted, 25);
+        assert_eq!(summary.stopped, 0);
+    }
+
+    #[test]
+    // Limits apply to each board on its own.
+    fn batch_limits() {
+        let config = BatchConfig {
+            limits: SolverLimits {
+                max_nodes: Some(10),
+                ..SolverLimits::default()
+            },
+            ..BatchConfig::default()
+        };
+        let boards = vec![Board::new(10).start_at(0, 0).unwrap(); 3];
+        let results = solve_batch(boards, &config);
+        // The node that went over the limit is counted too.
+        assert!(results.iter().all(|r| r.nodes == 11));
+        assert_eq!(BatchSummary::of(&results).stopped, 3);
+    }
+}
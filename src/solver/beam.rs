@@ -0,0 +1,88 @@
+//! Beam-search solver.
+//!
+//! Advances a set of partial boards one move at a time. At each depth every
+//! possible move from every board in the beam is played, and only the
+//! `width` children with the most empty cells still reachable from their
+//! current position are kept, preferring children with fewer onward moves
+//! (Warnsdorff's rule) between equally good ones. Wider beams use more memory
+//! but are more likely to complete the board.
+
+use super::{candidates, Budget, MoveOrder, PartialSolution};
+use crate::board::Board;
+use std::cmp::Reverse;
+
+#[derive(Debug, Clone)]
+/// Options controlling [`beam`].
+pub struct BeamConfig {
+    /// The number of partial boards kept at each depth.
+    pub width: usize,
+}
+
+impl Default for BeamConfig {
+    fn default() -> Self {
+        BeamConfig { width: 512 }
+    }
+}
+
+/// Search for a complete filling of `board` with a beam search and return the
+/// deepest board reached. Stops as soon as a board in the beam is won.
+pub fn beam(board: &Board, config: &BeamConfig) -> PartialSolution {
+    beam_within(board, config, &Budget::unlimited())
+}
+
+/// Like [`beam`], but stops once `budget` runs out.
+pub(super) fn beam_within(board: &Board, config: &BeamConfig, budget: &Budget) -> PartialSolution {
+    let mut level = vec![PartialSolution {
+        board: board.clone(),
+        moves: Vec::new(),
+    }];
+    loop {
+        if let Some(won) = level.iter().find(|p| p.board.is_won()) {
+            return won.clone();
+        }
+        let mut next: Vec<((Reverse<usize>, usize), PartialSolution)> = Vec::new();
+        for partial in &level {
+            for (dir, board) in candidates(&partial.board, &MoveOrder::Natural) {
+                if !budget.spend(&board) {
+                    return level.swap_remove(0);
+                }
+                let mut moves = partial.moves.clone();
+                moves.push(dir);
+                let key = (Reverse(board.reachable()), board.move_count());
+                next.push((key, PartialSolution { board, moves }));
+            }
+        }
+        if next.is_empty() {
+            return level.swap_remove(0);
+        }
+        next.sort_by_key(|(key, _)| *key);
+        next.truncate(config.width.max(1));
+        level = next.into_iter().map(|(_, partial)| partial).collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::replay;
+    use super::*;
+
+    #[test]
+    // A beam search completes a 10x10 board, and its moves replay to the
+    // board it returns.
+    fn beam_10() {
+        let start = Board::new(10).start_at(4, 4).unwrap();
+        let best = beam(&start, &BeamConfig { width: 64 });
+        assert!(best.board.is_won());
+        assert_eq!(replay(&start, &best.moves).score(), best.board.score());
+    }
+
+    #[test]
+    // A beam of width one is a greedy search that still returns a consistent
+    // partial solution.
+    fn beam_width_1() {
+        let start = Board::new(8).start_at(3, 3).unwrap();
+        let best = beam(&start, &BeamConfig { width: 1 });
+        assert_eq!(best.board.score(), best.moves.len() + 1);
+        assert!(best.board.is_blocked() || best.board.is_won());
+    }
+}
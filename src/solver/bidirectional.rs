@@ -0,0 +1,171 @@
+//! Bidirectional search, growing the path from both of its ends.
+//!
+//! When the cell holding the last value is known, the path can be grown
+//! backwards from it as well as forwards from the current position. The
+//! backward end is recorded as a chain of clues: a clue holding `v` is placed
+//! one jump from the clue holding `v + 1`. At each step the search extends
+//! whichever end has fewer ways to continue, so a cell that is hard to reach
+//! from one end is dealt with from the other, and each end only has to grow
+//! about half way. Once the ends meet, the remaining forward moves run along
+//! the clues.
+
+use super::limits::Budget;
+use super::{children, Outcome, PartialSolution, SolverConfig};
+use crate::board::{Board, Direction};
+
+/// Search for a sequence of moves that completes `board` by growing the path
+/// from both ends. The last cell is taken from a clue holding the last value,
+/// as set with `Board::with_clues()`. In closed mode, where the last cell must
+/// be one jump from the first, each such cell is tried in turn. Without
+/// either, the search only grows forwards. The `parallelism` option of
+/// `config` is ignored.
+pub fn solve_bidirectional(board: &Board, config: &SolverConfig) -> Option<Vec<Direction>> {
+    bidirectional_within(board, config, &Budget::unlimited()).solution()
+}
+
+/// Like [`solve_bidirectional`], but stops once `budget` runs out.
+pub(super) fn bidirectional_within(
+    board: &Board,
+    config: &SolverConfig,
+    budget: &Budget,
+) -> Outcome {
+    let mut best = PartialSolution {
+        board: board.clone(),
+        moves: Vec::new(),
+    };
+    let last = board.cells() as u16;
+    let mut ends = vec![board.clone()];
+    if config.closed && board.clues().iter().all(|&(_, _, v)| v != last) {
+        if let Some((x, y)) = board.start_position() {
+            ends = Direction::iterator()
+                .filter_map(|&dir| board.target(x, y, dir))
+                .filter_map(|(x, y)| board.add_clue(x, y, last).ok())
+                .collect();
+        }
+    }
+    for end in ends {
+        if let Some(won) = grow(end, config, budget, &mut best.board) {
+            return Outcome::Solved(moves_between(board, &won));
+        }
+    }
+    best.moves = moves_between(board, &best.board);
+    if budget.exceeded() {
+        Outcome::stopped(best, budget)
+    } else {
+        Outcome::Exhausted
+    }
+}
+
+/// Grow the path of `board` from whichever end has fewer ways to continue,
+/// returning the completed board if there is one. `best` is replaced by any
+/// board whose forward path is longer than its own.
+fn grow(board: Board, config: &SolverConfig, budget: &Budget, best: &mut Board) -> Option<Board> {
+    if !budget.spend(&board) {
+        return None;
+    }
+    if board.score() > best.score() {
+        *best = board.clone();
+    }
+    if config.is_goal(&board) {
+        return Some(board);
+    }
+    let forward: Vec<Board> = children(&board, config)
+        .into_iter()
+        .map(|(_, b)| b)
+        .collect();
+    // Until the ends meet, the tail must be extended as well, so a tail that
+    // cannot be extended is as dead as a head that cannot move.
+    let next = match tail(&board) {
+        Some((cell, value)) if forward.len() > 1 => {
+            let backward = extend_tail(&board, cell, value, config);
+            if backward.len() < forward.len() {
+                backward
+            } else {
+                forward
+            }
+        }
+        _ => forward,
+    };
+    next.into_iter().find_map(|b| grow(b, config, budget, best))
+}
+
+/// Return the location and value of the lowest clue in the chain of clues
+/// ending at the last value, if that chain has not yet met the forward path.
+fn tail(board: &Board) -> Option<((usize, usize), u16)> {
+    let clues = board.clues();
+    let find = |value: u16| clues.iter().find(|&&(_, _, v)| v == value);
+    let mut value = board.cells() as u16;
+    let mut cell = find(value)?;
+    while let Some(lower) = find(value - 1) {
+        value -= 1;
+        cell = lower;
+    }
+    if value as usize <= board.score() + 1 {
+        return None;
+    }
+    Some(((cell.0, cell.1), value))
+}
+
+/// Return the boards made by adding a clue one lower than the tail at each
+/// empty cell one jump from it, leaving out any that fail a configured prune.
+fn extend_tail(
+    board: &Board,
+    (x, y): (usize, usize),
+    value: u16,
+    config: &SolverConfig,
+) -> Vec<Board> {
+    let from = board.current_position();
+    Direction::iterator()
+        .filter_map(|&dir| board.target(x, y, dir))
+        .filter_map(|(x, y)| board.add_clue(x, y, value - 1).ok())
+        .filter(|b| config.prunes.iter().all(|p| p.allows(from, b)))
+        .collect()
+}
+
+/// Return the moves that lead from the current position of `from` to that of
+/// `to`, which must extend the path of `from`.
+fn moves_between(from: &Board, to: &Board) -> Vec<Direction> {
+    to.path()[from.score().max(1) - 1..]
+        .windows(2)
+        .map(|pair| {
+            let (x, y) = pair[0];
+            *Direction::iterator()
+                .find(|&&dir| to.target(x, y, dir) == Some(pair[1]))
+                .unwrap()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{count_solutions_with_config, replay};
+    use super::*;
+
+    #[test]
+    // Given the last cell of a 6x6 game, the search completes the board
+    // through it.
+    fn bidirectional_end_clue() {
+        let board = Board::with_clues(6, &[(5, 5, 36)]).unwrap();
+        let start = board.start_at(0, 0).unwrap();
+        let moves = solve_bidirectional(&start, &SolverConfig::default()).unwrap();
+        let end = replay(&start, &moves);
+        assert!(end.is_won());
+        assert_eq!(end.value_at(5, 5), 36);
+    }
+
+    #[test]
+    // In closed mode the search finds a closed tour, and agrees with the
+    // forward search when there is none.
+    fn bidirectional_closed() {
+        let config = SolverConfig {
+            closed: true,
+            ..SolverConfig::default()
+        };
+        let start = Board::new(6).start_at(0, 0).unwrap();
+        let moves = solve_bidirectional(&start, &config).unwrap();
+        assert!(replay(&start, &moves).is_closed_tour());
+        let start = Board::new(5).start_at(1, 0).unwrap();
+        let closed = count_solutions_with_config(&start, &config);
+        assert_eq!(solve_bidirectional(&start, &config).is_some(), closed > 0);
+    }
+}
@@ -0,0 +1,182 @@
+//! An opening book of first moves known to lead to a solution.
+//!
+//! An [`OpeningBook`] maps a board size and starting square to the first few
+//! moves of a solution from there. Because every line in a book is the start
+//! of a complete solution, playing it before searching never leads the
+//! search into a dead part of the tree, and the search only has to find the
+//! rest of the path. A book is built with [`OpeningBook::generate`], and
+//! saved and loaded as a small text file with one line per starting square.
+
+use super::checkpoint::{directions, invalid, number, parse_directions, path_of};
+use super::{replay, solve_all_starts_with_config, solve_with_config, SolverConfig};
+use crate::board::{Board, Direction, MoveRule};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// The first line of every opening book file.
+const HEADER: &str = "square100 book 1";
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// OpeningBook holds the first moves of a solution for each board size and
+/// starting square it knows about.
+pub struct OpeningBook {
+    lines: BTreeMap<(usize, (usize, usize)), Vec<Direction>>,
+}
+
+impl OpeningBook {
+    /// Return an empty book.
+    pub fn new() -> Self {
+        OpeningBook::default()
+    }
+
+    /// Return a book holding the first `depth` moves of a solution from every
+    /// starting square of a `size` x `size` board that has one, found by
+    /// [`solve_all_starts_with_config`] with the options in `config`.
+    pub fn generate(size: usize, depth: usize, config: &SolverConfig) -> Self {
+        let mut book = OpeningBook::new();
+        for start in solve_all_starts_with_config(size, config) {
+            if let Some(mut moves) = start.solution {
+                moves.truncate(depth);
+                book.insert(size, (start.x, start.y), moves);
+            }
+        }
+        book
+    }
+
+    /// Record `moves` as the opening from the square `start` of a `size` x
+    /// `size` board, replacing any opening already recorded there. The moves
+    /// must be the start of a solution for the book to be of any use.
+    pub fn insert(&mut self, size: usize, start: (usize, usize), moves: Vec<Direction>) {
+        self.lines.insert((size, start), moves);
+    }
+
+    /// Return the opening from the square `start` of a `size` x `size`
+    /// board, if the book has one.
+    pub fn get(&self, size: usize, start: (usize, usize)) -> Option<&[Direction]> {
+        self.lines.get(&(size, start)).map(|moves| moves.as_slice())
+    }
+
+    /// Return the number of openings in the book.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Return `true` if the book has no openings.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Search for a sequence of moves that completes `board` like
+    /// [`solve_with_config`], but first play the rest of the book's opening
+    /// if the moves made so far on `board` are the start of it. If the
+    /// search finds nothing after the opening, for instance because the
+    /// board has clues or `config` asks for a closed tour, it falls back to
+    /// searching from `board` itself.
+    pub fn solve(&self, board: &Board, config: &SolverConfig) -> Option<Vec<Direction>> {
+        let plain = board.is_square()
+            && board.blocked().is_empty()
+            && board.rule() == MoveRule::default()
+            && !board.wraps();
+        if let Some((start, played)) = path_of(board).filter(|_| plain) {
+            if let Some(line) = self.get(board.size(), start) {
+                if line.len() > played.len() && line.starts_with(&played) {
+                    let rest = &line[played.len()..];
+                    let opened = replay(board, rest);
+                    if opened.score() == board.score() + rest.len() {
+                        if let Some(moves) = solve_with_config(&opened, config) {
+                            return Some(rest.iter().copied().chain(moves).collect());
+                        }
+                    }
+                }
+            }
+        }
+        solve_with_config(board, config)
+    }
+
+    /// Write the book to the file at `path`.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut text = String::new();
+        writeln!(text, "{}", HEADER).unwrap();
+        for ((size, (x, y)), moves) in &self.lines {
+            writeln!(text, "{} {} {}{}", size, x, y, directions(moves)).unwrap();
+        }
+        fs::write(path, text)
+    }
+
+    /// Read a book written by [`save`](OpeningBook::save).
+    pub fn load<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let text = fs::read_to_string(path)?;
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(invalid("not an opening book file"));
+        }
+        let mut book = OpeningBook::new();
+        for line in lines.filter(|line| !line.trim().is_empty()) {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            let size = number(words.first())?;
+            let start = (number(words.get(1))?, number(words.get(2))?);
+            let moves = parse_directions(words.get(3..).unwrap_or(&[]))?;
+            let end = Board::try_with_dimensions(size, size)
+                .and_then(|board| board.start_at(start.0, start.1))
+                .map(|board| replay(&board, &moves).score())
+                .map_err(|e| invalid(&e.to_string()))?;
+            if end != moves.len() + 1 {
+                return Err(invalid("opening moves do not fit the board"));
+            }
+            book.insert(size, start, moves);
+        }
+        Ok(book)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A generated book survives a save and load, and solving through it
+    // plays its opening first.
+    fn book_round_trip() {
+        let config = SolverConfig::default();
+        let book = OpeningBook::generate(5, 6, &config);
+        assert!(!book.is_empty());
+        let path = std::env::temp_dir().join(format!("square100-{}-book", std::process::id()));
+        book.save(&path).unwrap();
+        let loaded = OpeningBook::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(loaded, book);
+
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let line = book.get(5, (0, 0)).unwrap().to_vec();
+        let after = start.next_move(line[0]).unwrap();
+        let moves = book.solve(&after, &config).unwrap();
+        assert!(moves.starts_with(&line[1..]));
+        assert!(replay(&after, &moves).is_won());
+    }
+
+    #[test]
+    // A board with a clue that the opening contradicts is still solved.
+    fn book_falls_back() {
+        let config = SolverConfig::default();
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let first = solve_with_config(&start, &config).unwrap()[0];
+        let (x, y) = start.target(0, 0, first).unwrap();
+        let other = **start
+            .possible_moves()
+            .iter()
+            .find(|&&&d| d != first)
+            .unwrap();
+        let mut book = OpeningBook::new();
+        book.insert(5, (0, 0), vec![other]);
+        let board = Board::with_clues(5, &[(x, y, 2)])
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap();
+        let moves = book.solve(&board, &config).unwrap();
+        assert_eq!(moves[0], first);
+        assert!(replay(&board, &moves).is_won());
+    }
+}
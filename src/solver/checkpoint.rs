@@ -0,0 +1,494 @@
+//! A resumable depth-first solver.
+//!
+//! [`Solver`] runs the same search as [`solve_with_config`], but keeps its
+//! search stack in memory rather than on the call stack. A run can be stopped
+//! by [`SolverLimits`], written to disk with [`Solver::checkpoint`], and
+//! picked up later, possibly by another process, with [`Solver::resume`].
+//!
+//! A checkpoint is a small text file. Only the move index at each level of
+//! the stack is saved; the boards are rebuilt by replaying those moves, since
+//! the moves tried from a board depend only on the board and the options.
+//!
+//! [`solve_with_config`]: super::solve_with_config
+
+use super::limits::Budget;
+use super::stack::{is_dead, Stack};
+use super::transposition::TranspositionTable;
+use super::{replay, MoveOrder, Outcome, PartialSolution, Prune};
+use super::{SolverConfig, SolverLimits};
+use crate::board::{Board, Direction, MoveRule};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// The first line of every checkpoint file.
+const HEADER: &str = "square100 checkpoint 1";
+
+/// Solver is an exhaustive depth-first search that can be paused and resumed.
+/// The `parallelism` option of its config is ignored. Neither the
+/// transposition table nor the tablebase is saved in a checkpoint, so a
+/// resumed search starts with an empty transposition table and no tablebase.
+pub struct Solver {
+    /// The board the search started from.
+    start: Board,
+    config: SolverConfig,
+    table: Option<Mutex<TranspositionTable>>,
+    /// The search stack, whose path leads from `start` to the board on top.
+    stack: Stack,
+    /// The number of boards visited by every run so far.
+    nodes: u64,
+    best: PartialSolution,
+}
+
+impl Solver {
+    /// Return a solver that will search for a completion of `board` using the
+    /// options in `config`.
+    pub fn new(board: &Board, config: &SolverConfig) -> Self {
+        let mut solver = Solver {
+            start: board.clone(),
+            config: config.clone(),
+            table: config
+                .transposition
+                .map(|n| Mutex::new(TranspositionTable::new(n))),
+            stack: Stack::new(Vec::new()),
+            nodes: 0,
+            best: PartialSolution {
+                board: board.clone(),
+                moves: Vec::new(),
+            },
+        };
+        solver.stack.push(board.clone(), config);
+        solver
+    }
+
+    /// Search until the board is solved, the search is exhausted, or one of
+    /// `limits` is reached. A solver stopped by a limit can be run again, or
+    /// checkpointed, to carry on where it left off. Running a solver again
+    /// after a solution continues the search past that solution.
+    pub fn run(&mut self, limits: &SolverLimits) -> Outcome {
+        if self.nodes == 0 && self.config.is_goal(&self.start) {
+            self.nodes = 1;
+            return Outcome::Solved(Vec::new());
+        }
+        let budget = Budget::new(limits);
+        let table = self.table.as_ref();
+        loop {
+            if self.stack.top(&self.config, table).is_none() {
+                return Outcome::Exhausted;
+            }
+            let board = match self.stack.advance(&budget) {
+                Some(board) => board,
+                None => return Outcome::stopped(self.best.clone(), &budget),
+            };
+            self.nodes += 1;
+            if board.score() > self.best.board.score() {
+                self.best = PartialSolution {
+                    board: board.clone(),
+                    moves: self.stack.path.clone(),
+                };
+            }
+            if self.config.is_goal(&board) {
+                self.stack.solved();
+                let moves = self.stack.path.clone();
+                self.stack.back();
+                return Outcome::Solved(moves);
+            }
+            if is_dead(&board, &self.config, table) {
+                self.stack.back();
+                continue;
+            }
+            self.stack.push(board, &self.config);
+        }
+    }
+
+    /// Return the number of boards visited by every run of this solver,
+    /// including runs before it was checkpointed.
+    pub fn nodes(&self) -> u64 {
+        self.nodes
+    }
+
+    /// Return the highest-scoring board reached so far, and the moves leading
+    /// to it.
+    pub fn best(&self) -> &PartialSolution {
+        &self.best
+    }
+
+    /// Write the state of the search to the file at `path`. The file is
+    /// written in full to a temporary file next to it first, so an existing
+    /// checkpoint is never left half written.
+    pub fn checkpoint<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let path = path.as_ref();
+        let mut text = String::new();
+        writeln!(text, "{}", HEADER).unwrap();
+        if self.start.is_square() {
+            writeln!(text, "size {}", self.start.width()).unwrap();
+        } else {
+            let (width, height) = (self.start.width(), self.start.height());
+            writeln!(text, "size {} {}", width, height).unwrap();
+        }
+        match self.start.rule() {
+            rule if rule == MoveRule::default() => {}
+            MoveRule::Jump { straight, diagonal } => {
+                writeln!(text, "rule jump {} {}", straight, diagonal).unwrap()
+            }
+            MoveRule::Knight => writeln!(text, "rule knight").unwrap(),
+        }
+        if self.start.wraps() {
+            writeln!(text, "wrap").unwrap();
+        }
+        let blocked = self.start.blocked();
+        if !blocked.is_empty() {
+            let cells: String = blocked
+                .iter()
+                .map(|(x, y)| format!(" {} {}", x, y))
+                .collect();
+            writeln!(text, "blocked{}", cells).unwrap();
+        }
+        let clues: String = self
+            .start
+            .clues()
+            .iter()
+            .map(|(x, y, v)| format!(" {} {} {}", x, y, v))
+            .collect();
+        writeln!(text, "clues{}", clues).unwrap();
+        match path_of(&self.start) {
+            Some(((x, y), moves)) => {
+                writeln!(text, "start {} {}", x, y).unwrap();
+                writeln!(text, "moves{}", directions(&moves)).unwrap();
+            }
+            None => writeln!(text, "start none").unwrap(),
+        }
+        match &self.config.order {
+            MoveOrder::Random { seed } => writeln!(text, "order Random {}", seed).unwrap(),
+            MoveOrder::Custom(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "a custom move order cannot be checkpointed",
+                ))
+            }
+            order => writeln!(text, "order {:?}", order).unwrap(),
+        }
+        let prunes: String = self
+            .config
+            .prunes
+            .iter()
+            .map(|p| format!(" {:?}", p))
+            .collect();
+        writeln!(text, "prunes{}", prunes).unwrap();
+        match self.config.transposition {
+            Some(capacity) => writeln!(text, "transposition {}", capacity).unwrap(),
+            None => writeln!(text, "transposition none").unwrap(),
+        }
+        writeln!(text, "symmetry {}", self.config.symmetry).unwrap();
+        writeln!(text, "closed {}", self.config.closed).unwrap();
+        writeln!(text, "nodes {}", self.nodes).unwrap();
+        writeln!(text, "best{}", directions(&self.best.moves)).unwrap();
+        let stack: String = self
+            .stack
+            .frames
+            .iter()
+            .map(|f| format!(" {}", f.next))
+            .collect();
+        writeln!(text, "stack{}", stack).unwrap();
+
+        let temp = path.with_extension("tmp");
+        fs::write(&temp, text)?;
+        fs::rename(&temp, path)
+    }
+
+    /// Read a checkpoint written by [`checkpoint`](Solver::checkpoint) and
+    /// return a solver that carries on from where it was taken.
+    pub fn resume<P: AsRef<Path>>(path: P) -> io::Result<Solver> {
+        let text = fs::read_to_string(path)?;
+        let mut lines = text.lines();
+        if lines.next() != Some(HEADER) {
+            return Err(invalid("not a checkpoint file"));
+        }
+        let mut fields = lines
+            .map(|line| {
+                let mut words = line.split_whitespace();
+                (words.next().unwrap_or(""), words.collect::<Vec<_>>())
+            })
+            .peekable();
+        let mut field = |name: &str| match fields.next_if(|(key, _)| *key == name) {
+            Some((_, words)) => Ok(words),
+            None => Err(invalid(&format!("missing field '{}'", name))),
+        };
+
+        let size = field("size")?;
+        let width = number(size.first())?;
+        let height = match size.get(1) {
+            Some(_) => number(size.get(1))?,
+            None => width,
+        };
+        // Only boards with another move rule, that wrap, or with blocked
+        // cells have these fields.
+        let rule = match field("rule").unwrap_or_default().as_slice() {
+            [] => MoveRule::default(),
+            ["jump", straight, diagonal] => MoveRule::Jump {
+                straight: number(Some(straight))? as u8,
+                diagonal: number(Some(diagonal))? as u8,
+            },
+            ["knight"] => MoveRule::Knight,
+            _ => return Err(invalid("unknown move rule")),
+        };
+        let wrap = field("wrap").is_ok();
+        let blocked = field("blocked")
+            .unwrap_or_default()
+            .chunks(2)
+            .map(|c| Ok((number(c.first())?, number(c.get(1))?)))
+            .collect::<io::Result<Vec<_>>>()?;
+        let clues = field("clues")?
+            .chunks(3)
+            .map(|c| {
+                Ok((
+                    number(c.first())?,
+                    number(c.get(1))?,
+                    number(c.get(2))? as u16,
+                ))
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let mut board = Board::try_with_dimensions(width, height)
+            .and_then(|board| board.with_rule(rule))
+            .and_then(|board| board.with_wrap(wrap))
+            .and_then(|board| {
+                blocked
+                    .iter()
+                    .try_fold(board, |board, &(x, y)| board.block(x, y))
+            })
+            .and_then(|board| board.add_clues(&clues))
+            .map_err(|e| invalid(&e.to_string()))?;
+        let start = field("start")?;
+        if start.first() != Some(&"none") {
+            let (x, y) = (number(start.first())?, number(start.get(1))?);
+            board = board.start_at(x, y).map_err(|e| invalid(&e.to_string()))?;
+            let moves = parse_directions(&field("moves")?)?;
+            board = apply(&board, &moves)?;
+        }
+        let order = field("order")?;
+        let order = match order.first() {
+            Some(&"Natural") => MoveOrder::Natural,
+            Some(&"Warnsdorff") => MoveOrder::Warnsdorff,
+            Some(&"CenterOut") => MoveOrder::CenterOut,
+            Some(&"Random") => MoveOrder::Random {
+                seed: number(order.get(1))? as u64,
+            },
+            _ => return Err(invalid("unknown move order")),
+        };
+        let prunes = field("prunes")?
+            .iter()
+            .map(|&name| match name {
+                "ForwardCheck" => Ok(Prune::ForwardCheck),
+                "DeadEnd" => Ok(Prune::DeadEnd),
+                "Connectivity" => Ok(Prune::Connectivity),
+                "Articulation" => Ok(Prune::Articulation),
+                _ => Err(invalid(&format!("unknown prune '{}'", name))),
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        let transposition = match field("transposition")?.first() {
+            Some(&"none") => None,
+            capacity => Some(number(capacity)?),
+        };
+        let symmetry = match field("symmetry")?.first() {
+            Some(&"true") => true,
+            Some(&"false") => false,
+            _ => return Err(invalid("bad symmetry flag")),
+        };
+        let closed = match field("closed")?.first() {
+            Some(&"true") => true,
+            Some(&"false") => false,
+            _ => return Err(invalid("bad closed flag")),
+        };
+        let config = SolverConfig {
+            order,
+            prunes,
+            transposition,
+            symmetry,
+            closed,
+            ..SolverConfig::default()
+        };
+        let nodes = number(field("nodes")?.first())?;
+        let best = parse_directions(&field("best")?)?;
+        let stack = field("stack")?
+            .iter()
+            .map(|&n| number(Some(&n)))
+            .collect::<io::Result<Vec<usize>>>()?;
+
+        let mut solver = Solver::new(&board, &config);
+        solver.nodes = nodes as u64;
+        solver.best = PartialSolution {
+            board: apply(&board, &best)?,
+            moves: best,
+        };
+        solver.stack.frames.clear();
+        let mut current = board;
+        for (level, &next) in stack.iter().enumerate() {
+            solver.stack.push(current, &config);
+            let frame = solver.stack.frames.last_mut().unwrap();
+            if next > frame.children.len() || (next == 0 && level + 1 < stack.len()) {
+                return Err(invalid("stack does not match the board"));
+            }
+            frame.next = next;
+            // Whether a solution was found below a frame is not saved, so
+            // none of them may be remembered as dead.
+            frame.solved = true;
+            if level + 1 < stack.len() {
+                let (dir, board) = frame.children[next - 1].clone();
+                solver.stack.path.push(dir);
+                current = board;
+            } else {
+                break;
+            }
+        }
+        Ok(solver)
+    }
+}
+
+/// Return the starting square of `board` and the moves that lead from it to
+/// the current position, or `None` if the board has not been started.
+pub(super) fn path_of(board: &Board) -> Option<((usize, usize), Vec<Direction>)> {
+    let at = board.path();
+    let moves = at
+        .windows(2)
+        .map(|pair| {
+            let (x, y) = pair[0];
+            *Direction::iterator()
+                .find(|&&dir| board.target(x, y, dir) == Some(pair[1]))
+                .unwrap()
+        })
+        .collect();
+    Some((*at.first()?, moves))
+}
+
+/// Format `moves` as a list of direction names, each preceded by a space.
+pub(super) fn directions(moves: &[Direction]) -> String {
+    moves.iter().map(|d| format!(" {:?}", d)).collect()
+}
+
+/// Parse a list of direction names written by [`directions`].
+pub(super) fn parse_directions(words: &[&str]) -> io::Result<Vec<Direction>> {
+    words
+        .iter()
+        .map(|&word| {
+            Direction::iterator()
+                .find(|d| format!("{:?}", d) == word)
+                .copied()
+                .ok_or_else(|| invalid(&format!("unknown direction '{}'", word)))
+        })
+        .collect()
+}
+
+/// Apply `moves` to `board`, failing if any of them is not valid.
+fn apply(board: &Board, moves: &[Direction]) -> io::Result<Board> {
+    let end = replay(board, moves);
+    if end.score() != board.score() + moves.len() {
+        return Err(invalid("moves do not match the board"));
+    }
+    Ok(end)
+}
+
+/// Parse a non-negative number.
+pub(super) fn number(word: Option<&&str>) -> io::Result<usize> {
+    word.and_then(|w| w.parse().ok())
+        .ok_or_else(|| invalid("expected a number"))
+}
+
+/// Return an error for a malformed checkpoint.
+pub(super) fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return a path in the system temporary directory unique to `name`.
+    fn temp_path(name: &str) -> std::path::PathBuf {
+        std::env::temp_dir().join(format!("square100-{}-{}", std::process::id(), name))
+    }
+
+    #[test]
+    // A solver run without limits agrees with the recursive solver.
+    fn solver_5() {
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let config = SolverConfig::default();
+        let mut solver = Solver::new(&board, &config);
+        let moves = solver.run(&SolverLimits::default()).solution().unwrap();
+        assert_eq!(
+            Some(moves),
+            super::super::solve_with_config(&board, &config)
+        );
+    }
+
+    #[test]
+    // A search checkpointed after every few nodes, and resumed each time,
+    // finds the same solution as one that is not, blocked cell and all.
+    fn checkpoint_resume() {
+        let board = Board::new(6).block(5, 5).unwrap().start_at(0, 0).unwrap();
+        let board = board.next_move(Direction::Right).unwrap();
+        let config = SolverConfig {
+            order: MoveOrder::Warnsdorff,
+            transposition: Some(1024),
+            ..SolverConfig::default()
+        };
+        let mut whole = Solver::new(&board, &config);
+        let expected = whole.run(&SolverLimits::default()).solution();
+
+        let path = temp_path("checkpoint_resume");
+        let limits = SolverLimits {
+            max_nodes: Some(7),
+            ..SolverLimits::default()
+        };
+        Solver::new(&board, &config).checkpoint(&path).unwrap();
+        let outcome = loop {
+            let mut solver = Solver::resume(&path).unwrap();
+            match solver.run(&limits) {
+                Outcome::BudgetExceeded { best } => {
+                    assert_eq!(&best.board.score(), &solver.best().board.score());
+                    solver.checkpoint(&path).unwrap();
+                }
+                outcome => break outcome,
+            }
+        };
+        fs::remove_file(&path).unwrap();
+        assert!(expected.is_some());
+        assert_eq!(outcome.solution(), expected);
+    }
+
+    #[test]
+    // A board with another move rule that wraps around is checkpointed with
+    // its rule and wrap mode.
+    fn checkpoint_rule() {
+        let rule = MoveRule::Jump {
+            straight: 1,
+            diagonal: 2,
+        };
+        let board = Board::new(6)
+            .with_rule(rule)
+            .and_then(|b| b.with_wrap(true))
+            .and_then(|b| b.start_at(0, 0))
+            .unwrap();
+        let path = temp_path("checkpoint_rule");
+        Solver::new(&board, &SolverConfig::default())
+            .checkpoint(&path)
+            .unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let mut solver = Solver::resume(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert!(text.contains("\nrule jump 1 2\nwrap\n"));
+        let moves = solver.run(&SolverLimits::default()).solution().unwrap();
+        assert!(replay(&board, &moves).is_won());
+    }
+
+    #[test]
+    // A file that is not a checkpoint is rejected.
+    fn resume_invalid() {
+        let path = temp_path("resume_invalid");
+        fs::write(&path, "hello").unwrap();
+        let err = Solver::resume(&path).err().unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
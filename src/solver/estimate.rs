@@ -0,0 +1,91 @@
+//! Estimating the size of a search tree before searching it.
+//!
+//! Knuth's estimator follows a single random path from the root to a leaf.
+//! If the nodes on the path have `d1`, `d2`, ... children, the tree is
+//! estimated to hold `1 + d1 + d1*d2 + ...` nodes. Each probe is an unbiased
+//! estimate, so averaging many probes gives a usable prediction of how long
+//! an exhaustive search will take, although the spread between probes can be
+//! very large.
+
+use super::{children, SolverConfig};
+use crate::board::Board;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+/// Estimate the number of nodes in the search tree of `board` with the
+/// default options, averaging `samples` random probes. This is the number of
+/// nodes visited by a search that finds no solution, or by
+/// `count_solutions()`. The probes are seeded, so the estimate is the same
+/// each time.
+pub fn estimate_tree_size(board: &Board, samples: usize) -> f64 {
+    estimate_tree_size_with_config(board, &SolverConfig::default(), samples)
+}
+
+/// Like [`estimate_tree_size`], but for a search using the options in
+/// `config`. The `order` option has no effect on the size of the tree.
+pub fn estimate_tree_size_with_config(board: &Board, config: &SolverConfig, samples: usize) -> f64 {
+    let mut rng = StdRng::seed_from_u64(0);
+    let total: f64 = (0..samples).map(|_| probe(board, config, &mut rng)).sum();
+    total / samples.max(1) as f64
+}
+
+/// Follow one random path from `board` to a leaf of the search tree and
+/// return Knuth's estimate of the size of the tree.
+fn probe(board: &Board, config: &SolverConfig, rng: &mut StdRng) -> f64 {
+    let mut board = board.clone();
+    let mut width = 1.0;
+    let mut estimate = 1.0;
+    loop {
+        if config.is_goal(&board) {
+            return estimate;
+        }
+        let next = children(&board, config);
+        match next.choose(rng) {
+            Some((_, child)) => {
+                width *= next.len() as f64;
+                estimate += width;
+                board = child.clone();
+            }
+            None => return estimate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Return the number of nodes in the search tree below `board`.
+    fn tree_size(board: &Board, config: &SolverConfig) -> f64 {
+        1.0 + children(board, config)
+            .iter()
+            .map(|(_, child)| tree_size(child, config))
+            .sum::<f64>()
+    }
+
+    #[test]
+    // Averaged over many probes, the estimate is close to the real size of
+    // the search tree.
+    fn estimate_5() {
+        let board = Board::new(5).start_at(2, 2).unwrap();
+        let config = SolverConfig {
+            prunes: vec![],
+            ..SolverConfig::default()
+        };
+        let size = tree_size(&board, &config);
+        let estimate = estimate_tree_size_with_config(&board, &config, 20_000);
+        assert!(
+            (estimate - size).abs() < size * 0.1,
+            "{} vs {}",
+            estimate,
+            size
+        );
+    }
+
+    #[test]
+    // A board with no moves is a tree of one node.
+    fn estimate_leaf() {
+        assert_eq!(estimate_tree_size(&Board::new(5), 10), 1.0);
+    }
+}
@@ -0,0 +1,341 @@
+//! Exact-cover solver using Knuth's Dancing Links (DLX).
+//!
+//! The rest of the path is modelled as a choice of successor for the current
+//! position and for every empty cell. Each cell must be left exactly once and
+//! each empty cell entered exactly once, so the items to cover are the "out"
+//! of every such cell and the "in" of every empty cell, plus an "in" for a
+//! virtual cell after the last one. Each option is a jump from one cell to
+//! another, or from a cell to the virtual end, and covers one "out" and one
+//! "in". A jump that would close a loop of empty cells is never chosen, so
+//! every exact cover is a single path from the current position through all
+//! of the empty cells.
+//!
+//! The search is independent of the depth-first solver and makes a useful
+//! cross-check of it; it also counts the solutions of small boards quickly.
+
+use super::{replay, SolverConfig};
+use crate::board::{Board, Direction};
+
+/// Search for a sequence of moves that completes `board` with Dancing Links.
+/// Only the `closed` option of `config` is used. An unstarted board has no
+/// solution.
+pub fn solve_exact_cover(board: &Board, config: &SolverConfig) -> Option<Vec<Direction>> {
+    let mut found = None;
+    cover(board, config, |moves| {
+        found = Some(moves);
+        true
+    });
+    found
+}
+
+/// Count every sequence of moves that completes `board` with Dancing Links.
+/// Only the `closed` option of `config` is used. An unstarted board has no
+/// solutions.
+pub fn count_exact_cover(board: &Board, config: &SolverConfig) -> u64 {
+    let mut count = 0;
+    cover(board, config, |_| {
+        count += 1;
+        false
+    });
+    count
+}
+
+/// Call `found` with the moves of each completion of `board` until it returns
+/// `true`.
+fn cover<F>(board: &Board, config: &SolverConfig, mut found: F)
+where
+    F: FnMut(Vec<Direction>) -> bool,
+{
+    if board.start_position().is_none() {
+        return;
+    }
+    if config.is_goal(board) {
+        found(Vec::new());
+        return;
+    }
+    let mut links = Links::new(board, config);
+    let mut chosen = Vec::new();
+    links.search(&mut chosen, &mut |links: &Links, chosen: &[usize]| {
+        let moves = links.moves(board, chosen);
+        // Clues are checked once the path is known rather than modelled as
+        // items, since they constrain the position of a cell along the path.
+        config.is_goal(&replay(board, &moves)) && found(moves)
+    });
+}
+
+/// The sparse matrix of a board's exact-cover problem, as doubly linked lists.
+///
+/// Nodes `0..=items` are the list headers, with node 0 heading the list of
+/// items still to cover. Every other node belongs to an option and to the
+/// column of the item it covers.
+struct Links {
+    left: Vec<usize>,
+    right: Vec<usize>,
+    up: Vec<usize>,
+    down: Vec<usize>,
+    /// The item each node covers.
+    item: Vec<usize>,
+    /// The option each node belongs to.
+    option: Vec<usize>,
+    /// The number of options still covering each item.
+    len: Vec<usize>,
+    /// The first node, and the cells jumped from and to, of each option. A
+    /// jump to the virtual end goes to `None`.
+    options: Vec<(usize, usize, Option<usize>)>,
+    /// For the cell at either end of a chain of chosen jumps, the cell at the
+    /// other end.
+    end: Vec<usize>,
+    /// The board's current position.
+    from: usize,
+}
+
+impl Links {
+    /// Build the matrix for the rest of the path of `board`.
+    fn new(board: &Board, config: &SolverConfig) -> Links {
+        let width = board.width();
+        let (x, y) = board.current_position();
+        let from = y * width + x;
+        let grid = width * board.height();
+        let empty = |i: usize| {
+            let (x, y) = (i % width, i / width);
+            board.value_at(x, y) == 0 && !board.blocked_at(x, y)
+        };
+        let first = board.start_position().map(|(x, y)| y * width + x);
+        // Every cell that must be left gets an "out" item, every empty cell
+        // an "in" item, and the virtual end the last item.
+        let mut out_item = vec![0; grid];
+        let mut in_item = vec![0; grid];
+        let mut items = 0;
+        for i in (0..grid).filter(|&i| i == from || empty(i)) {
+            items += 1;
+            out_item[i] = items;
+            if i != from {
+                items += 1;
+                in_item[i] = items;
+            }
+        }
+        let end_item = items + 1;
+        let items = end_item;
+
+        let mut links = Links {
+            left: (0..=items).map(|i| (i + items) % (items + 1)).collect(),
+            right: (0..=items).map(|i| (i + 1) % (items + 1)).collect(),
+            up: (0..=items).collect(),
+            down: (0..=items).collect(),
+            item: (0..=items).collect(),
+            option: vec![0; items + 1],
+            len: vec![0; items + 1],
+            options: Vec::new(),
+            end: (0..grid).collect(),
+            from,
+        };
+        for u in (0..grid).filter(|&i| out_item[i] != 0) {
+            let (ux, uy) = (u % width, u / width);
+            for &dir in Direction::iterator() {
+                if let Some((vx, vy)) = board.target(ux, uy, dir) {
+                    let v = vy * width + vx;
+                    if in_item[v] != 0 {
+                        links.add_option(u, Some(v), &[out_item[u], in_item[v]]);
+                    }
+                }
+            }
+            let closes = first.is_some_and(|s| {
+                Direction::iterator()
+                    .any(|&dir| board.target(ux, uy, dir) == Some((s % width, s / width)))
+            });
+            if !config.closed || closes {
+                links.add_option(u, None, &[out_item[u], end_item]);
+            }
+        }
+        links
+    }
+
+    /// Add an option for the jump from cell `u` to cell `v`, covering `items`.
+    fn add_option(&mut self, u: usize, v: Option<usize>, items: &[usize]) {
+        let option = self.options.len();
+        self.options.push((self.item.len(), u, v));
+        for &i in items {
+            let node = self.item.len();
+            self.item.push(i);
+            self.option.push(option);
+            self.left.push(node);
+            self.right.push(node);
+            self.up.push(self.up[i]);
+            self.down.push(i);
+            let last = self.up[i];
+            self.down[last] = node;
+            self.up[i] = node;
+            self.len[i] += 1;
+        }
+    }
+
+    /// Return the nodes of the option that `node` belongs to, other than
+    /// `node` itself.
+    fn others(&self, node: usize) -> impl Iterator<Item = usize> {
+        let first = self.options[self.option[node]].0;
+        let last = self
+            .options
+            .get(self.option[node] + 1)
+            .map_or(self.item.len(), |o| o.0);
+        (first..last).filter(move |&n| n != node)
+    }
+
+    /// Remove item `i` from the list of items to cover, along with every
+    /// option that covers it.
+    fn cover(&mut self, i: usize) {
+        let mut p = self.down[i];
+        while p != i {
+            for q in self.others(p) {
+                self.up[self.down[q]] = self.up[q];
+                self.down[self.up[q]] = self.down[q];
+                self.len[self.item[q]] -= 1;
+            }
+            p = self.down[p];
+        }
+        self.right[self.left[i]] = self.right[i];
+        self.left[self.right[i]] = self.left[i];
+    }
+
+    /// Undo [`Links::cover`] for item `i`.
+    fn uncover(&mut self, i: usize) {
+        self.right[self.left[i]] = i;
+        self.left[self.right[i]] = i;
+        let mut p = self.up[i];
+        while p != i {
+            for q in self.others(p) {
+                self.up[self.down[q]] = q;
+                self.down[self.up[q]] = q;
+                self.len[self.item[q]] += 1;
+            }
+            p = self.up[p];
+        }
+    }
+
+    /// Choose options covering every remaining item, calling `found` with
+    /// the options in `chosen` for each exact cover until it returns `true`.
+    /// Returns `true` if it did.
+    fn search<F>(&mut self, chosen: &mut Vec<usize>, found: &mut F) -> bool
+    where
+        F: FnMut(&Links, &[usize]) -> bool,
+    {
+        if self.right[0] == 0 {
+            return found(self, chosen);
+        }
+        // Branch on the item with the fewest options left.
+        let mut i = self.right[0];
+        let mut j = self.right[i];
+        while j != 0 {
+            if self.len[j] < self.len[i] {
+                i = j;
+            }
+            j = self.right[j];
+        }
+        if self.len[i] == 0 {
+            return false;
+        }
+        self.cover(i);
+        let mut stop = false;
+        let mut p = self.down[i];
+        while p != i && !stop {
+            let (_, u, v) = self.options[self.option[p]];
+            if v.is_none_or(|v| self.end[u] != v) {
+                let joined = v.map(|v| self.join(u, v));
+                let others: Vec<usize> = self.others(p).collect();
+                for &q in &others {
+                    self.cover(self.item[q]);
+                }
+                chosen.push(self.option[p]);
+                stop = self.search(chosen, found);
+                chosen.pop();
+                for &q in others.iter().rev() {
+                    self.uncover(self.item[q]);
+                }
+                if let (Some(v), Some((a, b))) = (v, joined) {
+                    self.end[a] = u;
+                    self.end[b] = v;
+                }
+            }
+            p = self.down[p];
+        }
+        self.uncover(i);
+        stop
+    }
+
+    /// Join the chain ending at `u` to the chain starting at `v`, returning
+    /// the far ends of the two chains.
+    fn join(&mut self, u: usize, v: usize) -> (usize, usize) {
+        let (a, b) = (self.end[u], self.end[v]);
+        self.end[a] = b;
+        self.end[b] = a;
+        (a, b)
+    }
+
+    /// Return the moves along the path made by the `chosen` jumps.
+    fn moves(&self, board: &Board, chosen: &[usize]) -> Vec<Direction> {
+        let width = board.width();
+        let mut next = vec![None; width * board.height()];
+        for &o in chosen {
+            let (_, u, v) = self.options[o];
+            next[u] = v;
+        }
+        let mut moves = Vec::new();
+        let mut at = self.from;
+        while let Some(to) = next[at] {
+            let dir = Direction::iterator()
+                .find(|&&dir| {
+                    board.target(at % width, at / width, dir) == Some((to % width, to / width))
+                })
+                .unwrap();
+            moves.push(*dir);
+            at = to;
+        }
+        moves
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::count_solutions_with_config;
+    use super::*;
+
+    #[test]
+    // Dancing Links agrees with the depth-first search on the number of
+    // solutions from every start of a 5x5 board.
+    fn exact_cover_counts() {
+        let config = SolverConfig::default();
+        for y in 0..3 {
+            for x in y..3 {
+                let start = Board::new(5).start_at(x, y).unwrap();
+                assert_eq!(
+                    count_exact_cover(&start, &config),
+                    count_solutions_with_config(&start, &config),
+                    "start ({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    #[test]
+    // Closed mode counts only closed tours, and clues are respected.
+    fn exact_cover_closed_and_clues() {
+        let closed = SolverConfig {
+            closed: true,
+            ..SolverConfig::default()
+        };
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        assert_eq!(count_exact_cover(&start, &closed), 96);
+        let board = Board::with_clues(5, &[(4, 4, 25)]).unwrap();
+        let start = board.start_at(0, 0).unwrap();
+        let config = SolverConfig::default();
+        assert_eq!(
+            count_exact_cover(&start, &config),
+            count_solutions_with_config(&start, &config)
+        );
+        let moves = solve_exact_cover(&start, &config).unwrap();
+        let end = replay(&start, &moves);
+        assert!(end.is_won());
+        assert_eq!(end.value_at(4, 4), 25);
+    }
+}
@@ -0,0 +1,194 @@
+//! Quick proofs that a board cannot be completed.
+//!
+//! The cells still to be filled, together with the current position, form a
+//! graph whose edges are the jumps between them. Completing the board means
+//! finding a path through this graph that starts at the current position and
+//! visits every cell once. Counting arguments rule this out without any
+//! search when:
+//!
+//! * the graph is not connected,
+//! * more cells than one can only be entered and never left, or
+//! * the graph is bipartite, so the path alternates between its two classes
+//!   of cells, but the classes are the wrong sizes for that.
+//!
+//! On an empty square board the jump graph has odd cycles, so the last check
+//! never rules out a start. Once part of the board has been filled, or on a
+//! board with clues, the remaining graph is often bipartite.
+
+use crate::board::{Board, Direction};
+use std::fmt;
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+/// The reason a board cannot be completed.
+pub enum Infeasible {
+    /// The empty cell at (x, y) cannot be reached from the current position.
+    Disconnected { x: usize, y: usize },
+    /// `count` empty cells can be entered but never left, and only one of
+    /// them can be the last cell filled (two, if the board is not started).
+    DeadEnds { count: usize },
+    /// The cells left form a bipartite graph, but the class holding the
+    /// current position has `current` cells and the other has `other`, so no
+    /// path can alternate between them. On a board that is not started, the
+    /// first empty cell stands in for the current position.
+    Parity { current: usize, other: usize },
+}
+
+impl fmt::Display for Infeasible {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Infeasible::Disconnected { x, y } => {
+                write!(f, "cell [{},{}] cannot be reached", x, y)
+            }
+            Infeasible::DeadEnds { count } => {
+                write!(f, "{} cells can be entered but never left", count)
+            }
+            Infeasible::Parity { current, other } => write!(
+                f,
+                "jumps alternate between classes of {} and {} cells",
+                current, other
+            ),
+        }
+    }
+}
+
+/// Check `board` for a counting argument that proves it cannot be completed.
+/// Returns `Ok(())` if none applies, which does not mean that the board can
+/// be completed.
+pub fn check_feasibility(board: &Board) -> Result<(), Infeasible> {
+    let width = board.width();
+    let started = board.is_started();
+    let current = board.current_position();
+    let open = |(x, y): (usize, usize)| {
+        (board.value_at(x, y) == 0 && !board.blocked_at(x, y)) || (started && (x, y) == current)
+    };
+    let neighbors = |(x, y): (usize, usize)| {
+        Direction::iterator()
+            .filter_map(move |&dir| board.target(x, y, dir))
+            .filter(move |&p| open(p))
+    };
+    let cells: Vec<(usize, usize)> = (0..width * board.height())
+        .map(|i| (i % width, i / width))
+        .filter(|&p| open(p))
+        .collect();
+    let root = match (started, cells.first()) {
+        (true, _) => current,
+        (false, Some(&p)) => p,
+        (false, None) => return Ok(()),
+    };
+
+    // Two-color the graph from the root, noting whether it is bipartite.
+    let mut color: Vec<Option<bool>> = vec![None; width * board.height()];
+    let mut stack = vec![root];
+    let mut bipartite = true;
+    color[root.1 * width + root.0] = Some(true);
+    while let Some(p) = stack.pop() {
+        let c = color[p.1 * width + p.0].unwrap();
+        for (x, y) in neighbors(p) {
+            match color[y * width + x] {
+                None => {
+                    color[y * width + x] = Some(!c);
+                    stack.push((x, y));
+                }
+                Some(other) if other == c => bipartite = false,
+                Some(_) => {}
+            }
+        }
+    }
+    if let Some(&(x, y)) = cells.iter().find(|&&(x, y)| color[y * width + x].is_none()) {
+        return Err(Infeasible::Disconnected { x, y });
+    }
+
+    let dead_ends = cells
+        .iter()
+        .filter(|&&p| !(started && p == current) && neighbors(p).count() <= 1)
+        .count();
+    let allowed = if started { 1 } else { 2 };
+    if dead_ends > allowed && cells.len() > 1 {
+        return Err(Infeasible::DeadEnds { count: dead_ends });
+    }
+
+    if bipartite {
+        let same = cells
+            .iter()
+            .filter(|&&(x, y)| color[y * width + x] == Some(true))
+            .count();
+        let other = cells.len() - same;
+        let fits = if started {
+            same == other || same == other + 1
+        } else {
+            same.max(other) - same.min(other) <= 1
+        };
+        if !fits {
+            return Err(Infeasible::Parity {
+                current: same,
+                other,
+            });
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solver::{count_solutions, SolverConfig};
+    use Direction::*;
+
+    /// Return the board reached by playing `moves` on a 5x5 board from
+    /// (x, y).
+    fn play(x: usize, y: usize, moves: &[Direction]) -> Board {
+        super::super::replay(&Board::new(5).start_at(x, y).unwrap(), moves)
+    }
+
+    #[test]
+    // Boards that can be completed pass every check.
+    fn feasible() {
+        assert_eq!(check_feasibility(&Board::new(5)), Ok(()));
+        assert_eq!(check_feasibility(&play(0, 0, &[])), Ok(()));
+        assert_eq!(check_feasibility(&play(0, 0, &[Right, Down])), Ok(()));
+    }
+
+    #[test]
+    // Each argument catches a board with no solution, which an exhaustive
+    // count confirms.
+    fn infeasible() {
+        let dead_ends = play(
+            1,
+            1,
+            &[
+                DownRight, Left, Up, Right, DownLeft, Right, DownLeft, UpLeft, Right, DownLeft,
+            ],
+        );
+        let parity = play(
+            2,
+            1,
+            &[
+                DownLeft, Up, Right, Down, UpLeft, Right, DownLeft, UpLeft, Right, Down, Left,
+                UpRight,
+            ],
+        );
+        assert_eq!(
+            check_feasibility(&dead_ends),
+            Err(Infeasible::DeadEnds { count: 2 })
+        );
+        assert_eq!(
+            check_feasibility(&parity),
+            Err(Infeasible::Parity {
+                current: 6,
+                other: 7
+            })
+        );
+        let unpruned = SolverConfig {
+            prunes: vec![],
+            ..SolverConfig::default()
+        };
+        for board in &[dead_ends, parity] {
+            assert_eq!(count_solutions(board), 0);
+            assert_eq!(
+                crate::solver::count_solutions_with_config(board, &unpruned),
+                0
+            );
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//! Depth-first search over boards whose size is fixed at compile time.
+
+use crate::board::Direction;
+use crate::fixed::FixedBoard;
+use crate::plain::PlainBoard;
+
+/// Search for a sequence of moves that completes `board`, like
+/// [`solve`](super::solve), but on a [`FixedBoard`], so that no board the
+/// search visits is allocated. Moves are tried in the order of
+/// `Direction::iterator()`, and a branch is abandoned as soon as an empty
+/// cell next to the cell just left can no longer be entered, which is the
+/// check made by `Prune::ForwardCheck`. An unstarted board has no solution.
+pub fn solve_fixed<const N: usize>(board: &FixedBoard<N>) -> Option<Vec<Direction>> {
+    if !board.is_started() {
+        return None;
+    }
+    let mut path = Vec::with_capacity(N * N);
+    if search(board, &mut path) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Extend `path` with moves that complete `board`, returning `false` and
+/// leaving `path` as it was if there are none.
+fn search<const N: usize>(board: &FixedBoard<N>, path: &mut Vec<Direction>) -> bool {
+    if board.is_won() {
+        return true;
+    }
+    let from = board.current_position();
+    for dir in board.possible_moves() {
+        let next = match board.next_move(dir) {
+            Ok(next) => next,
+            Err(_) => continue,
+        };
+        if !forward_check(from, &next) {
+            continue;
+        }
+        path.push(dir);
+        if search(&next, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Check that every empty cell one jump from `from` is next to the current
+/// position of `board` or still has an empty cell one jump away.
+pub(super) fn forward_check<B: PlainBoard>(from: (usize, usize), board: &B) -> bool {
+    let to = board.current_position();
+    Direction::iterator()
+        .filter_map(|&dir| board.target(from.0, from.1, dir))
+        .filter(|&(x, y)| board.value_at(x, y) == 0 && board.free_neighbors(x, y) == 0)
+        .all(|(x, y)| Direction::iterator().any(|&dir| board.target(x, y, dir) == Some(to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::solve;
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    // The fixed search finds the same first solution as the board search,
+    // since both try moves in the same order and prune only dead branches.
+    fn solve_fixed_matches_solve() {
+        let fixed = FixedBoard::<6>::new().start_at(0, 0).unwrap();
+        let board = Board::new(6).start_at(0, 0).unwrap();
+        let moves = solve_fixed(&fixed).unwrap();
+        assert_eq!(Some(moves.clone()), solve(&board));
+        let won = moves.iter().fold(fixed, |b, &d| b.next_move(d).unwrap());
+        assert!(won.is_won());
+        assert_eq!(solve_fixed(&FixedBoard::<6>::new()), None);
+    }
+}
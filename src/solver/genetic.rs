@@ -0,0 +1,201 @@
+//! Genetic-algorithm solver.
+//!
+//! Each individual is a path of moves from the starting board, extended with
+//! random moves until it is blocked, and its fitness is the score of the board
+//! it reaches. Crossover splices two parents at a step where both stand on the
+//! same cell: the child keeps the first parent's prefix and continues with the
+//! second parent's suffix for as long as those moves remain valid. Mutation
+//! throws away a random suffix of the child and regrows it at random.
+
+use super::{replay, Budget, PartialSolution};
+use crate::board::{Board, Direction};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+#[derive(Debug, Clone)]
+/// Options controlling [`genetic`].
+pub struct GeneticConfig {
+    /// Seed for the random number generator, so runs can be reproduced.
+    pub seed: u64,
+    /// The number of individuals in each generation.
+    pub population: usize,
+    /// The probability that a child has its suffix mutated.
+    pub mutation_rate: f64,
+    /// The number of generations to breed before giving up.
+    pub generations: usize,
+}
+
+impl Default for GeneticConfig {
+    fn default() -> Self {
+        GeneticConfig {
+            seed: 0,
+            population: 100,
+            mutation_rate: 0.2,
+            generations: 200,
+        }
+    }
+}
+
+/// Evolve a population of paths from `board` for up to `config.generations`
+/// generations and return the highest-scoring board seen. Stops early if an
+/// individual wins the board.
+pub fn genetic(board: &Board, config: &GeneticConfig) -> PartialSolution {
+    genetic_within(board, config, &Budget::unlimited())
+}
+
+/// Like [`genetic`], but stops once `budget` runs out.
+pub(super) fn genetic_within(
+    board: &Board,
+    config: &GeneticConfig,
+    budget: &Budget,
+) -> PartialSolution {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let size = config.population.max(2);
+    let mut population: Vec<PartialSolution> = (0..size)
+        .map(|_| extend(board, Vec::new(), &mut rng, budget))
+        .collect();
+    let mut best = fittest(&population).clone();
+
+    for _ in 0..config.generations {
+        if best.board.is_won() || budget.exceeded() {
+            break;
+        }
+        // Elitism: the best individual always survives to the next generation.
+        let mut next = vec![best.clone()];
+        while next.len() < size {
+            let a = tournament(&population, &mut rng);
+            let b = tournament(&population, &mut rng);
+            let mut moves = crossover(a, b, board.score(), &mut rng);
+            if rng.gen::<f64>() < config.mutation_rate {
+                let cut = rng.gen_range(0..=moves.len());
+                moves.truncate(cut);
+            }
+            next.push(extend(board, moves, &mut rng, budget));
+        }
+        population = next;
+        let fit = fittest(&population);
+        if fit.board.score() > best.board.score() {
+            best = fit.clone();
+        }
+    }
+    best
+}
+
+/// Return the individual with the highest score.
+fn fittest(population: &[PartialSolution]) -> &PartialSolution {
+    population
+        .iter()
+        .max_by_key(|p| p.board.score())
+        .unwrap_or(&population[0])
+}
+
+/// Pick the fitter of two individuals chosen at random.
+fn tournament<'a>(population: &'a [PartialSolution], rng: &mut StdRng) -> &'a PartialSolution {
+    let a = &population[rng.gen_range(0..population.len())];
+    let b = &population[rng.gen_range(0..population.len())];
+    if a.board.score() >= b.board.score() {
+        a
+    } else {
+        b
+    }
+}
+
+/// Splice `a` and `b` at a random step where both stand on the same cell. If
+/// they never meet after the start, the moves of the fitter parent are used.
+/// `start` is the score of the board both parents started from.
+fn crossover(
+    a: &PartialSolution,
+    b: &PartialSolution,
+    start: usize,
+    rng: &mut StdRng,
+) -> Vec<Direction> {
+    let pa = positions(&a.board);
+    let pb = positions(&b.board);
+    // After k moves a parent stands on the cell holding value start + k.
+    let common: Vec<usize> = (1..a.moves.len().min(b.moves.len()))
+        .filter(|&k| pa[start + k - 1] == pb[start + k - 1])
+        .collect();
+    match common.choose(rng) {
+        Some(&k) => {
+            let mut moves = a.moves[..k].to_vec();
+            moves.extend_from_slice(&b.moves[k..]);
+            moves
+        }
+        None if a.board.score() >= b.board.score() => a.moves.clone(),
+        None => b.moves.clone(),
+    }
+}
+
+/// Return the row-major index of each filled cell of `board`, ordered by value.
+fn positions(board: &Board) -> Vec<usize> {
+    let width = board.width();
+    let mut cells = vec![0; board.score()];
+    for i in 0..width * board.height() {
+        let value = board.value_at(i % width, i / width) as usize;
+        if value > 0 {
+            cells[value - 1] = i;
+        }
+    }
+    cells
+}
+
+/// Apply as many of `moves` to `board` as are valid, then extend the path with
+/// random moves until the board is blocked or `budget` runs out.
+fn extend(
+    board: &Board,
+    mut moves: Vec<Direction>,
+    rng: &mut StdRng,
+    budget: &Budget,
+) -> PartialSolution {
+    let start = board.score();
+    let mut board = replay(board, &moves);
+    moves.truncate(board.score() - start);
+    while let Some(&&dir) = board.possible_moves().choose(rng) {
+        if !budget.spend(&board) {
+            break;
+        }
+        match board.next_move(dir) {
+            Ok(next) => board = next,
+            Err(_) => break,
+        }
+        moves.push(dir);
+    }
+    PartialSolution { board, moves }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // The genetic algorithm completes a 5x5 board, and its moves replay to
+    // the board it returns.
+    fn genetic_5() {
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let best = genetic(&start, &GeneticConfig::default());
+        assert!(best.board.is_won());
+        assert_eq!(replay(&start, &best.moves).score(), best.board.score());
+    }
+
+    #[test]
+    // Evolution never does worse than the fittest random individual, and
+    // the same seed always produces the same result.
+    fn genetic_10() {
+        let start = Board::new(10).start_at(2, 7).unwrap();
+        let config = GeneticConfig {
+            seed: 3,
+            population: 30,
+            generations: 20,
+            ..GeneticConfig::default()
+        };
+        let first = genetic(&start, &config);
+        assert_eq!(first.moves, genetic(&start, &config).moves);
+        assert_eq!(first.board.score(), first.moves.len() + 1);
+        let none = GeneticConfig {
+            generations: 0,
+            ..config
+        };
+        assert!(first.board.score() >= genetic(&start, &none).board.score());
+    }
+}
@@ -0,0 +1,112 @@
+//! Depth-first search over boards of any geometry.
+
+use crate::geometry::{Geometry, GeometryBoard};
+
+/// Search for a sequence of moves that completes `board`, a board on any
+/// [`Geometry`], such as a [`HexBoard`](crate::hex::HexBoard) or a
+/// [`Board3D`](crate::cube::Board3D). Moves are
+/// tried in the order of `MoveOrder::Warnsdorff`, fewest onward moves first,
+/// and a branch is abandoned as soon as an empty cell next to the cell just
+/// left can no longer be entered, which is the check made by
+/// `Prune::ForwardCheck`. An unstarted board has no solution.
+pub fn solve_geometry<G: Geometry>(board: &GeometryBoard<G>) -> Option<Vec<usize>> {
+    if !board.is_started() {
+        return None;
+    }
+    let mut path = Vec::with_capacity(board.cells());
+    if search(board, &mut path) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Extend `path` with moves that complete `board`, returning `false` and
+/// leaving `path` as it was if there are none.
+fn search<G: Geometry>(board: &GeometryBoard<G>, path: &mut Vec<usize>) -> bool {
+    if board.is_won() {
+        return true;
+    }
+    let from = board.current().expect("a started board has a position");
+    let mut moves: Vec<(usize, GeometryBoard<G>)> = board
+        .possible_moves()
+        .filter_map(|dir| board.next_move(dir).ok().map(|next| (dir, next)))
+        .collect();
+    moves.sort_by_key(|(_, next)| next.free_neighbors(next.current().unwrap()));
+    for (dir, next) in moves {
+        if !forward_check(from, &next) {
+            continue;
+        }
+        path.push(dir);
+        if search(&next, path) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Check that every empty cell one move from `from` is next to the current
+/// position of `board` or still has an empty cell one move away.
+fn forward_check<G: Geometry>(from: usize, board: &GeometryBoard<G>) -> bool {
+    let geometry = board.geometry();
+    let to = board.current().unwrap();
+    (0..geometry.directions())
+        .filter_map(|dir| geometry.target(from, dir))
+        .filter(|&cell| board.value_at(cell) == 0 && board.free_neighbors(cell) == 0)
+        .all(|cell| (0..geometry.directions()).any(|dir| geometry.target(cell, dir) == Some(to)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::{Board, Direction, MoveRule};
+    use crate::cube::{Board3D, Cube};
+    use crate::hex::{Hex, HexBoard, HexRule};
+
+    #[test]
+    // The search completes a square board through its geometry, and a
+    // hexagonal board of each size it is given unless its moves stay on one
+    // colour of the hexagon.
+    fn solve_geometry_boards() {
+        let board = Board::new(6);
+        let start = GeometryBoard::new(board.clone()).start_at(0).unwrap();
+        let moves = solve_geometry(&start).unwrap();
+        let mut played = board.start_at(0, 0).unwrap();
+        for &dir in &moves {
+            played = played
+                .next_move(Direction::iterator().as_slice()[dir])
+                .unwrap();
+        }
+        assert!(played.is_won());
+        for size in 4..=6 {
+            let start = HexBoard::new(Hex::new(size).unwrap()).start_at(0).unwrap();
+            let moves = solve_geometry(&start).unwrap();
+            let won = moves.iter().fold(start, |b, &d| b.next_move(d).unwrap());
+            assert!(won.is_won());
+        }
+        assert_eq!(solve_geometry(&HexBoard::new(Hex::new(4).unwrap())), None);
+        let rule = HexRule {
+            straight: 3,
+            diagonal: 2,
+        };
+        let start = HexBoard::new(Hex::with_rule(4, rule).unwrap())
+            .start_at(0)
+            .unwrap();
+        assert_eq!(solve_geometry(&start), None);
+    }
+
+    #[test]
+    // The same search completes a 5x5x5 cube, under jumps and as a
+    // knight's tour.
+    fn solve_cube() {
+        for rule in [MoveRule::default(), MoveRule::Knight] {
+            let cube = Cube::with_rule(5, 5, 5, rule).unwrap();
+            let start = Board3D::new(cube).start_at(0).unwrap();
+            let moves = solve_geometry(&start).unwrap();
+            let won = moves.iter().fold(start, |b, &d| b.next_move(d).unwrap());
+            assert!(won.is_won());
+            assert_eq!(won.score(), 125);
+        }
+    }
+}
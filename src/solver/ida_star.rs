@@ -0,0 +1,84 @@
+//! IDA* search for the longest path.
+//!
+//! The cost of a path is the number of cells it leaves empty once it is
+//! blocked. The cells that a board's [`upper_bound`] says can never be filled
+//! are a lower bound on the cost of any path through it, since the bound only
+//! counts cells that can still be reached. Each iteration searches
+//! depth-first for a path whose cost is within a threshold, abandoning any
+//! board whose lower bound exceeds it, and the threshold is raised by one
+//! after each failed iteration. The first path found is therefore as long as
+//! possible, and the search needs no more memory than the depth-first search
+//! of one iteration.
+
+use super::{candidates, upper_bound, PartialSolution, SolverConfig};
+use crate::board::{Board, Direction};
+
+/// Return the highest-scoring board that can be reached from `board`, and the
+/// moves leading to it, using IDA*. A complete board is found by the first
+/// iteration, if there is one. Only the `order` option of `config` is used.
+pub fn ida_star(board: &Board, config: &SolverConfig) -> PartialSolution {
+    let mut threshold = 0;
+    loop {
+        let mut path = Vec::new();
+        if let Some(board) = bounded(board, config, threshold, &mut path) {
+            return PartialSolution { board, moves: path };
+        }
+        threshold += 1;
+    }
+}
+
+/// Search depth-first below `board`, reached by `path`, for a blocked board
+/// that leaves at most `threshold` cells empty. Returns that board, leaving
+/// the moves to it in `path`.
+fn bounded(
+    board: &Board,
+    config: &SolverConfig,
+    threshold: usize,
+    path: &mut Vec<Direction>,
+) -> Option<Board> {
+    if board.cells() - upper_bound(board) > threshold {
+        return None;
+    }
+    let next = candidates(board, &config.order);
+    if next.is_empty() {
+        return Some(board.clone());
+    }
+    for (dir, child) in next {
+        path.push(dir);
+        if let Some(found) = bounded(&child, config, threshold, path) {
+            return Some(found);
+        }
+        path.pop();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{max_score, replay};
+    use super::*;
+    use Direction::*;
+
+    #[test]
+    // IDA* completes a board that can be completed.
+    fn ida_star_solvable() {
+        let board = Board::new(6).start_at(0, 0).unwrap();
+        let best = ida_star(&board, &SolverConfig::default());
+        assert!(best.board.is_won());
+        assert!(replay(&board, &best.moves).is_won());
+    }
+
+    #[test]
+    // On a board that has stranded a cell, IDA* reaches the same score as the
+    // branch-and-bound search.
+    fn ida_star_unsolvable() {
+        let moves = [
+            Down, Right, UpLeft, Down, Right, UpLeft, DownLeft, Right, UpLeft, Right,
+        ];
+        let board = replay(&Board::new(5).start_at(0, 0).unwrap(), &moves);
+        let config = SolverConfig::default();
+        let best = ida_star(&board, &config);
+        assert_eq!(best.board.score(), max_score(&board, &config).board.score());
+        assert_eq!(replay(&board, &best.moves).score(), best.board.score());
+    }
+}
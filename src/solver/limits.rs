@@ -0,0 +1,272 @@
+//! Limits on how much work a solve may do.
+
+use crate::board::Board;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Default)]
+/// CancelToken lets another thread stop a solve cleanly. Clones share the
+/// same flag, so a caller keeps one clone and passes another to the solver in
+/// [`SolverLimits`]. The solver checks the flag at every node it visits.
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Return a new token that has not been cancelled.
+    pub fn new() -> Self {
+        CancelToken::default()
+    }
+
+    /// Ask every solve holding a clone of this token to stop.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Return `true` once [`cancel`](CancelToken::cancel) has been called on
+    /// any clone of this token.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl PartialEq for CancelToken {
+    /// Tokens are equal if they are clones sharing the same flag.
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// A snapshot of how a solve is going, passed to a [`ProgressHook`].
+pub struct Progress {
+    /// The number of nodes visited so far.
+    pub nodes: u64,
+    /// The time since the solve started.
+    pub elapsed: Duration,
+    /// The average number of nodes visited per second.
+    pub nodes_per_second: f64,
+    /// The score of the board at the node just visited, which is its depth
+    /// in the search.
+    pub depth: usize,
+    /// The highest score of any board visited so far.
+    pub best: usize,
+    /// The number of frames on the explicit search stack of the thread that
+    /// made the report, or 0 for strategies that do not keep one.
+    pub stack_depth: usize,
+}
+
+#[derive(Clone)]
+/// ProgressHook is called with a [`Progress`] report once every `every`
+/// nodes of a solve. When a solve is split across threads it may be called
+/// from any of them.
+pub struct ProgressHook {
+    every: u64,
+    report: Arc<dyn Fn(&Progress) + Send + Sync>,
+}
+
+impl ProgressHook {
+    /// Return a hook that calls `report` once every `every` nodes.
+    pub fn new<F>(every: u64, report: F) -> Self
+    where
+        F: Fn(&Progress) + Send + Sync + 'static,
+    {
+        ProgressHook {
+            every: every.max(1),
+            report: Arc::new(report),
+        }
+    }
+}
+
+impl fmt::Debug for ProgressHook {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ProgressHook {{ every: {} }}", self.every)
+    }
+}
+
+impl PartialEq for ProgressHook {
+    /// Hooks are equal if they are clones sharing the same callback.
+    fn eq(&self, other: &Self) -> bool {
+        self.every == other.every && Arc::ptr_eq(&self.report, &other.report)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+/// SolverLimits bounds a run of any strategy. A node is one board reached by
+/// the strategy, whether in its search tree or in a random playout.
+pub struct SolverLimits {
+    /// The maximum number of nodes to visit, or `None` for no limit.
+    pub max_nodes: Option<u64>,
+    /// The maximum time to run for, or `None` for no limit.
+    pub max_duration: Option<Duration>,
+    /// A token that stops the solve once cancelled, or `None`.
+    pub cancel: Option<CancelToken>,
+    /// A hook that is told how the solve is going, or `None`.
+    pub progress: Option<ProgressHook>,
+}
+
+/// Budget tracks the work done against a set of limits. It is shared by every
+/// thread of a solve.
+pub(super) struct Budget {
+    limits: SolverLimits,
+    start: Instant,
+    nodes: AtomicU64,
+    exceeded: AtomicBool,
+    /// The highest score seen, kept only for progress reports.
+    best: AtomicUsize,
+}
+
+impl Budget {
+    pub(super) fn new(limits: &SolverLimits) -> Self {
+        Budget {
+            limits: limits.clone(),
+            start: Instant::now(),
+            nodes: AtomicU64::new(0),
+            exceeded: AtomicBool::new(false),
+            best: AtomicUsize::new(0),
+        }
+    }
+
+    pub(super) fn unlimited() -> Self {
+        Budget::new(&SolverLimits::default())
+    }
+
+    /// Count one node, at which the strategy has reached `board`. Returns
+    /// `false` once any limit has been reached or the solve has been
+    /// cancelled, after which the caller should stop and return the best
+    /// result it has.
+    pub(super) fn spend(&self, board: &Board) -> bool {
+        self.spend_in_stack(board, 0)
+    }
+
+    /// Like [`spend`](Budget::spend), for a search that keeps an explicit
+    /// stack holding `stack_depth` frames.
+    pub(super) fn spend_in_stack(&self, board: &Board, stack_depth: usize) -> bool {
+        if self.exceeded() {
+            return false;
+        }
+        if self.cancelled() {
+            self.exceeded.store(true, Ordering::Relaxed);
+            return false;
+        }
+        let nodes = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+        if let Some(hook) = &self.limits.progress {
+            let best = self.best.fetch_max(board.score(), Ordering::Relaxed);
+            if nodes.is_multiple_of(hook.every) {
+                let elapsed = self.start.elapsed();
+                (hook.report)(&Progress {
+                    nodes,
+                    elapsed,
+                    nodes_per_second: nodes as f64 / elapsed.as_secs_f64().max(1e-9),
+                    depth: board.score(),
+                    best: best.max(board.score()),
+                    stack_depth,
+                });
+            }
+        }
+        let over_nodes = self.limits.max_nodes.is_some_and(|max| nodes > max);
+        let over_time = self
+            .limits
+            .max_duration
+            .is_some_and(|max| self.start.elapsed() >= max);
+        if over_nodes || over_time {
+            self.exceeded.store(true, Ordering::Relaxed);
+            return false;
+        }
+        true
+    }
+
+    /// Return `true` if any limit has been reached or the solve has been
+    /// cancelled.
+    pub(super) fn exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+
+    /// Return the number of nodes counted so far.
+    pub(super) fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    /// Return the time since the budget was created.
+    pub(super) fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+
+    /// Return `true` if the solve has been cancelled through its token.
+    pub(super) fn cancelled(&self) -> bool {
+        self.limits
+            .cancel
+            .as_ref()
+            .is_some_and(CancelToken::is_cancelled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // A node limit allows exactly that many nodes.
+    fn node_limit() {
+        let board = Board::new(5);
+        let budget = Budget::new(&SolverLimits {
+            max_nodes: Some(3),
+            ..SolverLimits::default()
+        });
+        assert!(budget.spend(&board) && budget.spend(&board) && budget.spend(&board));
+        assert!(!budget.exceeded());
+        assert!(!budget.spend(&board));
+        assert!(budget.exceeded());
+    }
+
+    #[test]
+    // A zero duration is exceeded by the first node.
+    fn duration_limit() {
+        let board = Board::new(5);
+        let budget = Budget::new(&SolverLimits {
+            max_duration: Some(Duration::from_secs(0)),
+            ..SolverLimits::default()
+        });
+        assert!(!budget.spend(&board));
+        assert!(Budget::unlimited().spend(&board));
+    }
+
+    #[test]
+    // Cancelling any clone of a token stops a budget holding another clone.
+    fn cancel_token() {
+        let board = Board::new(5);
+        let token = CancelToken::new();
+        let budget = Budget::new(&SolverLimits {
+            cancel: Some(token.clone()),
+            ..SolverLimits::default()
+        });
+        assert!(budget.spend(&board));
+        token.clone().cancel();
+        assert!(budget.cancelled());
+        assert!(!budget.spend(&board));
+        assert!(budget.exceeded());
+    }
+
+    #[test]
+    // A progress hook is called once every few nodes with the depth of the
+    // current node and the best score so far.
+    fn progress_hook() {
+        let reports = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let seen = reports.clone();
+        let budget = Budget::new(&SolverLimits {
+            progress: Some(ProgressHook::new(2, move |p: &Progress| {
+                seen.lock().unwrap().push((p.nodes, p.depth, p.best));
+            })),
+            ..SolverLimits::default()
+        });
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let next = board
+            .clone()
+            .next_move(crate::board::Direction::Right)
+            .unwrap();
+        for b in &[&board, &next, &next, &board, &board] {
+            assert!(budget.spend(b));
+        }
+        assert_eq!(*reports.lock().unwrap(), vec![(2, 2, 2), (4, 1, 2)]);
+    }
+}
@@ -0,0 +1,162 @@
+//! Search for the highest score a board can reach.
+//!
+//! Some boards cannot be completed from their current position. Rather than
+//! only reporting that, [`max_score`] searches for the longest path that can
+//! be played from the current position. It is a branch-and-bound search: a
+//! branch is abandoned once its [`upper_bound`] cannot beat the best path
+//! found so far.
+
+use super::{candidates, PartialSolution, SolverConfig};
+use crate::board::{Board, Direction};
+
+/// Return an upper bound on the score of any board that can be reached from
+/// `board`. Only the empty cells reachable from the current position by
+/// jumping through empty cells can ever be filled. Of those, a cell that
+/// could be entered but not left again can only be the last cell filled, so
+/// at most one such cell counts. Cells that cannot be reached are ignored,
+/// trapped or not.
+pub fn upper_bound(board: &Board) -> usize {
+    let reachable = board.reachable_cells();
+    if reachable.is_empty() {
+        return board.score();
+    }
+    let (cx, cy) = board.current_position();
+    let adjacent =
+        |x, y| Direction::iterator().any(|&dir| board.target(cx, cy, dir) == Some((x, y)));
+    let mut traps = 0;
+    for &(x, y) in &reachable {
+        let trap = match board.free_neighbors(x, y) {
+            0 => adjacent(x, y),
+            1 => !adjacent(x, y),
+            _ => false,
+        };
+        if trap {
+            traps += 1;
+        }
+    }
+    board.score() + reachable.len() - traps.max(1) + 1
+}
+
+/// Return the highest-scoring board that can be reached from `board`, and the
+/// moves leading to it. The search stops early if the board can be completed.
+/// Only the `order` option of `config` is used: the prunes reject boards that
+/// cannot be completed, which may still score highest.
+pub fn max_score(board: &Board, config: &SolverConfig) -> PartialSolution {
+    let mut best = PartialSolution {
+        board: board.clone(),
+        moves: Vec::new(),
+    };
+    let mut path = Vec::new();
+    extend(board, config, &mut path, &mut best);
+    best
+}
+
+/// Recursively try every move from `board`, reached by `path`, recording the
+/// highest-scoring board seen in `best`.
+fn extend(
+    board: &Board,
+    config: &SolverConfig,
+    path: &mut Vec<Direction>,
+    best: &mut PartialSolution,
+) {
+    let cells = board.cells();
+    if board.score() > best.board.score() {
+        *best = PartialSolution {
+            board: board.clone(),
+            moves: path.clone(),
+        };
+    }
+    for (dir, next) in candidates(board, &config.order) {
+        if best.board.score() == cells {
+            return;
+        }
+        if upper_bound(&next) <= best.board.score() {
+            continue;
+        }
+        path.push(dir);
+        extend(&next, config, path, best);
+        path.pop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::replay;
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::{Rng, SeedableRng};
+    use Direction::*;
+
+    /// Return the highest score reachable from `board` by trying every path.
+    fn brute_force(board: &Board) -> usize {
+        board
+            .possible_moves()
+            .into_iter()
+            .map(|&dir| brute_force(&board.next_move(dir).unwrap()))
+            .max()
+            .unwrap_or_else(|| board.score())
+    }
+
+    /// Play random moves from a random start on a 5x5 or 6x6 board, chosen by
+    /// `seed`, until some empty cell can no longer be reached and few enough
+    /// cells can be reached to try every path from there. Returns `None` if
+    /// the board is blocked first.
+    fn stranded(seed: u64) -> Option<Board> {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let size = rng.gen_range(5..=6);
+        let x = rng.gen_range(0..size);
+        let y = rng.gen_range(0..size);
+        let mut board = Board::new(size).start_at(x, y).unwrap();
+        loop {
+            let reachable = board.reachable();
+            if reachable < board.empty_cells().count() && reachable <= 16 {
+                return Some(board);
+            }
+            let &dir = *board.possible_moves().choose(&mut rng)?;
+            board = board.next_move(dir).unwrap();
+        }
+    }
+
+    #[test]
+    // On random boards with cells that can no longer be reached, the bound
+    // is never below the best score that can be reached, and the search
+    // finds that score.
+    fn upper_bound_holds() {
+        assert_eq!(upper_bound(&Board::new(5)), 0);
+        assert_eq!(upper_bound(&Board::new(5).start_at(0, 0).unwrap()), 25);
+        let boards: Vec<Board> = (0..300).filter_map(stranded).collect();
+        assert!(boards.len() > 100);
+        for board in boards {
+            let best = brute_force(&board);
+            assert!(upper_bound(&board) >= best, "{}", board);
+            assert!(upper_bound(&board) <= board.score() + board.reachable());
+            let found = max_score(&board, &SolverConfig::default());
+            assert_eq!(found.board.score(), best, "{}", board);
+        }
+    }
+
+    #[test]
+    // A board that can be completed scores every cell.
+    fn max_score_solvable() {
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let best = max_score(&board, &SolverConfig::default());
+        assert!(best.board.is_won());
+        assert!(replay(&board, &best.moves).is_won());
+    }
+
+    #[test]
+    // On a board that has stranded a cell, the search finds the longest path
+    // that can still be played, as trying every path does.
+    fn max_score_unsolvable() {
+        let moves = [
+            Down, Right, UpLeft, Down, Right, UpLeft, DownLeft, Right, UpLeft, Right,
+        ];
+        let board = replay(&Board::new(5).start_at(0, 0).unwrap(), &moves);
+        assert_eq!(board.score(), 11);
+        let best = max_score(&board, &SolverConfig::default());
+        assert!(!best.board.is_won());
+        assert_eq!(best.board.score(), brute_force(&board));
+        assert_eq!(replay(&board, &best.moves).score(), best.board.score());
+    }
+}
@@ -0,0 +1,199 @@
+//! Monte Carlo tree search solver.
+//!
+//! Grows a search tree from the starting board one node per iteration. Each
+//! iteration descends the tree using UCT, expands one untried move, plays
+//! random moves from the new node until the board is blocked, and propagates
+//! the score of that playout back up the tree. A node's value is the best
+//! score of any playout through it, so the search is drawn towards the
+//! branches that came closest to filling the board. Runtime is bounded by the
+//! number of iterations rather than by the size of the search space.
+
+use super::{Budget, PartialSolution};
+use crate::board::{Board, Direction};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+#[derive(Debug, Clone)]
+/// Options controlling [`mcts`].
+pub struct MctsConfig {
+    /// Seed for the random number generator, so runs can be reproduced.
+    pub seed: u64,
+    /// The number of playouts to perform before giving up.
+    pub iterations: usize,
+    /// The UCT exploration constant. Larger values favour rarely visited
+    /// branches over those with high scores.
+    pub exploration: f64,
+}
+
+impl Default for MctsConfig {
+    fn default() -> Self {
+        MctsConfig {
+            seed: 0,
+            iterations: 10_000,
+            exploration: std::f64::consts::SQRT_2,
+        }
+    }
+}
+
+/// A node of the search tree.
+struct Node {
+    board: Board,
+    /// The index of the parent node and the move that leads from it here.
+    parent: Option<(usize, Direction)>,
+    children: Vec<usize>,
+    /// Moves from this node that have not been expanded yet.
+    untried: Vec<Direction>,
+    visits: u32,
+    /// The best score of any playout through this node.
+    best: usize,
+}
+
+/// Search for a complete filling of `board` with Monte Carlo tree search and
+/// return the highest-scoring board seen. Stops early if the board is won.
+pub fn mcts(board: &Board, config: &MctsConfig) -> PartialSolution {
+    mcts_within(board, config, &Budget::unlimited())
+}
+
+/// Like [`mcts`], but stops once `budget` runs out.
+pub(super) fn mcts_within(board: &Board, config: &MctsConfig, budget: &Budget) -> PartialSolution {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let cells = board.cells() as f64;
+    let mut nodes = vec![new_node(board.clone(), None, &mut rng)];
+    let mut best = PartialSolution {
+        board: board.clone(),
+        moves: Vec::new(),
+    };
+
+    for _ in 0..config.iterations {
+        if best.board.is_won() || budget.exceeded() {
+            break;
+        }
+
+        // Selection: descend through fully expanded nodes.
+        let mut node = 0;
+        while nodes[node].untried.is_empty() && !nodes[node].children.is_empty() {
+            let parent = &nodes[node];
+            let log_visits = f64::from(parent.visits).ln();
+            node = *parent
+                .children
+                .iter()
+                .max_by(|&&a, &&b| {
+                    let ua = uct(&nodes[a], log_visits, cells, config.exploration);
+                    let ub = uct(&nodes[b], log_visits, cells, config.exploration);
+                    ua.partial_cmp(&ub).unwrap_or(std::cmp::Ordering::Equal)
+                })
+                .unwrap_or(&node);
+        }
+
+        // Expansion: add a child for one untried move.
+        if let Some(dir) = nodes[node].untried.pop() {
+            if let Ok(next) = nodes[node].board.next_move(dir) {
+                let child = nodes.len();
+                nodes.push(new_node(next, Some((node, dir)), &mut rng));
+                nodes[node].children.push(child);
+                node = child;
+            }
+        }
+
+        // Playout: random moves until blocked.
+        let mut playout = nodes[node].board.clone();
+        let mut tail = Vec::new();
+        while let Some(&&dir) = playout.possible_moves().choose(&mut rng) {
+            if !budget.spend(&playout) {
+                break;
+            }
+            match playout.next_move(dir) {
+                Ok(next) => playout = next,
+                Err(_) => break,
+            }
+            tail.push(dir);
+        }
+        let score = playout.score();
+        if score > best.board.score() {
+            let mut moves = path_to(&nodes, node);
+            moves.extend(tail);
+            best = PartialSolution {
+                board: playout,
+                moves,
+            };
+        }
+
+        // Backpropagation: record the visit and best score up to the root.
+        let mut current = Some(node);
+        while let Some(i) = current {
+            nodes[i].visits += 1;
+            nodes[i].best = nodes[i].best.max(score);
+            current = nodes[i].parent.map(|(p, _)| p);
+        }
+    }
+    best
+}
+
+/// Create an unvisited node for `board`, with its possible moves shuffled so
+/// that expansion tries them in random order.
+fn new_node(board: Board, parent: Option<(usize, Direction)>, rng: &mut StdRng) -> Node {
+    let mut untried: Vec<Direction> = board.iter_moves().collect();
+    untried.shuffle(rng);
+    Node {
+        board,
+        parent,
+        children: Vec::new(),
+        untried,
+        visits: 0,
+        best: 0,
+    }
+}
+
+/// The UCT value of `node`, whose parent has been visited `exp(log_visits)`
+/// times. Scores are normalised by the number of cells on the board.
+fn uct(node: &Node, log_visits: f64, cells: f64, exploration: f64) -> f64 {
+    if node.visits == 0 {
+        return f64::INFINITY;
+    }
+    let visits = f64::from(node.visits);
+    node.best as f64 / cells + exploration * (log_visits / visits).sqrt()
+}
+
+/// Return the moves leading from the root of the tree to `node`.
+fn path_to(nodes: &[Node], node: usize) -> Vec<Direction> {
+    let mut moves = Vec::new();
+    let mut current = node;
+    while let Some((parent, dir)) = nodes[current].parent {
+        moves.push(dir);
+        current = parent;
+    }
+    moves.reverse();
+    moves
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::replay;
+    use super::*;
+
+    #[test]
+    // MCTS completes a 5x5 board, and its moves replay to the board it
+    // returns.
+    fn mcts_5() {
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let best = mcts(&start, &MctsConfig::default());
+        assert!(best.board.is_won());
+        assert_eq!(replay(&start, &best.moves).score(), best.board.score());
+    }
+
+    #[test]
+    // A bounded run on a large board returns a consistent partial solution,
+    // and the same seed always produces the same result.
+    fn mcts_12() {
+        let start = Board::new(12).start_at(6, 6).unwrap();
+        let config = MctsConfig {
+            seed: 11,
+            iterations: 300,
+            ..MctsConfig::default()
+        };
+        let best = mcts(&start, &config);
+        assert_eq!(best.board.score(), best.moves.len() + 1);
+        assert_eq!(best.moves, mcts(&start, &config).moves);
+    }
+}
@@ -0,0 +1,1205 @@
+//! Brute-force solver for the puzzle.
+//!
+//! The solver performs a depth-first search over the possible moves from the
+//! current position of a board, backtracking whenever a branch is blocked.
+//! The order in which moves are tried, whether the top of the search tree is
+//! split across threads, and which [`Prune`] checks cut branches short are
+//! controlled by [`SolverConfig`].
+//!
+//! Heuristic strategies such as [`random_restart`], [`anneal`], [`genetic`],
+//! [`mcts`] and [`beam`] are not guaranteed to complete a board; they return
+//! the best [`PartialSolution`] they find instead. For boards that cannot be
+//! completed, [`max_score`] and [`ida_star`] find the highest score that can
+//! still be reached.
+//!
+//! Any [`Strategy`] can be run within [`SolverLimits`] by [`solve_within`],
+//! which reports how the run ended as an [`Outcome`]. A [`CancelToken`] in
+//! the limits lets another thread stop the run early, and a [`ProgressHook`]
+//! reports how the run is going. Boards that [`check_feasibility`] can prove
+//! unsolvable are reported without searching. A [`Solver`] runs the
+//! exhaustive search in steps that can be checkpointed to disk and resumed,
+//! and [`OpeningBook::solve`] skips the first moves from known starts by
+//! playing a stored opening before searching.
+//! [`solve_fixed`] runs a lean search on a `FixedBoard`, whose size is
+//! known at compile time, [`solve_small`] on a `SmallBoard` of up to 11x11,
+//! remembering dead ends by their filled cells, and [`solve_geometry`] on a
+//! board of any shape, such as a hexagon or a cube.
+//! [`solve_exact_cover`] and [`count_exact_cover`] solve the same problem
+//! with Dancing Links instead, as an independent check on the search, and
+//! with the `z3` feature enabled `solve_smt` and `count_smt` hand it to
+//! the Z3 SMT solver.
+
+use crate::board::{Board, Direction};
+use crate::symmetry::Symmetry;
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+
+mod anneal;
+mod batch;
+mod beam;
+mod bidirectional;
+mod book;
+mod checkpoint;
+mod estimate;
+mod exact_cover;
+mod feasibility;
+mod fixed;
+mod genetic;
+mod geometry;
+mod ida_star;
+mod limits;
+mod max_score;
+mod mcts;
+mod ordering;
+mod prune;
+mod random;
+mod small;
+#[cfg(feature = "z3")]
+mod smt;
+mod solutions;
+mod stack;
+mod tablebase;
+mod transposition;
+
+pub use self::anneal::{anneal, AnnealConfig, CoolingSchedule};
+pub use self::batch::{solve_batch, BatchConfig, BatchSummary, SolveResult};
+pub use self::beam::{beam, BeamConfig};
+pub use self::bidirectional::solve_bidirectional;
+pub use self::book::OpeningBook;
+pub use self::checkpoint::Solver;
+pub use self::estimate::{estimate_tree_size, estimate_tree_size_with_config};
+pub use self::exact_cover::{count_exact_cover, solve_exact_cover};
+pub use self::feasibility::{check_feasibility, Infeasible};
+pub use self::fixed::solve_fixed;
+pub use self::genetic::{genetic, GeneticConfig};
+pub use self::geometry::solve_geometry;
+pub use self::ida_star::ida_star;
+use self::limits::Budget;
+pub use self::limits::{CancelToken, Progress, ProgressHook, SolverLimits};
+pub use self::max_score::{max_score, upper_bound};
+pub use self::mcts::{mcts, MctsConfig};
+pub use self::ordering::{MoveOrder, MoveOrdering};
+pub use self::prune::Prune;
+pub use self::random::{random_restart, RandomRestartConfig};
+pub use self::small::solve_small;
+#[cfg(feature = "z3")]
+pub use self::smt::{count_smt, solve_smt};
+pub use self::solutions::{solutions, Solutions};
+use self::stack::{is_dead, Stack};
+pub use self::tablebase::Tablebase;
+use self::transposition::TranspositionTable;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Parallelism selects whether a single solve is spread across threads.
+pub enum Parallelism {
+    /// Search on the calling thread only.
+    Sequential,
+    /// Split the first `depth` plies of the search tree into tasks that are
+    /// scheduled on the rayon thread pool. The first task to find a solution
+    /// stops the others, so the solution returned may differ between runs.
+    Split { depth: usize },
+}
+
+#[derive(Debug, Clone)]
+/// Options controlling how the solver searches.
+pub struct SolverConfig {
+    /// The order in which moves are tried at each position.
+    pub order: MoveOrder,
+    /// Whether the search is split across threads.
+    pub parallelism: Parallelism,
+    /// Checks applied, in order, to every board the search reaches. A branch
+    /// is abandoned as soon as any of them fails.
+    pub prunes: Vec<Prune>,
+    /// The maximum number of dead board states remembered so that they are
+    /// not searched again when reached by another move order, or `None` to
+    /// remember none.
+    pub transposition: Option<usize>,
+    /// Whether the transposition table treats board states that are
+    /// rotations or reflections of each other as the same state. This costs
+    /// more per lookup but lets one dead state rule out all of its images.
+    /// It is ignored when `closed` is set.
+    pub symmetry: bool,
+    /// Whether only closed tours count as solutions: the last cell filled
+    /// must be one jump away from the first, as checked by
+    /// `Board::is_closed_tour()`. The heuristic strategies ignore this.
+    pub closed: bool,
+    /// A table of endgames consulted once few enough cells are left, so
+    /// that a board the table shows cannot be completed is abandoned without
+    /// searching it.
+    pub tablebase: Option<Arc<Tablebase>>,
+}
+
+impl SolverConfig {
+    /// Return `true` if `board` is a solution under these options.
+    fn is_goal(&self, board: &Board) -> bool {
+        if self.closed {
+            board.is_closed_tour()
+        } else {
+            board.is_won()
+        }
+    }
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig {
+            order: MoveOrder::Natural,
+            parallelism: Parallelism::Sequential,
+            prunes: vec![Prune::ForwardCheck, Prune::DeadEnd, Prune::Connectivity],
+            transposition: None,
+            symmetry: false,
+            closed: false,
+            tablebase: None,
+        }
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+/// The best board found by a strategy that does not necessarily complete the
+/// board, together with the moves that lead to it from the starting board.
+pub struct PartialSolution {
+    /// The board after applying `moves`.
+    pub board: Board,
+    /// The moves applied to the starting board, in order.
+    pub moves: Vec<Direction>,
+}
+
+#[derive(Debug, Clone)]
+/// Strategy selects the algorithm used by [`solve_within`], together with
+/// its options.
+pub enum Strategy {
+    /// Exhaustive depth-first search, as done by [`solve_with_config`].
+    Search(SolverConfig),
+    /// Iterative-deepening search, as done by [`solve_iterative_deepening`].
+    IterativeDeepening(SolverConfig),
+    /// Search from both ends of the path, as done by [`solve_bidirectional`].
+    Bidirectional(SolverConfig),
+    /// Random playouts, as done by [`random_restart`].
+    RandomRestart(RandomRestartConfig),
+    /// Simulated annealing, as done by [`anneal`].
+    Anneal(AnnealConfig),
+    /// A genetic algorithm, as done by [`genetic`].
+    Genetic(GeneticConfig),
+    /// Monte Carlo tree search, as done by [`mcts`].
+    Mcts(MctsConfig),
+    /// Beam search, as done by [`beam`].
+    Beam(BeamConfig),
+}
+
+impl Default for Strategy {
+    fn default() -> Self {
+        Strategy::Search(SolverConfig::default())
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone)]
+/// Outcome describes how a run of [`solve_within`] ended.
+pub enum Outcome {
+    /// The board was completed by these moves.
+    Solved(Vec<Direction>),
+    /// An exhaustive strategy searched every move without completing the
+    /// board, proving that it has no solution.
+    Exhausted,
+    /// A limit was reached, or a heuristic strategy used up its own
+    /// iterations, before the board was completed. `best` is the
+    /// highest-scoring board reached.
+    BudgetExceeded { best: PartialSolution },
+    /// The run was stopped by its [`CancelToken`] before the board was
+    /// completed. `best` is the highest-scoring board reached.
+    Cancelled { best: PartialSolution },
+    /// [`check_feasibility`] proved that the board has no solution, so no
+    /// search was run.
+    Infeasible(Infeasible),
+}
+
+impl Outcome {
+    /// Return the outcome of a heuristic strategy that stopped at `best`.
+    fn from_best(best: PartialSolution, budget: &Budget) -> Outcome {
+        if best.board.is_won() {
+            Outcome::Solved(best.moves)
+        } else {
+            Outcome::stopped(best, budget)
+        }
+    }
+
+    /// Return the outcome of a run that stopped at `best` without completing
+    /// the board, either because it was cancelled or for any other reason.
+    fn stopped(best: PartialSolution, budget: &Budget) -> Outcome {
+        if budget.cancelled() {
+            Outcome::Cancelled { best }
+        } else {
+            Outcome::BudgetExceeded { best }
+        }
+    }
+
+    /// Return the winning moves, if the board was solved.
+    pub fn solution(self) -> Option<Vec<Direction>> {
+        match self {
+            Outcome::Solved(moves) => Some(moves),
+            _ => None,
+        }
+    }
+}
+
+/// Run `strategy` on `board`, stopping once any of `limits` is reached or its
+/// cancel token is cancelled. Boards that [`check_feasibility`] proves have
+/// no solution are not searched.
+pub fn solve_within(board: &Board, strategy: &Strategy, limits: &SolverLimits) -> Outcome {
+    run_strategy(board, strategy, &Budget::new(limits))
+}
+
+/// Run `strategy` on `board` within `budget`.
+fn run_strategy(board: &Board, strategy: &Strategy, budget: &Budget) -> Outcome {
+    if let Err(reason) = check_feasibility(board) {
+        return Outcome::Infeasible(reason);
+    }
+    match strategy {
+        Strategy::Search(config) => run(board, &Context::new(board, config, budget)),
+        Strategy::IterativeDeepening(config) => deepen(board, config, budget, |_, _| {}),
+        Strategy::Bidirectional(config) => {
+            bidirectional::bidirectional_within(board, config, budget)
+        }
+        Strategy::RandomRestart(config) => {
+            Outcome::from_best(random::random_restart_within(board, config, budget), budget)
+        }
+        Strategy::Anneal(config) => {
+            Outcome::from_best(anneal::anneal_within(board, config, budget), budget)
+        }
+        Strategy::Genetic(config) => {
+            Outcome::from_best(genetic::genetic_within(board, config, budget), budget)
+        }
+        Strategy::Mcts(config) => {
+            Outcome::from_best(mcts::mcts_within(board, config, budget), budget)
+        }
+        Strategy::Beam(config) => {
+            Outcome::from_best(beam::beam_within(board, config, budget), budget)
+        }
+    }
+}
+
+/// A callback shared by the threads of a single solve.
+type Callback<'a> = Mutex<dyn FnMut(&Board) + Send + 'a>;
+
+/// State shared by every branch of a single solve.
+struct Context<'a> {
+    config: &'a SolverConfig,
+    /// Set once any branch has found a solution, so that branches running on
+    /// other threads can give up.
+    stop: AtomicBool,
+    /// Board states proven dead, shared by every thread.
+    table: Option<Mutex<TranspositionTable>>,
+    /// The limits on the solve.
+    budget: &'a Budget,
+    /// The highest score reached so far.
+    best: AtomicUsize,
+    /// The highest-scoring board reached so far, and the moves leading to it.
+    best_partial: Mutex<PartialSolution>,
+    /// Called with each board that beats the highest score reached so far.
+    on_best: Option<&'a Callback<'a>>,
+}
+
+impl<'a> Context<'a> {
+    fn new(board: &Board, config: &'a SolverConfig, budget: &'a Budget) -> Self {
+        Context {
+            config,
+            stop: AtomicBool::new(false),
+            table: config
+                .transposition
+                .map(|n| Mutex::new(TranspositionTable::new(n))),
+            budget,
+            best: AtomicUsize::new(board.score()),
+            best_partial: Mutex::new(PartialSolution {
+                board: board.clone(),
+                moves: Vec::new(),
+            }),
+            on_best: None,
+        }
+    }
+
+    /// Record `board`, reached by `path`, and report it to the `on_best`
+    /// callback if it beats every board reached so far.
+    fn improve(&self, board: &Board, path: &[Direction]) {
+        let score = board.score();
+        if score > self.best.fetch_max(score, Ordering::Relaxed) {
+            let mut best = self.best_partial.lock().unwrap();
+            if score > best.board.score() {
+                *best = PartialSolution {
+                    board: board.clone(),
+                    moves: path.to_vec(),
+                };
+            }
+            if let Some(on_best) = self.on_best {
+                (on_best.lock().unwrap())(board);
+            }
+        }
+    }
+}
+
+/// Search for a sequence of moves that completes `board`, starting from its
+/// current position. Returns `Some(moves)` with the winning moves in order if
+/// a solution exists; otherwise `None`. An unstarted board has no solution.
+pub fn solve(board: &Board) -> Option<Vec<Direction>> {
+    solve_with_config(board, &SolverConfig::default())
+}
+
+/// Like [`solve`], but searches using the options in `config`.
+pub fn solve_with_config(board: &Board, config: &SolverConfig) -> Option<Vec<Direction>> {
+    let budget = Budget::unlimited();
+    run(board, &Context::new(board, config, &budget)).solution()
+}
+
+/// Like [`solve_with_config`], but calls `on_best` with each board the search
+/// reaches that scores higher than any board reached before it. This lets a
+/// long-running solve report progress, and the caller keep the best board
+/// seen in case the solve is abandoned. When the search is split across
+/// threads, `on_best` may be called from any of them.
+pub fn solve_with<F>(board: &Board, config: &SolverConfig, on_best: F) -> Option<Vec<Direction>>
+where
+    F: FnMut(&Board) + Send,
+{
+    let on_best = Mutex::new(on_best);
+    let budget = Budget::unlimited();
+    let mut ctx = Context::new(board, config, &budget);
+    ctx.on_best = Some(&on_best);
+    run(board, &ctx).solution()
+}
+
+/// Solve `board` with the shared state in `ctx`.
+fn run(board: &Board, ctx: &Context) -> Outcome {
+    let depth = match ctx.config.parallelism {
+        Parallelism::Sequential => 0,
+        Parallelism::Split { depth } => depth,
+    };
+    match split(board, ctx, depth, Vec::new()) {
+        Some(moves) => Outcome::Solved(moves),
+        None if ctx.budget.exceeded() => {
+            Outcome::stopped(ctx.best_partial.lock().unwrap().clone(), ctx.budget)
+        }
+        None => Outcome::Exhausted,
+    }
+}
+
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Debug, Clone, PartialEq)]
+/// The outcome of solving a board from a single starting square.
+pub struct StartResult {
+    /// The x location of the starting square.
+    pub x: usize,
+    /// The y location of the starting square.
+    pub y: usize,
+    /// The winning moves from the starting square, or `None` if the board
+    /// cannot be completed from there.
+    pub solution: Option<Vec<Direction>>,
+}
+
+/// Solve a board of dimensions `size` x `size` from every starting square.
+/// Only one starting square from each set of squares that are rotations or
+/// reflections of each other is solved, and its solution is mapped onto the
+/// others. These squares are solved in parallel on the rayon thread pool, and
+/// the results are returned in row-major order.
+pub fn solve_all_starts(size: usize) -> Vec<StartResult> {
+    solve_all_starts_with_config(size, &SolverConfig::default())
+}
+
+/// Like [`solve_all_starts`], but searches using the options in `config`.
+pub fn solve_all_starts_with_config(size: usize, config: &SolverConfig) -> Vec<StartResult> {
+    let board = Board::new(size);
+    let size = board.size();
+    let canonical: Vec<((usize, usize), Symmetry)> = (0..size * size)
+        .map(|i| Symmetry::canonical(size, (i % size, i / size)))
+        .collect();
+    let mut distinct: Vec<(usize, usize)> = canonical.iter().map(|&(c, _)| c).collect();
+    distinct.sort_by_key(|&(x, y)| (y, x));
+    distinct.dedup();
+    let solved: HashMap<(usize, usize), Option<Vec<Direction>>> = distinct
+        .into_par_iter()
+        .map(|(x, y)| {
+            let solution = board
+                .clone()
+                .start_at(x, y)
+                .ok()
+                .and_then(|start| solve_with_config(&start, config));
+            ((x, y), solution)
+        })
+        .collect();
+    canonical
+        .iter()
+        .enumerate()
+        .map(|(i, (c, symmetry))| {
+            let back = symmetry.inverse();
+            let solution = solved[c]
+                .as_ref()
+                .map(|moves| moves.iter().map(|&d| back.apply_direction(d)).collect());
+            StartResult {
+                x: i % size,
+                y: i / size,
+                solution,
+            }
+        })
+        .collect()
+}
+
+/// Count every sequence of moves that completes `board`, starting from its
+/// current position, rather than stopping at the first. The moves from
+/// `board` are counted in parallel on the rayon thread pool. An unstarted
+/// board has no solutions.
+pub fn count_solutions(board: &Board) -> u64 {
+    count_solutions_with_config(board, &SolverConfig::default())
+}
+
+/// Like [`count_solutions`], but searches using the options in `config`. Only
+/// the `order`, `prunes`, `closed` and `tablebase` options are used.
+pub fn count_solutions_with_config(board: &Board, config: &SolverConfig) -> u64 {
+    if config.is_goal(board) {
+        return 1;
+    }
+    children(board, config)
+        .into_par_iter()
+        .map(|(_, next)| count(&next, config))
+        .sum()
+}
+
+/// Recursively count the completions of `board`.
+fn count(board: &Board, config: &SolverConfig) -> u64 {
+    if config.is_goal(board) {
+        return 1;
+    }
+    children(board, config)
+        .into_iter()
+        .map(|(_, next)| count(&next, config))
+        .sum()
+}
+
+/// Search for a sequence of moves that completes `board` using iterative
+/// deepening: the search tree is explored to a depth of one move, then two,
+/// and so on. After each depth, `report` is called with that depth and the
+/// longest partial path found within it, so callers have a usable result even
+/// if they abandon the search. Returns the winning moves once a solution is
+/// found, or `None` once a depth is reached that no path can fill. The
+/// `parallelism` option of `config` is ignored.
+pub fn solve_iterative_deepening<F>(
+    board: &Board,
+    config: &SolverConfig,
+    report: F,
+) -> Option<Vec<Direction>>
+where
+    F: FnMut(usize, &[Direction]),
+{
+    deepen(board, config, &Budget::unlimited(), report).solution()
+}
+
+/// Run an iterative-deepening search of `board` within `budget`.
+fn deepen<F>(board: &Board, config: &SolverConfig, budget: &Budget, mut report: F) -> Outcome
+where
+    F: FnMut(usize, &[Direction]),
+{
+    let start = board.clone();
+    if config.is_goal(board) {
+        return Outcome::Solved(vec![]);
+    }
+    let mut depth = 1;
+    let mut deepest = Vec::new();
+    loop {
+        let mut path = Vec::new();
+        let mut best = Vec::new();
+        let won = search_limited(board, config, budget, depth, &mut path, &mut best);
+        if budget.exceeded() {
+            if best.len() > deepest.len() {
+                deepest = best;
+            }
+            let best = PartialSolution {
+                board: replay(&start, &deepest),
+                moves: deepest,
+            };
+            return Outcome::stopped(best, budget);
+        }
+        report(depth, &best);
+        if won {
+            return Outcome::Solved(best);
+        }
+        if best.len() < depth {
+            return Outcome::Exhausted;
+        }
+        deepest = best;
+        depth += 1;
+    }
+}
+
+/// Depth-first search from `board` that never descends more than `limit`
+/// moves, recording the longest path seen in `best`. Returns `true` if the
+/// board was won, in which case `best` holds the winning moves.
+fn search_limited(
+    board: &Board,
+    config: &SolverConfig,
+    budget: &Budget,
+    limit: usize,
+    path: &mut Vec<Direction>,
+    best: &mut Vec<Direction>,
+) -> bool {
+    if !budget.spend(board) {
+        return false;
+    }
+    if config.is_goal(board) {
+        best.clone_from(path);
+        return true;
+    }
+    if path.len() > best.len() {
+        best.clone_from(path);
+    }
+    if path.len() == limit {
+        return false;
+    }
+    for (dir, next) in children(board, config) {
+        path.push(dir);
+        if search_limited(&next, config, budget, limit, path, best) {
+            return true;
+        }
+        path.pop();
+    }
+    false
+}
+
+/// Search the first `depth` plies below `board`, which is reached by `path`,
+/// in parallel, one task per move, then fall back to a sequential search.
+/// Returns the winning moves found by whichever task finishes first.
+fn split(
+    board: &Board,
+    ctx: &Context,
+    depth: usize,
+    mut path: Vec<Direction>,
+) -> Option<Vec<Direction>> {
+    if depth == 0 {
+        return if search(board, ctx, &mut path) {
+            Some(path)
+        } else {
+            None
+        };
+    }
+    if ctx.config.is_goal(board) {
+        return Some(path);
+    }
+    children(board, ctx.config)
+        .into_par_iter()
+        .find_map_any(|(dir, next)| {
+            let mut path = path.clone();
+            path.push(dir);
+            split(&next, ctx, depth - 1, path)
+        })
+}
+
+/// Try every possible move from `board`, depth first, pushing each move onto
+/// `path` and popping it again when the branch fails. Returns `true` once the
+/// board has been won, leaving the winning moves in `path`. Returns `false`
+/// early if another branch has already found a solution, or the budget has
+/// run out. The search keeps its own [`Stack`] of frames on the heap rather
+/// than recursing, so the depth of the search is not limited by the size of
+/// the thread's stack.
+fn search(board: &Board, ctx: &Context, path: &mut Vec<Direction>) -> bool {
+    let mut stack = Stack::new(std::mem::take(path));
+    let mut entering = board.clone();
+    let found = loop {
+        if ctx.stop.load(Ordering::Relaxed) {
+            break false;
+        }
+        ctx.improve(&entering, &stack.path);
+        if ctx.config.is_goal(&entering) {
+            ctx.stop.store(true, Ordering::Relaxed);
+            break true;
+        }
+        if is_dead(&entering, ctx.config, ctx.table.as_ref()) {
+            stack.back();
+        } else {
+            stack.push(entering, ctx.config);
+        }
+        // A branch abandoned because another branch won, or because the
+        // budget ran out, has not been proven dead.
+        let table = ctx
+            .table
+            .as_ref()
+            .filter(|_| !ctx.stop.load(Ordering::Relaxed));
+        if stack.top(ctx.config, table).is_none() {
+            break false;
+        }
+        entering = match stack.advance(ctx.budget) {
+            Some(board) => board,
+            None => {
+                ctx.stop.store(true, Ordering::Relaxed);
+                break false;
+            }
+        };
+    };
+    *path = stack.path;
+    found
+}
+
+/// Return the boards resulting from each possible move from `board`, paired
+/// with the move that produced them, in the order given by `order`.
+fn candidates(board: &Board, order: &MoveOrder) -> Vec<(Direction, Board)> {
+    let mut moves: Vec<Direction> = board.iter_moves().collect();
+    order.order(board, &mut moves);
+    moves
+        .into_iter()
+        .filter_map(|dir| board.next_move(dir).ok().map(|b| (dir, b)))
+        .collect()
+}
+
+/// Return the boards resulting from each possible move from `board` in the
+/// order given by `config`, leaving out any that fail a configured prune.
+fn children(board: &Board, config: &SolverConfig) -> Vec<(Direction, Board)> {
+    let from = board.current_position();
+    let mut next = candidates(board, &config.order);
+    next.retain(|(_, b)| config.prunes.iter().all(|p| p.allows(from, b)));
+    if config.closed {
+        next.retain(|(_, b)| can_close(b));
+    }
+    if let Some(table) = &config.tablebase {
+        next.retain(|(_, b)| table.allows(b));
+    }
+    next
+}
+
+/// Return `false` if `board` can no longer be completed as a closed tour,
+/// because every cell one jump away from its first cell has been filled
+/// while empty cells remain.
+fn can_close(board: &Board) -> bool {
+    match board.start_position() {
+        Some((x, y)) => board.free_neighbors(x, y) > 0 || board.score() == board.cells(),
+        None => false,
+    }
+}
+
+/// Apply `moves` to `board` in order, stopping at the first move that is not
+/// valid. Returns the resulting board.
+fn replay(board: &Board, moves: &[Direction]) -> Board {
+    let mut board = board.clone();
+    for &dir in moves {
+        match board.next_move(dir) {
+            Ok(next) => board = next,
+            Err(_) => break,
+        }
+    }
+    board
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::MoveRule;
+
+    #[test]
+    // Solve a 5x5 board from the top-left corner and replay the moves.
+    fn solve_5() {
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        let moves = solve(&board).expect("5x5 board should be solvable");
+        assert_eq!(moves.len(), 24);
+        for m in moves {
+            board = board.next_move(m).unwrap();
+        }
+        assert!(board.is_won());
+    }
+
+    #[test]
+    // An unstarted board cannot be solved.
+    fn solve_not_started() {
+        let board = Board::new(5);
+        assert_eq!(solve(&board), None);
+    }
+
+    #[test]
+    // A board that is already won is solved by the empty move list.
+    fn solve_already_won() {
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        for m in solve(&board).unwrap() {
+            board = board.next_move(m).unwrap();
+        }
+        assert_eq!(solve(&board), Some(vec![]));
+    }
+
+    #[test]
+    // Blocked cells are left out of the path, and every prune agrees with
+    // the unpruned search on how many ways there are around them.
+    fn solve_blocked() {
+        let board = Board::new(6)
+            .block(2, 2)
+            .and_then(|b| b.block(3, 3))
+            .unwrap()
+            .start_at(0, 0)
+            .unwrap();
+        let moves = solve(&board).expect("6x6 with two holes should be solvable");
+        assert_eq!(moves.len(), 33);
+        assert!(replay(&board, &moves).is_won());
+        let unpruned = SolverConfig {
+            prunes: vec![],
+            ..SolverConfig::default()
+        };
+        let pruned = SolverConfig {
+            prunes: vec![
+                Prune::ForwardCheck,
+                Prune::DeadEnd,
+                Prune::Connectivity,
+                Prune::Articulation,
+            ],
+            ..SolverConfig::default()
+        };
+        assert_eq!(
+            count_solutions_with_config(&board, &pruned),
+            count_solutions_with_config(&board, &unpruned)
+        );
+    }
+
+    #[test]
+    // Warnsdorff ordering solves a 20x20 board, whose values do not fit in
+    // a byte.
+    fn solve_20_warnsdorff() {
+        let config = SolverConfig {
+            order: MoveOrder::Warnsdorff,
+            ..SolverConfig::default()
+        };
+        let board = Board::new(20).start_at(0, 0).unwrap();
+        let moves = solve_with_config(&board, &config).expect("20x20 should be solvable");
+        let won = replay(&board, &moves);
+        assert!(won.is_won());
+        assert_eq!(won.last_value(), 400);
+    }
+
+    #[test]
+    // Boards with other move rules are solved along moves of their rule,
+    // with and without symmetry in the transposition table.
+    fn solve_move_rule() {
+        let rules = [
+            MoveRule::Jump {
+                straight: 1,
+                diagonal: 1,
+            },
+            MoveRule::Jump {
+                straight: 1,
+                diagonal: 2,
+            },
+        ];
+        for &rule in &rules {
+            let board = Board::new(6)
+                .with_rule(rule)
+                .unwrap()
+                .start_at(0, 0)
+                .unwrap();
+            for symmetry in [false, true] {
+                let config = SolverConfig {
+                    transposition: Some(1 << 12),
+                    symmetry,
+                    ..SolverConfig::default()
+                };
+                let moves = solve_with_config(&board, &config).unwrap();
+                assert!(replay(&board, &moves).is_won());
+            }
+        }
+    }
+
+    #[test]
+    // Knight's tours are found from a corner of a 5x5 board and of a
+    // chessboard, and a start on the smaller colour of a 5x5 board is ruled
+    // out, since the knight changes colour with every move.
+    fn solve_knight() {
+        let board = Board::new(5).with_rule(MoveRule::Knight).unwrap();
+        let start = board.start_at(0, 0).unwrap();
+        let moves = solve(&start).unwrap();
+        assert!(replay(&start, &moves).is_won());
+        assert!(check_feasibility(&board.start_at(1, 0).unwrap()).is_err());
+        let board = Board::with_dimensions(8, 8)
+            .with_rule(MoveRule::Knight)
+            .and_then(|b| b.start_at(0, 0))
+            .unwrap();
+        let config = SolverConfig {
+            order: MoveOrder::Warnsdorff,
+            ..SolverConfig::default()
+        };
+        let moves = solve_with_config(&board, &config).unwrap();
+        assert!(replay(&board, &moves).is_won());
+    }
+
+    #[test]
+    // Boards that wrap around are solved along jumps across their edges,
+    // with and without symmetry in the transposition table.
+    fn solve_wrap() {
+        for size in [6, 7] {
+            let board = Board::new(size)
+                .with_wrap(true)
+                .unwrap()
+                .start_at(0, 0)
+                .unwrap();
+            for symmetry in [false, true] {
+                let config = SolverConfig {
+                    transposition: Some(1 << 12),
+                    symmetry,
+                    ..SolverConfig::default()
+                };
+                let moves = solve_with_config(&board, &config).unwrap();
+                assert!(replay(&board, &moves).is_won());
+            }
+        }
+    }
+
+    #[test]
+    // An 8x12 board is solved with the default options, with and without
+    // symmetry in the transposition table.
+    fn solve_rectangular() {
+        let board = Board::with_dimensions(8, 12).start_at(0, 0).unwrap();
+        for symmetry in [false, true] {
+            let config = SolverConfig {
+                order: MoveOrder::Warnsdorff,
+                symmetry,
+                ..SolverConfig::default()
+            };
+            let moves = solve_with_config(&board, &config).expect("8x12 should be solvable");
+            assert_eq!(moves.len(), 95);
+            assert!(replay(&board, &moves).is_won());
+        }
+    }
+
+    #[test]
+    // Warnsdorff ordering solves a 10x10 board and the moves replay to a win.
+    fn solve_10_warnsdorff() {
+        let config = SolverConfig {
+            order: MoveOrder::Warnsdorff,
+            ..SolverConfig::default()
+        };
+        let mut board = Board::new(10).start_at(0, 0).unwrap();
+        let moves = solve_with_config(&board, &config).expect("10x10 should be solvable");
+        assert_eq!(moves.len(), 99);
+        for m in moves {
+            board = board.next_move(m).unwrap();
+        }
+        assert!(board.is_won());
+    }
+
+    #[test]
+    // Every starting square of a 5x5 board is reported once, in row-major
+    // order, and any solution found replays to a win.
+    fn all_starts_5() {
+        let results = solve_all_starts(5);
+        assert_eq!(results.len(), 25);
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!((result.x, result.y), (i % 5, i / 5));
+            if let Some(moves) = &result.solution {
+                let mut board = Board::new(5).start_at(result.x, result.y).unwrap();
+                for m in moves {
+                    board = board.next_move(*m).unwrap();
+                }
+                assert!(board.is_won());
+            }
+        }
+        assert!(results[0].solution.is_some());
+    }
+
+    #[test]
+    // Splitting the top of the search tree across threads still finds a
+    // solution that replays to a win.
+    fn solve_split() {
+        for order in [MoveOrder::Natural, MoveOrder::Warnsdorff] {
+            let config = SolverConfig {
+                order: order.clone(),
+                parallelism: Parallelism::Split { depth: 2 },
+                ..SolverConfig::default()
+            };
+            let mut board = Board::new(5).start_at(0, 0).unwrap();
+            let moves = solve_with_config(&board, &config).expect("5x5 should be solvable");
+            for m in moves {
+                board = board.next_move(m).unwrap();
+            }
+            assert!(board.is_won(), "order {:?}", order);
+        }
+    }
+
+    #[test]
+    // Iterative deepening reports one partial path per depth, each as long as
+    // its depth, and finishes with a winning solution.
+    fn solve_5_iterative_deepening() {
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        let mut reports = Vec::new();
+        let moves = solve_iterative_deepening(&board, &SolverConfig::default(), |depth, best| {
+            reports.push((depth, best.len()))
+        })
+        .expect("5x5 should be solvable");
+        assert_eq!(reports.len(), 24);
+        for (depth, len) in reports {
+            assert_eq!(depth, len);
+        }
+        for m in moves {
+            board = board.next_move(m).unwrap();
+        }
+        assert!(board.is_won());
+    }
+
+    #[test]
+    // Iterative deepening gives up once no path can reach the next depth.
+    fn iterative_deepening_no_solution() {
+        let board = Board::new(5);
+        let mut reports = Vec::new();
+        let result = solve_iterative_deepening(&board, &SolverConfig::default(), |depth, best| {
+            reports.push((depth, best.len()))
+        });
+        assert_eq!(result, None);
+        assert_eq!(reports, vec![(1, 0)]);
+    }
+
+    #[test]
+    // The transposition table never changes whether a board can be solved.
+    fn transposition_agrees() {
+        let config = SolverConfig {
+            prunes: vec![],
+            ..SolverConfig::default()
+        };
+        let cached = SolverConfig {
+            transposition: Some(1 << 12),
+            ..config.clone()
+        };
+        let symmetric = SolverConfig {
+            symmetry: true,
+            ..cached.clone()
+        };
+        for &(x, y) in &[(0, 0), (1, 2), (3, 4), (5, 5)] {
+            let mut board = Board::new(6).start_at(x, y).unwrap();
+            for _ in 0..8 {
+                let dir = *board.possible_moves()[0];
+                board = board.next_move(dir).unwrap();
+            }
+            let expected = solve_with_config(&board, &config).is_some();
+            for c in &[&cached, &symmetric] {
+                assert_eq!(
+                    solve_with_config(&board, c).is_some(),
+                    expected,
+                    "start ({}, {})",
+                    x,
+                    y
+                );
+            }
+        }
+    }
+
+    #[test]
+    // The anytime callback sees boards of strictly increasing score, ending
+    // with the won board.
+    fn solve_with_reports_progress() {
+        let board = Board::new(6).start_at(0, 0).unwrap();
+        let mut scores = Vec::new();
+        let moves = solve_with(&board, &SolverConfig::default(), |best| {
+            scores.push(best.score())
+        });
+        assert!(moves.is_some());
+        assert!(scores.windows(2).all(|w| w[0] < w[1]));
+        assert_eq!(scores.first(), Some(&2));
+        assert_eq!(scores.last(), Some(&36));
+    }
+
+    #[test]
+    // An exhaustive search ends as solved or exhausted when unlimited.
+    fn solve_within_unlimited() {
+        let limits = SolverLimits::default();
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let outcome = solve_within(&board, &Strategy::default(), &limits);
+        assert!(matches!(outcome, Outcome::Solved(ref moves) if moves.len() == 24));
+        let outcome = solve_within(&Board::new(5), &Strategy::default(), &limits);
+        assert!(matches!(outcome, Outcome::Exhausted));
+    }
+
+    #[test]
+    // Every strategy stops within a node limit and returns a best board that
+    // its moves replay to.
+    fn solve_within_node_limit() {
+        let limits = SolverLimits {
+            max_nodes: Some(50),
+            ..SolverLimits::default()
+        };
+        let strategies = vec![
+            Strategy::Search(SolverConfig::default()),
+            Strategy::IterativeDeepening(SolverConfig::default()),
+            Strategy::Bidirectional(SolverConfig::default()),
+            Strategy::RandomRestart(RandomRestartConfig::default()),
+            Strategy::Anneal(AnnealConfig::default()),
+            Strategy::Genetic(GeneticConfig::default()),
+            Strategy::Mcts(MctsConfig::default()),
+            Strategy::Beam(BeamConfig::default()),
+        ];
+        let board = Board::new(12).start_at(0, 0).unwrap();
+        for strategy in &strategies {
+            match solve_within(&board, strategy, &limits) {
+                Outcome::BudgetExceeded { best } => {
+                    assert!(best.board.score() > 1, "{:?}", strategy);
+                    assert_eq!(replay(&board, &best.moves).score(), best.board.score());
+                }
+                outcome => panic!("{:?} ended with {:?}", strategy, outcome),
+            }
+        }
+    }
+
+    #[test]
+    // A zero time limit stops the search straight away.
+    fn solve_within_time_limit() {
+        let limits = SolverLimits {
+            max_duration: Some(std::time::Duration::from_secs(0)),
+            ..SolverLimits::default()
+        };
+        let board = Board::new(10).start_at(0, 0).unwrap();
+        let outcome = solve_within(&board, &Strategy::default(), &limits);
+        assert!(matches!(outcome, Outcome::BudgetExceeded { .. }));
+    }
+
+    #[test]
+    // A cancelled token stops every strategy, which reports the cancellation.
+    fn solve_within_cancelled() {
+        let token = CancelToken::new();
+        token.cancel();
+        let limits = SolverLimits {
+            cancel: Some(token),
+            ..SolverLimits::default()
+        };
+        let board = Board::new(10).start_at(0, 0).unwrap();
+        let strategies = vec![
+            Strategy::Search(SolverConfig::default()),
+            Strategy::IterativeDeepening(SolverConfig::default()),
+            Strategy::Mcts(MctsConfig::default()),
+        ];
+        for strategy in &strategies {
+            let outcome = solve_within(&board, strategy, &limits);
+            assert!(
+                matches!(outcome, Outcome::Cancelled { .. }),
+                "{:?}",
+                strategy
+            );
+        }
+    }
+
+    #[test]
+    // Cancelling from another thread stops a solve that is already running.
+    fn solve_within_cancelled_from_thread() {
+        let token = CancelToken::new();
+        let limits = SolverLimits {
+            cancel: Some(token.clone()),
+            ..SolverLimits::default()
+        };
+        let canceller = std::thread::spawn(move || {
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            token.cancel();
+        });
+        // An exhaustive search of a 12x12 board takes far longer than the
+        // test allows without the token.
+        let board = Board::new(12).start_at(0, 0).unwrap();
+        let config = SolverConfig {
+            order: MoveOrder::Natural,
+            prunes: vec![],
+            ..SolverConfig::default()
+        };
+        let outcome = solve_within(&board, &Strategy::Search(config), &limits);
+        canceller.join().unwrap();
+        assert!(matches!(outcome, Outcome::Cancelled { .. }));
+    }
+
+    #[test]
+    // Counting finds every solution, whether or not the prunes are enabled.
+    fn count_solutions_5() {
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let unpruned = SolverConfig {
+            prunes: vec![],
+            ..SolverConfig::default()
+        };
+        assert_eq!(count_solutions(&board), 552);
+        assert_eq!(count_solutions_with_config(&board, &unpruned), 552);
+        assert_eq!(count_solutions(&Board::new(5)), 0);
+    }
+
+    #[test]
+    // In closed mode the solver only accepts tours that return to the start,
+    // and finds every open path that happens to close.
+    fn solve_closed() {
+        let board = Board::new(6).start_at(0, 0).unwrap();
+        let config = SolverConfig {
+            closed: true,
+            ..SolverConfig::default()
+        };
+        let moves = solve_with_config(&board, &config).unwrap();
+        assert!(replay(&board, &moves).is_closed_tour());
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let closing = solutions(&board)
+            .filter(|moves| replay(&board, moves).is_closed_tour())
+            .count();
+        assert_eq!(count_solutions_with_config(&board, &config), 96);
+        assert_eq!(closing, 96);
+    }
+
+    #[test]
+    // The solver fills in a board with clues, passing through every clue
+    // with its value. Symmetric transposition keys do not merge states of a
+    // board whose clues are not symmetric.
+    fn solve_clues() {
+        let clues = [(0, 0, 1), (3, 1, 8), (2, 3, 15), (4, 0, 25)];
+        let board = Board::with_clues(5, &clues).unwrap();
+        let start = board.start_at(0, 0).unwrap();
+        let moves = solve(&start).unwrap();
+        let end = replay(&start, &moves);
+        assert!(end.is_won());
+        for &(x, y, value) in &clues {
+            assert_eq!(end.value_at(x, y), value);
+        }
+        let config = SolverConfig {
+            transposition: Some(1 << 10),
+            symmetry: true,
+            ..SolverConfig::default()
+        };
+        assert_eq!(
+            Solutions::new(&start, &config).count() as u64,
+            count_solutions(&start)
+        );
+    }
+
+    #[test]
+    // A deep search runs on a thread with a small stack, and progress reports
+    // show the depth of its explicit stack.
+    fn search_small_stack() {
+        let depths = std::sync::Arc::new(Mutex::new(Vec::new()));
+        let seen = depths.clone();
+        let limits = SolverLimits {
+            progress: Some(ProgressHook::new(1, move |p: &Progress| {
+                seen.lock().unwrap().push((p.depth, p.stack_depth));
+            })),
+            ..SolverLimits::default()
+        };
+        let outcome = std::thread::Builder::new()
+            .stack_size(64 * 1024)
+            .spawn(move || {
+                let board = Board::new(10).start_at(0, 0).unwrap();
+                let config = SolverConfig {
+                    order: MoveOrder::Warnsdorff,
+                    ..SolverConfig::default()
+                };
+                solve_within(&board, &Strategy::Search(config), &limits)
+            })
+            .unwrap()
+            .join()
+            .unwrap();
+        assert!(matches!(outcome, Outcome::Solved(ref moves) if moves.len() == 99));
+        let depths = depths.lock().unwrap();
+        assert!(depths.iter().all(|&(depth, stack)| depth == stack + 1));
+        assert_eq!(depths.last(), Some(&(100, 99)));
+    }
+
+    #[test]
+    // A board proven infeasible is reported as such by every strategy.
+    fn solve_within_infeasible() {
+        use Direction::*;
+        let moves = [
+            Up, Right, DownLeft, DownRight, Up, DownLeft, UpLeft, Right, Down, UpLeft, Right,
+        ];
+        let board = replay(&Board::new(5).start_at(1, 3).unwrap(), &moves);
+        let limits = SolverLimits::default();
+        for strategy in &[Strategy::default(), Strategy::Beam(BeamConfig::default())] {
+            let outcome = solve_within(&board, strategy, &limits);
+            assert!(matches!(
+                outcome,
+                Outcome::Infeasible(Infeasible::Disconnected { x: 0, y: 4 })
+            ));
+        }
+    }
+}
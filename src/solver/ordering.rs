@@ -0,0 +1,162 @@
+//! The order in which the solver tries the moves from a position.
+//!
+//! A depth-first search finds a solution sooner when it tries the most
+//! promising moves first. [`MoveOrder`] selects one of the built-in orders,
+//! or wraps any type implementing [`MoveOrdering`].
+
+use crate::board::{Board, Direction};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use std::fmt;
+use std::sync::Arc;
+
+/// MoveOrdering chooses the order in which the possible moves from a board
+/// are tried. The solver calls it at every node, possibly from several
+/// threads at once. It must give the same order each time it sees the same
+/// board, or a checkpointed search will not resume where it left off.
+pub trait MoveOrdering: Send + Sync {
+    /// Reorder `moves`, the possible moves from the current position of
+    /// `board`, into the order in which they should be tried.
+    fn order(&self, board: &Board, moves: &mut Vec<Direction>);
+}
+
+#[derive(Clone)]
+/// MoveOrder selects the order in which the solver tries the possible moves
+/// from a position.
+pub enum MoveOrder {
+    /// Try moves in the order returned by `Board::possible_moves()`.
+    Natural,
+    /// Try the moves with the fewest onward moves from the target cell first
+    /// (Warnsdorff's rule). Ties keep their natural order.
+    Warnsdorff,
+    /// Try the moves whose target cell is nearest the center of the board
+    /// first. Ties keep their natural order.
+    CenterOut,
+    /// Try moves in a random order. The order depends only on `seed` and the
+    /// current position and score of the board.
+    Random { seed: u64 },
+    /// Try moves in the order given by a user-supplied [`MoveOrdering`].
+    Custom(Arc<dyn MoveOrdering>),
+}
+
+impl MoveOrdering for MoveOrder {
+    fn order(&self, board: &Board, moves: &mut Vec<Direction>) {
+        let (x, y) = board.current_position();
+        let target = |dir: Direction| board.target(x, y, dir).unwrap_or((x, y));
+        match self {
+            MoveOrder::Natural => {}
+            MoveOrder::Warnsdorff => {
+                moves.sort_by_key(|&dir| {
+                    let (x, y) = target(dir);
+                    board.free_neighbors(x, y)
+                });
+            }
+            MoveOrder::CenterOut => {
+                let (mx, my) = (board.width() as i64 - 1, board.height() as i64 - 1);
+                moves.sort_by_key(|&dir| {
+                    let (x, y) = target(dir);
+                    let (dx, dy) = (2 * x as i64 - mx, 2 * y as i64 - my);
+                    dx * dx + dy * dy
+                });
+            }
+            MoveOrder::Random { seed } => {
+                let position = (y * board.width() + x) as u64;
+                let mix = (board.score() as u64) << 32 | position;
+                let mut rng = StdRng::seed_from_u64(seed ^ mix.wrapping_mul(0x9e37_79b9_7f4a_7c15));
+                moves.shuffle(&mut rng);
+            }
+            MoveOrder::Custom(ordering) => ordering.order(board, moves),
+        }
+    }
+}
+
+impl fmt::Debug for MoveOrder {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoveOrder::Natural => write!(f, "Natural"),
+            MoveOrder::Warnsdorff => write!(f, "Warnsdorff"),
+            MoveOrder::CenterOut => write!(f, "CenterOut"),
+            MoveOrder::Random { seed } => write!(f, "Random {{ seed: {} }}", seed),
+            MoveOrder::Custom(_) => write!(f, "Custom"),
+        }
+    }
+}
+
+impl PartialEq for MoveOrder {
+    /// Built-in orders are equal if they have the same options. Custom
+    /// orders are equal only if they share the same ordering.
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (MoveOrder::Natural, MoveOrder::Natural) => true,
+            (MoveOrder::Warnsdorff, MoveOrder::Warnsdorff) => true,
+            (MoveOrder::CenterOut, MoveOrder::CenterOut) => true,
+            (MoveOrder::Random { seed: a }, MoveOrder::Random { seed: b }) => a == b,
+            (MoveOrder::Custom(a), MoveOrder::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Reverses the natural order.
+    struct Reversed;
+
+    impl MoveOrdering for Reversed {
+        fn order(&self, _: &Board, moves: &mut Vec<Direction>) {
+            moves.reverse();
+        }
+    }
+
+    /// Return the moves from `board` in the order given by `order`.
+    fn ordered(board: &Board, order: &MoveOrder) -> Vec<Direction> {
+        let mut moves: Vec<Direction> = board.iter_moves().collect();
+        order.order(board, &mut moves);
+        moves
+    }
+
+    #[test]
+    // From a corner of a 10x10 board, Warnsdorff's rule prefers the edges and
+    // center-out ordering prefers the diagonal.
+    fn built_in_orders() {
+        let board = Board::new(10).start_at(0, 0).unwrap();
+        use Direction::*;
+        assert_eq!(
+            ordered(&board, &MoveOrder::Natural),
+            vec![Down, DownRight, Right]
+        );
+        assert_eq!(
+            ordered(&board, &MoveOrder::Warnsdorff),
+            vec![Down, Right, DownRight]
+        );
+        assert_eq!(
+            ordered(&board, &MoveOrder::CenterOut),
+            vec![DownRight, Down, Right]
+        );
+        let random = MoveOrder::Random { seed: 7 };
+        let mut shuffled = ordered(&board, &random);
+        assert_eq!(shuffled, ordered(&board, &random));
+        shuffled.sort_by_key(|&d| d as u8);
+        assert_eq!(shuffled, vec![Down, DownRight, Right]);
+    }
+
+    #[test]
+    // A custom ordering is consulted by the solver, which still finds a
+    // solution.
+    fn custom_order() {
+        let order = MoveOrder::Custom(Arc::new(Reversed));
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        assert_eq!(
+            ordered(&board, &order),
+            vec![Direction::Right, Direction::DownRight, Direction::Down]
+        );
+        let config = crate::solver::SolverConfig {
+            order,
+            ..Default::default()
+        };
+        assert!(crate::solver::solve_with_config(&board, &config).is_some());
+    }
+}
@@ -0,0 +1,243 @@
+//! Checks that let the solver abandon a branch before searching it.
+//!
+//! Every prune is sound: it only rejects boards that cannot be completed, so
+//! enabling one never changes whether the solver finds a solution.
+
+use crate::board::{Board, Direction};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// Prune is a check applied to every board the solver reaches.
+pub enum Prune {
+    /// Abandon a board on which some empty cell can no longer be entered,
+    /// because none of the cells one jump away from it are empty or the
+    /// current position.
+    ForwardCheck,
+    /// Abandon a board with a cell that would have to be the last one filled
+    /// while other cells remain, or with two cells that would both have to be
+    /// last. An empty cell that is not next to the current position, with
+    /// only one empty cell one jump away, can be entered but never left.
+    DeadEnd,
+    /// Abandon a board on which some empty cell cannot be reached from the
+    /// current position by jumping through empty cells. Once the empty cells
+    /// split into disconnected regions, the path can only ever fill one.
+    Connectivity,
+    /// Abandon a board whose last move landed on an articulation point of
+    /// the empty cells: a cell whose filling splits the cells still empty
+    /// into groups that can only be joined through it. The path can enter
+    /// only one group from there and never return for the others. This
+    /// catches every board that `Connectivity` does, and more, for about the
+    /// same cost.
+    Articulation,
+}
+
+impl Prune {
+    /// Return `true` if `board`, reached by a move from `from`, may still be
+    /// completable; `false` if the branch should be abandoned.
+    pub(super) fn allows(self, from: (usize, usize), board: &Board) -> bool {
+        match self {
+            Prune::ForwardCheck => forward_check(from, board),
+            Prune::DeadEnd => dead_end(board),
+            Prune::Connectivity => connectivity(board),
+            Prune::Articulation => articulation(board),
+        }
+    }
+}
+
+/// Return the cells one jump away from (x, y).
+fn neighbors(board: &Board, x: usize, y: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    Direction::iterator().filter_map(move |&dir| board.target(x, y, dir))
+}
+
+/// Check that every empty cell next to `from` can still be entered. Only
+/// these cells need checking: `from` was the only cell to stop being a way
+/// into its neighbors, and the neighbors of the new position can always be
+/// entered from it.
+fn forward_check(from: (usize, usize), board: &Board) -> bool {
+    let to = board.current_position();
+    neighbors(board, from.0, from.1)
+        .filter(|&(x, y)| board.value_at(x, y) == 0)
+        .all(|(x, y)| neighbors(board, x, y).any(|n| n == to || board.value_at(n.0, n.1) == 0))
+}
+
+/// Check that at most one empty cell is forced to be the last one filled,
+/// using the free neighbor counts the board keeps up to date.
+fn dead_end(board: &Board) -> bool {
+    let to = board.current_position();
+    let empty = board.remaining();
+    let mut forced = 0;
+    for (x, y) in board.empty_cells() {
+        // Jumps are symmetric, so (x, y) is next to the current position
+        // exactly when it is one jump away from it.
+        let adjacent =
+            Direction::iterator().any(|&dir| board.target(to.0, to.1, dir) == Some((x, y)));
+        match (board.free_neighbors(x, y), adjacent) {
+            (0, false) => return false,
+            (0, true) if empty > 1 => return false,
+            (1, false) => forced += 1,
+            _ => {}
+        }
+        if forced > 1 {
+            return false;
+        }
+    }
+    true
+}
+
+/// Check that every empty cell is reachable from the current position.
+fn connectivity(board: &Board) -> bool {
+    let empty = board.remaining();
+    board.reachable() == empty
+}
+
+/// Check that the empty cells are connected to each other without passing
+/// through the current position, and that the current position is next to
+/// one of them.
+fn articulation(board: &Board) -> bool {
+    let width = board.width();
+    let empty = board.remaining();
+    let (x, y) = board.current_position();
+    let first = match neighbors(board, x, y).find(|&(x, y)| board.value_at(x, y) == 0) {
+        Some(cell) => cell,
+        None => return empty == 0,
+    };
+    let mut seen = vec![false; width * board.height()];
+    seen[first.1 * width + first.0] = true;
+    let mut stack = vec![first];
+    let mut count = 1;
+    while let Some((x, y)) = stack.pop() {
+        for (x, y) in neighbors(board, x, y) {
+            let i = y * width + x;
+            if !seen[i] && board.value_at(x, y) == 0 {
+                seen[i] = true;
+                count += 1;
+                stack.push((x, y));
+            }
+        }
+    }
+    count == empty
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use Direction::*;
+
+    #[test]
+    // Forward checking allows every move of a game until the move that
+    // leaves an empty cell with no way in.
+    fn forward_check_strands_cell() {
+        let moves = [
+            Down, Right, UpLeft, Down, Right, UpLeft, DownLeft, Right, UpLeft, Right,
+        ];
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        for (i, &m) in moves.iter().enumerate() {
+            let from = board.current_position();
+            board = board.next_move(m).unwrap();
+            let last = i == moves.len() - 1;
+            assert_eq!(
+                Prune::ForwardCheck.allows(from, &board),
+                !last,
+                "move {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    // Forward checking prunes nothing on the way to a solution.
+    fn forward_check_allows_solution() {
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        let moves = crate::solver::solve_with_config(
+            &board,
+            &crate::solver::SolverConfig {
+                prunes: vec![],
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        for m in moves {
+            let from = board.current_position();
+            board = board.next_move(m).unwrap();
+            assert!(Prune::ForwardCheck.allows(from, &board));
+        }
+    }
+
+    #[test]
+    // Connectivity catches a split of the empty cells that forward checking
+    // misses.
+    fn connectivity_split() {
+        let moves = [
+            Down, Right, Up, DownLeft, DownRight, Up, Left, DownRight, UpRight, Down, UpLeft,
+            DownLeft,
+        ];
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        for (i, &m) in moves.iter().enumerate() {
+            let from = board.current_position();
+            board = board.next_move(m).unwrap();
+            let last = i == moves.len() - 1;
+            assert!(Prune::ForwardCheck.allows(from, &board));
+            assert_eq!(
+                Prune::Connectivity.allows(from, &board),
+                !last,
+                "move {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    // Dead-end counting catches two cells that would both have to be last,
+    // which the other prunes miss.
+    fn dead_end_two_ends() {
+        let moves = [
+            Down, Right, Up, DownLeft, DownRight, Up, Left, Down, UpRight, DownRight, Up, DownLeft,
+            Up, DownLeft,
+        ];
+        let mut board = Board::new(5).start_at(0, 0).unwrap();
+        for (i, &m) in moves.iter().enumerate() {
+            let from = board.current_position();
+            board = board.next_move(m).unwrap();
+            let last = i == moves.len() - 1;
+            assert!(Prune::ForwardCheck.allows(from, &board));
+            assert!(Prune::Connectivity.allows(from, &board));
+            assert_eq!(Prune::DeadEnd.allows(from, &board), !last, "move {}", i);
+        }
+    }
+
+    #[test]
+    // Articulation catches a move that cuts the empty cells in two, which
+    // connectivity misses because both groups are still reachable from the
+    // new position.
+    fn articulation_cut() {
+        let moves = [
+            Down, Left, Up, DownRight, Up, DownLeft, Right, UpLeft, Right, Down,
+        ];
+        let mut board = Board::new(5).start_at(3, 1).unwrap();
+        for (i, &m) in moves.iter().enumerate() {
+            let from = board.current_position();
+            board = board.next_move(m).unwrap();
+            let last = i == moves.len() - 1;
+            assert!(Prune::Connectivity.allows(from, &board));
+            assert_eq!(
+                Prune::Articulation.allows(from, &board),
+                !last,
+                "move {}",
+                i
+            );
+        }
+    }
+
+    #[test]
+    // Articulation loses no solutions.
+    fn articulation_count() {
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let config = crate::solver::SolverConfig {
+            prunes: vec![Prune::Articulation],
+            ..Default::default()
+        };
+        assert_eq!(
+            crate::solver::count_solutions_with_config(&board, &config),
+            552
+        );
+    }
+}
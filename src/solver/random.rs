@@ -0,0 +1,120 @@
+//! Random-restart solver.
+//!
+//! Plays random legal moves until the board is blocked, then starts over from
+//! the original board, keeping the highest-scoring board seen. This quickly
+//! finds high-scoring fillings of large boards, but is not guaranteed to find
+//! a complete one.
+
+use super::{Budget, PartialSolution};
+use crate::board::Board;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
+
+#[derive(Debug, Clone)]
+/// Options controlling [`random_restart`].
+pub struct RandomRestartConfig {
+    /// Seed for the random number generator, so runs can be reproduced.
+    pub seed: u64,
+    /// The number of random games to play before giving up.
+    pub restarts: usize,
+}
+
+impl Default for RandomRestartConfig {
+    fn default() -> Self {
+        RandomRestartConfig {
+            seed: 0,
+            restarts: 1000,
+        }
+    }
+}
+
+/// Play up to `config.restarts` random games from `board` and return the
+/// highest-scoring board reached. Stops early if a game wins the board.
+pub fn random_restart(board: &Board, config: &RandomRestartConfig) -> PartialSolution {
+    random_restart_within(board, config, &Budget::unlimited())
+}
+
+/// Like [`random_restart`], but stops once `budget` runs out.
+pub(super) fn random_restart_within(
+    board: &Board,
+    config: &RandomRestartConfig,
+    budget: &Budget,
+) -> PartialSolution {
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    let mut best = PartialSolution {
+        board: board.clone(),
+        moves: Vec::new(),
+    };
+    for _ in 0..config.restarts {
+        if best.board.is_won() || budget.exceeded() {
+            break;
+        }
+        let game = play_random(board, &mut rng, budget);
+        if game.board.score() > best.board.score() {
+            best = game;
+        }
+    }
+    best
+}
+
+/// Play random legal moves from `board` until it is blocked or `budget` runs
+/// out.
+fn play_random(board: &Board, rng: &mut StdRng, budget: &Budget) -> PartialSolution {
+    let mut board = board.clone();
+    let mut moves = Vec::new();
+    while let Some(&&dir) = board.possible_moves().choose(rng) {
+        if !budget.spend(&board) {
+            break;
+        }
+        match board.next_move(dir) {
+            Ok(next) => board = next,
+            Err(_) => break,
+        }
+        moves.push(dir);
+    }
+    PartialSolution { board, moves }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    // The best board found is the result of replaying its moves.
+    fn moves_replay_to_best() {
+        let start = Board::new(10).start_at(4, 4).unwrap();
+        let best = random_restart(&start, &RandomRestartConfig::default());
+        assert!(best.board.score() > 1);
+        assert_eq!(best.board.score(), best.moves.len() + 1);
+        let mut board = start;
+        for m in best.moves {
+            board = board.next_move(m).unwrap();
+        }
+        assert_eq!(board.score(), best.board.score());
+    }
+
+    #[test]
+    // The same seed always produces the same result.
+    fn seeded_runs_repeat() {
+        let start = Board::new(8).start_at(0, 0).unwrap();
+        let config = RandomRestartConfig {
+            seed: 42,
+            restarts: 50,
+        };
+        let first = random_restart(&start, &config);
+        let second = random_restart(&start, &config);
+        assert_eq!(first.moves, second.moves);
+    }
+
+    #[test]
+    // Enough random games on a small board find a complete filling.
+    fn wins_5() {
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let config = RandomRestartConfig {
+            seed: 1,
+            restarts: 100_000,
+        };
+        assert!(random_restart(&start, &config).board.is_won());
+    }
+}
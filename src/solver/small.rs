@@ -0,0 +1,81 @@
+//! Depth-first search over boards whose filled cells fit in one integer.
+
+use super::fixed::forward_check;
+use crate::board::Direction;
+use crate::plain::PlainBoard;
+use crate::small::SmallBoard;
+use std::collections::HashSet;
+
+/// Search for a sequence of moves that completes `board`, like
+/// [`solve_fixed`](super::solve_fixed), but on a [`SmallBoard`]. Moves are
+/// tried in the order of `Direction::iterator()`, a branch is abandoned as
+/// soon as an empty cell next to the cell just left can no longer be
+/// entered, and the key of every state found to be a dead end is kept, so
+/// that reaching it again by another route costs one lookup. An unstarted
+/// board has no solution.
+pub fn solve_small(board: &SmallBoard) -> Option<Vec<Direction>> {
+    if !board.is_started() {
+        return None;
+    }
+    let mut path = Vec::with_capacity(board.size() * board.size());
+    let mut dead = HashSet::new();
+    if search(board, &mut path, &mut dead) {
+        Some(path)
+    } else {
+        None
+    }
+}
+
+/// Extend `path` with moves that complete `board`, returning `false` and
+/// leaving `path` as it was if there are none. States that cannot be
+/// completed are added to `dead`.
+fn search(board: &SmallBoard, path: &mut Vec<Direction>, dead: &mut HashSet<(u128, u8)>) -> bool {
+    if board.is_won() {
+        return true;
+    }
+    if dead.contains(&board.key()) {
+        return false;
+    }
+    let from = board.current_position();
+    for dir in board.possible_moves() {
+        let next = match board.next_move(dir) {
+            Ok(next) => next,
+            Err(_) => continue,
+        };
+        if !forward_check(from, &next) {
+            continue;
+        }
+        path.push(dir);
+        if search(&next, path, dead) {
+            return true;
+        }
+        path.pop();
+    }
+    dead.insert(board.key());
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::solve;
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    // The small search finds the same first solution as the board search,
+    // since skipping dead states prunes only dead branches, and solves the
+    // largest small board.
+    fn solve_small_matches_solve() {
+        let small = SmallBoard::new(6).unwrap().start_at(0, 0).unwrap();
+        let board = Board::new(6).start_at(0, 0).unwrap();
+        let moves = solve_small(&small).unwrap();
+        assert_eq!(Some(moves.clone()), solve(&board));
+        let won = moves.iter().fold(small, |b, &d| b.next_move(d).unwrap());
+        assert!(won.is_won());
+        assert_eq!(solve_small(&SmallBoard::new(6).unwrap()), None);
+        let small = SmallBoard::new(11).unwrap().start_at(0, 0).unwrap();
+        let moves = solve_small(&small).unwrap();
+        let won = moves.iter().fold(small, |b, &d| b.next_move(d).unwrap());
+        assert!(won.is_won());
+    }
+}
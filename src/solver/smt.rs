@@ -0,0 +1,185 @@
+//! SMT solver backend using Z3, enabled by the `z3` feature.
+//!
+//! The puzzle is encoded with one integer per cell that is not blocked,
+//! holding the value placed there. The values are all different and between
+//! 1 and the number of cells; every cell other than the last holds a value
+//! one less than a cell one jump away, which is the successor constraint;
+//! and the cells already filled on the board, and its clues, keep their
+//! values. In closed mode the last cell must also be one jump from the
+//! first.
+//!
+//! Z3 does not share any code with the native solvers, so it is useful for
+//! checking their claims, such as a board having exactly one solution.
+
+use super::SolverConfig;
+use crate::board::{Board, Direction};
+use z3::ast::{Ast, Bool, Int};
+use z3::{Config, Context, SatResult, Solver};
+
+/// Search for a completion of `board` with Z3 and return the completed board.
+/// Only the `closed` option of `config` is used. An unstarted board has no
+/// solution.
+pub fn solve_smt(board: &Board, config: &SolverConfig) -> Option<Board> {
+    let mut found = None;
+    smt_solutions(board, config, |won| {
+        found = Some(won);
+        true
+    });
+    found
+}
+
+/// Count the completions of `board` with Z3, stopping once `limit` have been
+/// found. A `limit` of 2 is enough to check that a board has exactly one
+/// solution. Only the `closed` option of `config` is used.
+pub fn count_smt(board: &Board, config: &SolverConfig, limit: u64) -> u64 {
+    let mut count = 0;
+    smt_solutions(board, config, |_| {
+        count += 1;
+        count >= limit
+    });
+    count
+}
+
+/// Call `found` with each completion of `board` until it returns `true`.
+/// Each completion is ruled out with a new constraint before the next is
+/// searched for.
+fn smt_solutions<F>(board: &Board, config: &SolverConfig, mut found: F)
+where
+    F: FnMut(Board) -> bool,
+{
+    if board.start_position().is_none() {
+        return;
+    }
+    let width = board.width();
+    let cells = board.cells();
+    let (open, index) = board.open_cells();
+    let ctx = Context::new(&Config::new());
+    let solver = Solver::new(&ctx);
+    let value: Vec<Int> = (0..cells)
+        .map(|i| Int::new_const(&ctx, format!("v{}", i)))
+        .collect();
+    let constant = |v: usize| Int::from_i64(&ctx, v as i64);
+    let neighbors = |i: usize| -> Vec<usize> {
+        let (x, y) = open[i];
+        Direction::iterator()
+            .filter_map(|&dir| board.target(x, y, dir))
+            .map(|(x, y)| index[y * width + x])
+            .collect()
+    };
+
+    solver.assert(&Ast::distinct(&ctx, &value.iter().collect::<Vec<_>>()));
+    for (i, v) in value.iter().enumerate() {
+        solver.assert(&v.ge(&constant(1)));
+        solver.assert(&v.le(&constant(cells)));
+        let next = Int::add(&ctx, &[v, &constant(1)]);
+        let mut steps: Vec<Bool> = neighbors(i).iter().map(|&n| value[n]._eq(&next)).collect();
+        steps.push(v._eq(&constant(cells)));
+        solver.assert(&Bool::or(&ctx, &steps.iter().collect::<Vec<_>>()));
+        if config.closed {
+            let closes: Vec<Bool> = neighbors(i)
+                .iter()
+                .map(|&n| value[n]._eq(&constant(1)))
+                .collect();
+            let closes = Bool::or(&ctx, &closes.iter().collect::<Vec<_>>());
+            solver.assert(&v._eq(&constant(cells)).implies(&closes));
+        }
+        let (x, y) = open[i];
+        for fixed in [board.value_at(x, y), board.clue_at(x, y)] {
+            if fixed > 0 {
+                solver.assert(&v._eq(&constant(fixed as usize)));
+            }
+        }
+    }
+
+    while solver.check() == SatResult::Sat {
+        let model = match solver.get_model() {
+            Some(model) => model,
+            None => return,
+        };
+        let values: Vec<usize> = value
+            .iter()
+            .map(|v| model.eval(v).and_then(|v| v.as_i64()).unwrap_or(0) as usize)
+            .collect();
+        match decode(board, &open, &values) {
+            Some(won) => {
+                if found(won) {
+                    return;
+                }
+            }
+            None => return,
+        }
+        let same: Vec<Bool> = value
+            .iter()
+            .zip(&values)
+            .map(|(v, &n)| v._eq(&constant(n)))
+            .collect();
+        solver.assert(&Bool::and(&ctx, &same.iter().collect::<Vec<_>>()).not());
+    }
+}
+
+/// Replay the path given by `values`, the value of each of the `open` cells,
+/// from the current position of `board`. Returns `None` if it is not a legal
+/// completion.
+fn decode(board: &Board, open: &[(usize, usize)], values: &[usize]) -> Option<Board> {
+    let mut at = vec![0; values.len() + 1];
+    for (i, &v) in values.iter().enumerate() {
+        *at.get_mut(v)? = i;
+    }
+    let mut board = board.clone();
+    for &cell in &at[board.score() + 1..] {
+        let (x, y) = board.current_position();
+        let to = open[cell];
+        let dir = Direction::iterator().find(|&&dir| board.target(x, y, dir) == Some(to))?;
+        board = board.next_move(*dir).ok()?;
+    }
+    Some(board)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::count_solutions_with_config;
+    use super::*;
+
+    #[test]
+    // Z3 completes a board with a clue, and agrees with the native solver
+    // on the number of completions of a nearly full board.
+    fn smt_solve_and_count() {
+        let config = SolverConfig::default();
+        let board = Board::with_clues(5, &[(4, 4, 25)]).unwrap();
+        let start = board.start_at(0, 0).unwrap();
+        let won = solve_smt(&start, &config).unwrap();
+        assert!(won.is_won());
+        assert_eq!(won.value_at(4, 4), 25);
+
+        let mut board = start.clone();
+        let moves = crate::solver::solve_with_config(&start, &config).unwrap();
+        for &m in &moves[..18] {
+            board = board.next_move(m).unwrap();
+        }
+        assert_eq!(
+            count_smt(&board, &config, 100),
+            count_solutions_with_config(&board, &config)
+        );
+    }
+
+    #[test]
+    // In closed mode, Z3 finds only closed tours.
+    fn smt_closed() {
+        let config = SolverConfig {
+            closed: true,
+            ..SolverConfig::default()
+        };
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        assert!(solve_smt(&start, &config).unwrap().is_closed_tour());
+    }
+
+    #[test]
+    // Z3 leaves blocked cells out of the path.
+    fn smt_blocked() {
+        let config = SolverConfig::default();
+        let start = Board::new(5).block(2, 2).unwrap().start_at(0, 0).unwrap();
+        let won = solve_smt(&start, &config).unwrap();
+        assert!(won.is_won());
+        assert_eq!(won.score(), 24);
+    }
+}
@@ -0,0 +1,160 @@
+//! Lazy enumeration of every solution.
+
+use super::{replay, Outcome, Solver, SolverConfig, SolverLimits};
+use crate::board::{Board, Direction};
+use std::collections::HashSet;
+
+/// Solutions is an iterator over every sequence of moves that completes a
+/// board, starting from its current position. Each solution is found only
+/// when [`next`](Iterator::next) is called, so callers can stop after as many
+/// as they need. The `parallelism` option of its config is ignored.
+pub struct Solutions {
+    start: Board,
+    solver: Solver,
+    done: bool,
+    /// The canonical form of every completed board yielded so far, if only
+    /// one solution from each symmetry class is wanted.
+    seen: Option<HashSet<Board>>,
+}
+
+impl Solutions {
+    /// Return an iterator over the solutions of `board`, searched using the
+    /// options in `config`.
+    pub fn new(board: &Board, config: &SolverConfig) -> Self {
+        Solutions {
+            start: board.clone(),
+            solver: Solver::new(board, config),
+            done: false,
+            seen: None,
+        }
+    }
+
+    /// Yield only one solution from each set of solutions whose completed
+    /// boards are rotations or reflections of each other. The completed
+    /// board of every solution yielded is remembered, so this uses memory in
+    /// proportion to the number of distinct solutions.
+    pub fn distinct(mut self) -> Self {
+        self.seen = Some(HashSet::new());
+        self
+    }
+}
+
+impl Iterator for Solutions {
+    type Item = Vec<Direction>;
+
+    fn next(&mut self) -> Option<Vec<Direction>> {
+        if self.done {
+            return None;
+        }
+        loop {
+            let moves = match self.solver.run(&SolverLimits::default()) {
+                Outcome::Solved(moves) => moves,
+                _ => {
+                    self.done = true;
+                    return None;
+                }
+            };
+            match &mut self.seen {
+                Some(seen) => {
+                    if seen.insert(replay(&self.start, &moves).canonical()) {
+                        return Some(moves);
+                    }
+                }
+                None => return Some(moves),
+            }
+        }
+    }
+}
+
+/// Return an iterator over every solution of `board`, searched using the
+/// default options.
+pub fn solutions(board: &Board) -> Solutions {
+    Solutions::new(board, &SolverConfig::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{count_solutions, count_solutions_with_config, replay};
+    use super::*;
+
+    #[test]
+    // The iterator yields as many distinct winning paths as there are
+    // solutions.
+    fn solutions_5() {
+        let board = Board::new(5).start_at(0, 0).unwrap();
+        let all: Vec<Vec<Direction>> = solutions(&board).collect();
+        assert_eq!(all.len() as u64, count_solutions(&board));
+        assert!(all.iter().all(|moves| replay(&board, moves).is_won()));
+        for pair in all.windows(2) {
+            assert_ne!(pair[0], pair[1]);
+        }
+    }
+
+    #[test]
+    // A transposition table does not hide solutions reached by another path.
+    fn solutions_transposition() {
+        let board = Board::new(5).start_at(2, 2).unwrap();
+        let config = SolverConfig {
+            transposition: Some(1 << 12),
+            ..SolverConfig::default()
+        };
+        let count = Solutions::new(&board, &config).count() as u64;
+        assert_eq!(count, count_solutions(&board));
+    }
+
+    #[test]
+    // A transposition table that merges symmetric states does not merge
+    // states whose blocked cells are not images of each other.
+    fn solutions_symmetry_blocked() {
+        let config = SolverConfig {
+            transposition: Some(1 << 16),
+            symmetry: true,
+            ..SolverConfig::default()
+        };
+        for &((bx, by), (x, y)) in &[((1, 2), (0, 0)), ((0, 1), (2, 2))] {
+            let board = Board::new(5).block(bx, by).unwrap().start_at(x, y).unwrap();
+            let count = Solutions::new(&board, &config).count() as u64;
+            assert_eq!(count, count_solutions(&board));
+        }
+    }
+
+    #[test]
+    // A transposition table that merges symmetric states does not lose
+    // closed tours, whose end depends on where the path started.
+    fn solutions_symmetry_closed() {
+        let config = SolverConfig {
+            transposition: Some(1 << 16),
+            symmetry: true,
+            closed: true,
+            ..SolverConfig::default()
+        };
+        for &(x, y) in &[(0, 0), (4, 1)] {
+            let board = Board::new(5).start_at(x, y).unwrap();
+            let count = Solutions::new(&board, &config).count() as u64;
+            assert_eq!(count, count_solutions_with_config(&board, &config));
+            assert_eq!(count, 96);
+        }
+    }
+
+    #[test]
+    // Taking a few solutions stops the search early.
+    fn solutions_take() {
+        let board = Board::new(8).start_at(0, 0).unwrap();
+        let first: Vec<Vec<Direction>> = solutions(&board).take(3).collect();
+        assert_eq!(first.len(), 3);
+    }
+
+    #[test]
+    // From the center of a 5x5 board every solution has eight images, all of
+    // which also start from the center, so only one in eight is distinct.
+    fn solutions_distinct() {
+        let board = Board::new(5).start_at(2, 2).unwrap();
+        let all = solutions(&board).count();
+        let distinct: Vec<Vec<Direction>> = solutions(&board).distinct().collect();
+        assert_eq!(distinct.len() * 8, all);
+        let mut seen = HashSet::new();
+        for moves in &distinct {
+            assert!(seen.insert(replay(&board, moves).canonical()));
+        }
+    }
+}
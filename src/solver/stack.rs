@@ -0,0 +1,129 @@
+//! The explicit stack of the depth-first search.
+//!
+//! [`solve_with_config`](super::solve_with_config) and [`Solver`](super::Solver)
+//! both search depth first with a stack of frames kept on the heap rather
+//! than by recursing, so that the depth of the search is not limited by the
+//! size of the thread's stack. [`Stack`] holds those frames and the moves
+//! leading to the top one, spends a node of the budget on every move it
+//! makes, and remembers every board below which all of the moves have failed
+//! as dead, so that reaching it again costs one lookup.
+
+use super::limits::Budget;
+use super::transposition::{Key, TranspositionTable};
+use super::{children, SolverConfig};
+use crate::board::{Board, Direction};
+use std::sync::Mutex;
+
+/// One level of the stack: the moves from a board, and how many of them have
+/// been tried.
+pub(super) struct Frame {
+    pub(super) board: Board,
+    pub(super) children: Vec<(Direction, Board)>,
+    pub(super) next: usize,
+    /// Whether a solution has been found below this board, in which case it
+    /// is not dead once all of its moves have been tried.
+    pub(super) solved: bool,
+}
+
+/// Stack is the search stack of a depth-first search. The moves in `path`
+/// lead to the board of the top frame, from the board of the bottom frame
+/// once the moves that reached it are taken off.
+pub(super) struct Stack {
+    pub(super) frames: Vec<Frame>,
+    pub(super) path: Vec<Direction>,
+}
+
+impl Stack {
+    /// Return an empty stack, for a search from a board reached by `path`.
+    pub(super) fn new(path: Vec<Direction>) -> Self {
+        Stack {
+            frames: Vec::new(),
+            path,
+        }
+    }
+
+    /// Push a frame for `board`, the last board returned by
+    /// [`advance`](Stack::advance) or the board the search starts from.
+    pub(super) fn push(&mut self, board: Board, config: &SolverConfig) {
+        let children = children(&board, config);
+        self.frames.push(Frame {
+            board,
+            children,
+            next: 0,
+            solved: false,
+        });
+    }
+
+    /// Pop every frame all of whose moves have been tried, remembering its
+    /// board as dead in `table` unless a solution was found below it. Returns
+    /// the board of the frame left on top, or `None` if the stack is empty.
+    pub(super) fn top(
+        &mut self,
+        config: &SolverConfig,
+        table: Option<&Mutex<TranspositionTable>>,
+    ) -> Option<&Board> {
+        while self
+            .frames
+            .last()
+            .is_some_and(|frame| frame.next == frame.children.len())
+        {
+            let frame = self.frames.pop().unwrap();
+            if !self.frames.is_empty() {
+                self.path.pop();
+            }
+            if let (Some(table), false) = (table, frame.solved) {
+                table
+                    .lock()
+                    .unwrap()
+                    .insert(Key::with_config(&frame.board, config));
+            }
+        }
+        self.frames.last().map(|frame| &frame.board)
+    }
+
+    /// Make the next move from the board on top of the stack, which must
+    /// have one left, and return the board it reaches, or `None` without
+    /// making it if `budget` has run out. The move stays on the path until
+    /// it is taken back with [`back`](Stack::back), or until the frame pushed
+    /// for the board is popped.
+    pub(super) fn advance(&mut self, budget: &Budget) -> Option<Board> {
+        let depth = self.frames.len();
+        let frame = self.frames.last_mut().unwrap();
+        let (dir, board) = frame.children[frame.next].clone();
+        if !budget.spend_in_stack(&board, depth) {
+            return None;
+        }
+        frame.next += 1;
+        self.path.push(dir);
+        Some(board)
+    }
+
+    /// Take back the last move returned by [`advance`](Stack::advance), whose
+    /// board is not pushed.
+    pub(super) fn back(&mut self) {
+        if !self.frames.is_empty() {
+            self.path.pop();
+        }
+    }
+
+    /// Record that a solution has been found below every board on the stack.
+    pub(super) fn solved(&mut self) {
+        for frame in &mut self.frames {
+            frame.solved = true;
+        }
+    }
+}
+
+/// Return `true` if `board` is already known to be dead.
+pub(super) fn is_dead(
+    board: &Board,
+    config: &SolverConfig,
+    table: Option<&Mutex<TranspositionTable>>,
+) -> bool {
+    table.is_some_and(|table| {
+        table
+            .lock()
+            .unwrap()
+            .contains(&Key::with_config(board, config))
+    })
+}
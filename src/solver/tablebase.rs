@@ -0,0 +1,167 @@
+//! Endgame tablebase of the last few cells of a board.
+//!
+//! Near the end of a game, whether the board can be completed depends only
+//! on which cells are still empty and where the path stands. A [`Tablebase`]
+//! records every such state with at most `depth` empty cells that can be
+//! completed, so the solver can look these up instead of searching them.
+//!
+//! The table is built backwards from the finished boards. A state with one
+//! more empty cell can be completed exactly when its position is one jump
+//! before a state that can, and the cell that jump lands on is empty. Only
+//! the states that can be completed are ever stored, which keeps the table
+//! much smaller than the number of states it covers.
+
+use crate::bitset::BitSet;
+use crate::board::{Board, Direction, MoveRule};
+use std::collections::HashSet;
+use std::fmt;
+
+/// The empty cells of a board as a bit set, indexed by `y * size + x`.
+type Cells = BitSet;
+
+/// Tablebase records the completable endgames of boards of one size.
+pub struct Tablebase {
+    size: usize,
+    /// The completable states with `n` empty cells, as the position and the
+    /// set of empty cells, at index `n`.
+    levels: Vec<HashSet<(u16, Cells)>>,
+}
+
+impl Tablebase {
+    /// Build the tablebase of every completable state of a `size` x `size`
+    /// board with at most `depth` empty cells. The number of states grows
+    /// quickly with `depth`, more so on larger boards.
+    pub fn build(size: usize, depth: usize) -> Self {
+        let board = Board::new(size);
+        let cells = size * size;
+        // The cells from which one jump lands on each cell.
+        let mut before = vec![Vec::new(); cells];
+        for from in 0..cells {
+            for &dir in Direction::iterator() {
+                if let Some((x, y)) = board.target(from % size, from / size, dir) {
+                    before[y * size + x].push(from);
+                }
+            }
+        }
+        let none = Cells::new(cells);
+        let mut levels = vec![(0..cells)
+            .map(|p| (p as u16, none.clone()))
+            .collect::<HashSet<_>>()];
+        while levels.len() <= depth.min(cells - 1) {
+            let mut next = HashSet::new();
+            for (position, empty) in levels.last().unwrap() {
+                let to = *position as usize;
+                let mut empty_after = empty.clone();
+                empty_after.insert(to);
+                for &from in &before[to] {
+                    if !empty.contains(from) {
+                        next.insert((from as u16, empty_after.clone()));
+                    }
+                }
+            }
+            levels.push(next);
+        }
+        Tablebase { size, levels }
+    }
+
+    /// Return the size of the boards this table covers.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Return the most empty cells a board can have and be covered by this
+    /// table.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// Return the number of completable states in the table.
+    pub fn len(&self) -> usize {
+        self.levels.iter().map(|level| level.len()).sum()
+    }
+
+    /// Return `true` if the table holds no states.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Return whether `board` can be completed, or `None` if the table does
+    /// not cover it: it is not square, is a different size, has blocked
+    /// cells, another move rule or wraps around, has not been started, or
+    /// has too many empty cells. Clues are
+    /// not taken into account, so a board with clues may not be completable
+    /// even if this returns `Some(true)`.
+    pub fn is_completable(&self, board: &Board) -> Option<bool> {
+        let size = board.size();
+        let remaining = board.remaining();
+        if !board.is_square()
+            || size != self.size
+            || !board.blocked().is_empty()
+            || board.rule() != MoveRule::default()
+            || board.wraps()
+            || remaining > self.depth()
+            || !board.is_started()
+        {
+            return None;
+        }
+        let mut empty = Cells::new(size * size);
+        for (x, y) in board.empty_cells() {
+            empty.insert(y * size + x);
+        }
+        let (x, y) = board.current_position();
+        let position = (y * size + x) as u16;
+        Some(self.levels[remaining].contains(&(position, empty)))
+    }
+
+    /// Return `false` if the table shows that `board` cannot be completed.
+    pub(super) fn allows(&self, board: &Board) -> bool {
+        self.is_completable(board) != Some(false)
+    }
+}
+
+impl fmt::Debug for Tablebase {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Tablebase")
+            .field("size", &self.size)
+            .field("depth", &self.depth())
+            .field("states", &self.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{count_solutions_with_config, solve_with_config, SolverConfig};
+    use super::*;
+    use std::sync::Arc;
+
+    #[test]
+    // The table agrees with an exhaustive search on every board one move
+    // off the path of a 5x5 solution that it covers, and the solver finds
+    // every solution when it consults the table.
+    fn tablebase_agrees_with_search() {
+        let table = Arc::new(Tablebase::build(5, 8));
+        let unpruned = SolverConfig {
+            prunes: vec![],
+            ..SolverConfig::default()
+        };
+        let start = Board::new(5).start_at(0, 0).unwrap();
+        let moves = solve_with_config(&start, &unpruned).unwrap();
+        let mut board = start.clone();
+        for &m in &moves {
+            board = board.next_move(m).unwrap();
+            for next in board.clone().possible_moves() {
+                let after = board.next_move(*next).unwrap();
+                if let Some(completable) = table.is_completable(&after) {
+                    let count = count_solutions_with_config(&after, &unpruned);
+                    assert_eq!(completable, count > 0);
+                }
+            }
+        }
+        let config = SolverConfig {
+            tablebase: Some(table),
+            ..SolverConfig::default()
+        };
+        assert_eq!(count_solutions_with_config(&start, &config), 552);
+    }
+}
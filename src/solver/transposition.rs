@@ -0,0 +1,213 @@
+//! Transposition table of board states the solver has proven dead.
+//!
+//! Different move orders can fill the same set of cells and finish on the
+//! same cell, leaving identical games to play out. Once the search has shown
+//! that such a state cannot be completed, recording it here stops the solver
+//! from searching it again when another move order reaches it.
+
+use super::SolverConfig;
+use crate::bitset::BitSet;
+use crate::board::Board;
+use crate::symmetry::Symmetry;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+/// Key identifies a board state by the cells that are filled, the current
+/// position, and the value at the current position.
+pub(super) struct Key {
+    /// The filled cells as a bit set, indexed by `y * width + x`.
+    occupied: BitSet,
+    position: u16,
+    value: u16,
+}
+
+impl Key {
+    /// Return the key for `board`.
+    pub(super) fn of(board: &Board) -> Key {
+        Key::transformed(board, Symmetry::Identity)
+    }
+
+    /// Return the key for `board` under the options in `config`: the
+    /// canonical key if symmetric states are to be merged, otherwise its own
+    /// key. A closed tour must end next to its start, which the key does not
+    /// record, so states that are images of each other are only equivalent
+    /// when the tour need not be closed.
+    pub(super) fn with_config(board: &Board, config: &SolverConfig) -> Key {
+        if config.symmetry && !config.closed {
+            Key::canonical(board)
+        } else {
+            Key::of(board)
+        }
+    }
+
+    /// Return the smallest key of the rotations and reflections of `board`
+    /// given by `Board::symmetries()`, so that symmetric states share a key.
+    /// Only the symmetries that map the blocked cells onto themselves are
+    /// used, as the image of a state under any other is a different game. A
+    /// board with clues is not symmetric in general, so its key is returned
+    /// unchanged.
+    pub(super) fn canonical(board: &Board) -> Key {
+        if board.has_clues() {
+            return Key::of(board);
+        }
+        let (width, height) = (board.width(), board.height());
+        let blocked = board.blocked();
+        board
+            .symmetries()
+            .into_iter()
+            .filter(|s| {
+                blocked.iter().all(|&p| {
+                    let (x, y) = s.apply_rect(width, height, p);
+                    board.blocked_at(x, y)
+                })
+            })
+            .map(|s| Key::transformed(board, s))
+            .min()
+            .unwrap_or_else(|| Key::of(board))
+    }
+
+    /// Return the key for the image of `board` under `symmetry`, which must
+    /// map the board onto a board of the same dimensions.
+    fn transformed(board: &Board, symmetry: Symmetry) -> Key {
+        let (width, height) = (board.width(), board.height());
+        let index = |p: (usize, usize)| {
+            let (x, y) = symmetry.apply_rect(width, height, p);
+            y * width + x
+        };
+        let occupied = if symmetry == Symmetry::Identity {
+            board.occupied().clone()
+        } else {
+            let mut occupied = BitSet::new(width * height);
+            for p in board.path() {
+                occupied.insert(index(p));
+            }
+            occupied
+        };
+        let (x, y) = board.current_position();
+        Key {
+            occupied,
+            position: index((x, y)) as u16,
+            value: board.value_at(x, y),
+        }
+    }
+
+    /// The number of filled cells. States with fewer filled cells stand for
+    /// larger subtrees, so are more valuable to keep.
+    fn filled(&self) -> usize {
+        self.occupied.len()
+    }
+}
+
+/// TranspositionTable is a fixed-size cache of dead board states. Each bucket
+/// holds two entries: the first keeps the state with the largest subtree seen
+/// in that bucket, and the second is always replaced by the newest state.
+pub(super) struct TranspositionTable {
+    buckets: Vec<[Option<Key>; 2]>,
+}
+
+impl TranspositionTable {
+    /// Create a table holding at most `capacity` states.
+    pub(super) fn new(capacity: usize) -> Self {
+        TranspositionTable {
+            buckets: vec![[None, None]; (capacity / 2).max(1)],
+        }
+    }
+
+    fn bucket(&self, key: &Key) -> usize {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        (hasher.finish() % self.buckets.len() as u64) as usize
+    }
+
+    /// Return `true` if `key` has been recorded as dead.
+    pub(super) fn contains(&self, key: &Key) -> bool {
+        self.buckets[self.bucket(key)]
+            .iter()
+            .any(|entry| entry.as_ref() == Some(key))
+    }
+
+    /// Record `key` as dead, evicting an older entry if its bucket is full.
+    pub(super) fn insert(&mut self, key: Key) {
+        let i = self.bucket(&key);
+        let bucket = &mut self.buckets[i];
+        match &bucket[0] {
+            Some(old) if old.filled() < key.filled() => bucket[1] = Some(key),
+            _ => bucket[1] = bucket[0].replace(key),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Direction;
+
+    #[test]
+    // Boards reached by different move orders share a key when the same
+    // cells are filled and they finish on the same cell.
+    fn transposed_keys_match() {
+        use Direction::*;
+        let start = Board::new(6).start_at(0, 0).unwrap();
+        let play = |moves: &[Direction]| {
+            let mut board = start.clone();
+            for &m in moves {
+                board = board.next_move(m).unwrap();
+            }
+            board
+        };
+        let a = play(&[Down, DownRight, Right, UpLeft, Up, DownRight]);
+        let b = play(&[Right, Down, Left, DownRight, Right, Up]);
+        assert_eq!(Key::of(&a), Key::of(&b));
+        let c = play(&[Right, Down, Left, DownRight, Right]);
+        assert_ne!(Key::of(&a), Key::of(&c));
+    }
+
+    #[test]
+    // A board and its mirror image share a canonical key.
+    fn canonical_keys_match() {
+        use Direction::*;
+        let a = Board::new(6)
+            .start_at(0, 0)
+            .unwrap()
+            .next_move(Right)
+            .unwrap()
+            .next_move(DownLeft)
+            .unwrap();
+        let b = Board::new(6)
+            .start_at(0, 0)
+            .unwrap()
+            .next_move(Down)
+            .unwrap()
+            .next_move(UpRight)
+            .unwrap();
+        assert_ne!(Key::of(&a), Key::of(&b));
+        assert_eq!(Key::canonical(&a), Key::canonical(&b));
+    }
+
+    #[test]
+    // A table with a single bucket keeps the state with the largest subtree
+    // and the newest state.
+    fn replacement_policy() {
+        let start = Board::new(6).start_at(0, 0).unwrap();
+        let one = Key::of(&start);
+        let two = Key::of(&start.next_move(Direction::Right).unwrap());
+        let three = Key::of(
+            &start
+                .clone()
+                .next_move(Direction::Down)
+                .unwrap()
+                .next_move(Direction::Right)
+                .unwrap(),
+        );
+        let mut table = TranspositionTable::new(2);
+        table.insert(two.clone());
+        table.insert(three.clone());
+        assert!(table.contains(&two) && table.contains(&three));
+        table.insert(one.clone());
+        assert!(table.contains(&one) && table.contains(&two));
+        assert!(!table.contains(&three));
+        table.insert(three.clone());
+        assert!(table.contains(&one) && table.contains(&three));
+    }
+}
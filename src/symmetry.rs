@@ -0,0 +1,195 @@
+//! The symmetries of a square board.
+//!
+//! The jump moves look the same after rotating or reflecting the board, so
+//! each of the eight symmetries of the square maps a game onto another legal
+//! game. Solvers use this to avoid repeating work on equivalent positions.
+//! This holds for every [`MoveRule`], though under some rules a symmetry
+//! maps a move onto a move in a direction other than the image of its own;
+//! see [`Symmetry::apply_move`].
+
+use crate::board::{Direction, MoveRule};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+/// Symmetry is one of the eight rotations and reflections of a square board.
+/// Rotations are clockwise, with y increasing downwards.
+pub enum Symmetry {
+    Identity,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+    /// Reflect left to right.
+    FlipH,
+    /// Reflect top to bottom.
+    FlipV,
+    /// Reflect across the main diagonal, swapping x and y.
+    Transpose,
+    /// Reflect across the anti-diagonal.
+    AntiTranspose,
+}
+
+impl Symmetry {
+    /// Return all eight symmetries, starting with the identity.
+    pub fn all() -> [Symmetry; 8] {
+        [
+            Symmetry::Identity,
+            Symmetry::Rotate90,
+            Symmetry::Rotate180,
+            Symmetry::Rotate270,
+            Symmetry::FlipH,
+            Symmetry::FlipV,
+            Symmetry::Transpose,
+            Symmetry::AntiTranspose,
+        ]
+    }
+
+    /// Return the symmetry that undoes this one.
+    pub fn inverse(self) -> Symmetry {
+        match self {
+            Symmetry::Rotate90 => Symmetry::Rotate270,
+            Symmetry::Rotate270 => Symmetry::Rotate90,
+            other => other,
+        }
+    }
+
+    /// Return `true` if the symmetry swaps the rows and columns of a board,
+    /// so that it maps a `width` x `height` board onto a `height` x `width`
+    /// one. Only the other four map a rectangular board onto itself.
+    pub fn swaps_axes(self) -> bool {
+        matches!(
+            self,
+            Symmetry::Rotate90
+                | Symmetry::Rotate270
+                | Symmetry::Transpose
+                | Symmetry::AntiTranspose
+        )
+    }
+
+    /// Map the cell at (x, y) on a board of dimensions `size` x `size`.
+    pub fn apply(self, size: usize, (x, y): (usize, usize)) -> (usize, usize) {
+        self.apply_rect(size, size, (x, y))
+    }
+
+    /// Map the cell at (x, y) on a board of dimensions `width` x `height`.
+    /// If the symmetry swaps the axes, the image is on a `height` x `width`
+    /// board.
+    pub fn apply_rect(self, width: usize, height: usize, (x, y): (usize, usize)) -> (usize, usize) {
+        let (mx, my) = (width - 1, height - 1);
+        match self {
+            Symmetry::Identity => (x, y),
+            Symmetry::Rotate90 => (my - y, x),
+            Symmetry::Rotate180 => (mx - x, my - y),
+            Symmetry::Rotate270 => (y, mx - x),
+            Symmetry::FlipH => (mx - x, y),
+            Symmetry::FlipV => (x, my - y),
+            Symmetry::Transpose => (y, x),
+            Symmetry::AntiTranspose => (my - y, mx - x),
+        }
+    }
+
+    /// Map a move direction, so that a move from (x, y) in direction `dir`
+    /// becomes a move from `apply(size, (x, y))` in `apply_direction(dir)`.
+    /// This holds for the `MoveRule::Jump` rules; use `apply_move()` for
+    /// any rule.
+    pub fn apply_direction(self, dir: Direction) -> Direction {
+        let image = self.apply_offset(unit(dir));
+        *Direction::iterator()
+            .find(|&&d| unit(d) == image)
+            .unwrap_or(&dir)
+    }
+
+    /// Map the direction of a move under `rule`, so that a move from (x, y)
+    /// in direction `dir` becomes a move from `apply(size, (x, y))` in
+    /// `apply_move(rule, dir)`.
+    pub fn apply_move(self, rule: MoveRule, dir: Direction) -> Direction {
+        match rule.offset(dir) {
+            Some(offset) => {
+                let image = self.apply_offset(offset);
+                *Direction::iterator()
+                    .find(|&&d| rule.offset(d) == Some(image))
+                    .unwrap_or(&dir)
+            }
+            None => self.apply_direction(dir),
+        }
+    }
+
+    /// Map the change (dx, dy) in location made by a move.
+    fn apply_offset(self, (dx, dy): (i32, i32)) -> (i32, i32) {
+        match self {
+            Symmetry::Identity => (dx, dy),
+            Symmetry::Rotate90 => (-dy, dx),
+            Symmetry::Rotate180 => (-dx, -dy),
+            Symmetry::Rotate270 => (dy, -dx),
+            Symmetry::FlipH => (-dx, dy),
+            Symmetry::FlipV => (dx, -dy),
+            Symmetry::Transpose => (dy, dx),
+            Symmetry::AntiTranspose => (-dy, -dx),
+        }
+    }
+
+    /// Return the image of (x, y) that comes first in row-major order, and
+    /// the symmetry that maps (x, y) onto it.
+    pub fn canonical(size: usize, (x, y): (usize, usize)) -> ((usize, usize), Symmetry) {
+        Symmetry::all()
+            .iter()
+            .map(|&s| (s.apply(size, (x, y)), s))
+            .min_by_key(|&((x, y), _)| (y, x))
+            .unwrap_or(((x, y), Symmetry::Identity))
+    }
+}
+
+/// The unit step (dx, dy) in the direction of `dir`.
+fn unit(dir: Direction) -> (i32, i32) {
+    match dir {
+        Direction::Down => (0, 1),
+        Direction::DownRight => (1, 1),
+        Direction::Right => (1, 0),
+        Direction::UpRight => (1, -1),
+        Direction::Up => (0, -1),
+        Direction::UpLeft => (-1, -1),
+        Direction::Left => (-1, 0),
+        Direction::DownLeft => (-1, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Board;
+
+    #[test]
+    // Every symmetry maps each jump onto a jump, under the default rule and
+    // the knight's, and is undone by its inverse.
+    fn jumps_are_symmetric() {
+        let board = Board::new(7);
+        let knight = board.with_rule(MoveRule::Knight).unwrap();
+        for &s in Symmetry::all().iter() {
+            for y in 0..7 {
+                for x in 0..7 {
+                    let p = s.apply(7, (x, y));
+                    assert_eq!(s.inverse().apply(7, p), (x, y));
+                    for &dir in Direction::iterator() {
+                        let to = board.target(x, y, dir).map(|t| s.apply(7, t));
+                        assert_eq!(board.target(p.0, p.1, s.apply_direction(dir)), to);
+                        let to = knight.target(x, y, dir).map(|t| s.apply(7, t));
+                        let image = s.apply_move(MoveRule::Knight, dir);
+                        assert_eq!(knight.target(p.0, p.1, image), to);
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    // A 10x10 board has 15 distinct starting squares.
+    fn canonical_starts() {
+        let mut starts: Vec<(usize, usize)> = (0..100)
+            .map(|i| Symmetry::canonical(10, (i % 10, i / 10)).0)
+            .collect();
+        starts.sort();
+        starts.dedup();
+        assert_eq!(starts.len(), 15);
+        let (c, s) = Symmetry::canonical(10, (9, 7));
+        assert_eq!(c, (2, 0));
+        assert_eq!(s.apply(10, (9, 7)), c);
+    }
+}